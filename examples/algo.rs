@@ -38,6 +38,11 @@ fn main() {
     insertion::sort_mut(&mut c);
     bubble::sort_mut(&mut d);
 
+    assert_eq!(sorted_bubble, a);
+    assert_eq!(sorted_insertion, b);
+    assert_eq!(sorted_merge, c);
+    assert_eq!(sorted_quick, d);
+
     // get a new longest common sequence object
     let sequence = LCSubsequence::new_subsequence("leighxxxft".to_string(), "right".to_string());
     assert_eq!(sequence.subsequence_len, 4);
@@ -0,0 +1,213 @@
+use super::Scoring;
+
+// the residue order of the built in matrices, as NCBI prints them
+const AMINO_ACIDS: &str = "ARNDCQEGHILKMFPSTWYVBZX*";
+
+#[rustfmt::skip]
+const BLOSUM62: [[i8; 24]; 24] = [
+    //A   R   N   D   C   Q   E   G   H   I   L   K   M   F   P   S   T   W   Y   V   B   Z   X   *
+    [ 4, -1, -2, -2,  0, -1, -1,  0, -2, -1, -1, -1, -1, -2, -1,  1,  0, -3, -2,  0, -2, -1,  0, -4], // A
+    [-1,  5,  0, -2, -3,  1,  0, -2,  0, -3, -2,  2, -1, -3, -2, -1, -1, -3, -2, -3, -1,  0, -1, -4], // R
+    [-2,  0,  6,  1, -3,  0,  0,  0,  1, -3, -3,  0, -2, -3, -2,  1,  0, -4, -2, -3,  3,  0, -1, -4], // N
+    [-2, -2,  1,  6, -3,  0,  2, -1, -1, -3, -4, -1, -3, -3, -1,  0, -1, -4, -3, -3,  4,  1, -1, -4], // D
+    [ 0, -3, -3, -3,  9, -3, -4, -3, -3, -1, -1, -3, -1, -2, -3, -1, -1, -2, -2, -1, -3, -3, -2, -4], // C
+    [-1,  1,  0,  0, -3,  5,  2, -2,  0, -3, -2,  1,  0, -3, -1,  0, -1, -2, -1, -2,  0,  3, -1, -4], // Q
+    [-1,  0,  0,  2, -4,  2,  5, -2,  0, -3, -3,  1, -2, -3, -1,  0, -1, -3, -2, -2,  1,  4, -1, -4], // E
+    [ 0, -2,  0, -1, -3, -2, -2,  6, -2, -4, -4, -2, -3, -3, -2,  0, -2, -2, -3, -3, -1, -2, -1, -4], // G
+    [-2,  0,  1, -1, -3,  0,  0, -2,  8, -3, -3, -1, -2, -1, -2, -1, -2, -2,  2, -3,  0,  0, -1, -4], // H
+    [-1, -3, -3, -3, -1, -3, -3, -4, -3,  4,  2, -3,  1,  0, -3, -2, -1, -3, -1,  3, -3, -3, -1, -4], // I
+    [-1, -2, -3, -4, -1, -2, -3, -4, -3,  2,  4, -2,  2,  0, -3, -2, -1, -2, -1,  1, -4, -3, -1, -4], // L
+    [-1,  2,  0, -1, -3,  1,  1, -2, -1, -3, -2,  5, -1, -3, -1,  0, -1, -3, -2, -2,  0,  1, -1, -4], // K
+    [-1, -1, -2, -3, -1,  0, -2, -3, -2,  1,  2, -1,  5,  0, -2, -1, -1, -1, -1,  1, -3, -1, -1, -4], // M
+    [-2, -3, -3, -3, -2, -3, -3, -3, -1,  0,  0, -3,  0,  6, -4, -2, -2,  1,  3, -1, -3, -3, -1, -4], // F
+    [-1, -2, -2, -1, -3, -1, -1, -2, -2, -3, -3, -1, -2, -4,  7, -1, -1, -4, -3, -2, -2, -1, -2, -4], // P
+    [ 1, -1,  1,  0, -1,  0,  0,  0, -1, -2, -2,  0, -1, -2, -1,  4,  1, -3, -2, -2,  0,  0,  0, -4], // S
+    [ 0, -1,  0, -1, -1, -1, -1, -2, -2, -1, -1, -1, -1, -2, -1,  1,  5, -2, -2,  0, -1, -1,  0, -4], // T
+    [-3, -3, -4, -4, -2, -2, -3, -2, -2, -3, -2, -3, -1,  1, -4, -3, -2, 11,  2, -3, -4, -3, -2, -4], // W
+    [-2, -2, -2, -3, -2, -1, -2, -3,  2, -1, -1, -2, -1,  3, -3, -2, -2,  2,  7, -1, -3, -2, -1, -4], // Y
+    [ 0, -3, -3, -3, -1, -2, -2, -3, -3,  3,  1, -2,  1, -1, -2, -2,  0, -3, -1,  4, -3, -2, -1, -4], // V
+    [-2, -1,  3,  4, -3,  0,  1, -1,  0, -3, -4,  0, -3, -3, -2,  0, -1, -4, -3, -3,  4,  1, -1, -4], // B
+    [-1,  0,  0,  1, -3,  3,  4, -2,  0, -3, -3,  1, -1, -3, -1,  0, -1, -3, -2, -2,  1,  4, -1, -4], // Z
+    [ 0, -1, -1, -1, -2, -1, -1, -1, -1, -1, -1, -1, -1, -1, -2,  0,  0, -2, -1, -1, -1, -1, -1, -4], // X
+    [-4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4,  1], // *
+];
+
+#[rustfmt::skip]
+const PAM250: [[i8; 24]; 24] = [
+    //A   R   N   D   C   Q   E   G   H   I   L   K   M   F   P   S   T   W   Y   V   B   Z   X   *
+    [ 2, -2,  0,  0, -2,  0,  0,  1, -1, -1, -2, -1, -1, -3,  1,  1,  1, -6, -3,  0,  0,  0,  0, -8], // A
+    [-2,  6,  0, -1, -4,  1, -1, -3,  2, -2, -3,  3,  0, -4,  0,  0, -1,  2, -4, -2, -1,  0, -1, -8], // R
+    [ 0,  0,  2,  2, -4,  1,  1,  0,  2, -2, -3,  1, -2, -3,  0,  1,  0, -4, -2, -2,  2,  1,  0, -8], // N
+    [ 0, -1,  2,  4, -5,  2,  3,  1,  1, -2, -4,  0, -3, -6, -1,  0,  0, -7, -4, -2,  3,  3, -1, -8], // D
+    [-2, -4, -4, -5, 12, -5, -5, -3, -3, -2, -6, -5, -5, -4, -3,  0, -2, -8,  0, -2, -4, -5, -3, -8], // C
+    [ 0,  1,  1,  2, -5,  4,  2, -1,  3, -2, -2,  1, -1, -5,  0, -1, -1, -5, -4, -2,  1,  3, -1, -8], // Q
+    [ 0, -1,  1,  3, -5,  2,  4,  0,  1, -2, -3,  0, -2, -5, -1,  0,  0, -7, -4, -2,  3,  3, -1, -8], // E
+    [ 1, -3,  0,  1, -3, -1,  0,  5, -2, -3, -4, -2, -3, -5,  0,  1,  0, -7, -5, -1,  0,  0, -1, -8], // G
+    [-1,  2,  2,  1, -3,  3,  1, -2,  6, -2, -2,  0, -2, -2,  0, -1, -1, -3,  0, -2,  1,  2, -1, -8], // H
+    [-1, -2, -2, -2, -2, -2, -2, -3, -2,  5,  2, -2,  2,  1, -2, -1,  0, -5, -1,  4, -2, -2, -1, -8], // I
+    [-2, -3, -3, -4, -6, -2, -3, -4, -2,  2,  6, -3,  4,  2, -3, -3, -2, -2, -1,  2, -3, -3, -1, -8], // L
+    [-1,  3,  1,  0, -5,  1,  0, -2,  0, -2, -3,  5,  0, -5, -1,  0,  0, -3, -4, -2,  1,  0, -1, -8], // K
+    [-1,  0, -2, -3, -5, -1, -2, -3, -2,  2,  4,  0,  6,  0, -2, -2, -1, -4, -2,  2, -2, -2, -1, -8], // M
+    [-3, -4, -3, -6, -4, -5, -5, -5, -2,  1,  2, -5,  0,  9, -5, -3, -3,  0,  7, -1, -4, -5, -2, -8], // F
+    [ 1,  0,  0, -1, -3,  0, -1,  0,  0, -2, -3, -1, -2, -5,  6,  1,  0, -6, -5, -1, -1,  0, -1, -8], // P
+    [ 1,  0,  1,  0,  0, -1,  0,  1, -1, -1, -3,  0, -2, -3,  1,  2,  1, -2, -3, -1,  0,  0,  0, -8], // S
+    [ 1, -1,  0,  0, -2, -1,  0,  0, -1,  0, -2,  0, -1, -3,  0,  1,  3, -5, -3,  0,  0, -1,  0, -8], // T
+    [-6,  2, -4, -7, -8, -5, -7, -7, -3, -5, -2, -3, -4,  0, -6, -2, -5, 17,  0, -6, -5, -6, -4, -8], // W
+    [-3, -4, -2, -4,  0, -4, -4, -5,  0, -1, -1, -4, -2,  7, -5, -3, -3,  0, 10, -2, -3, -4, -2, -8], // Y
+    [ 0, -2, -2, -2, -2, -2, -2, -1, -2,  4,  2, -2,  2, -1, -1, -1,  0, -6, -2,  4, -2, -2, -1, -8], // V
+    [ 0, -1,  2,  3, -4,  1,  3,  0,  1, -2, -3,  1, -2, -4, -1,  0,  0, -5, -3, -2,  3,  2, -1, -8], // B
+    [ 0,  0,  1,  3, -5,  3,  3,  0,  2, -2, -3,  0, -2, -5,  0,  0, -1, -6, -4, -2,  2,  3, -1, -8], // Z
+    [ 0, -1,  0, -1, -3, -1, -1, -1, -1, -1, -1, -1, -1, -2, -1,  0,  0, -4, -2, -1, -1, -1, -1, -8], // X
+    [-8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8, -8,  1], // *
+];
+
+/// A substitution matrix scoring every pair of residues, such as BLOSUM62 for amino acids. Residues are looked up
+/// by their ASCII letter, ignoring case. A residue the matrix has no row for is scored as "X" when the matrix has one,
+/// and with the lowest score in the matrix otherwise.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::align::{Scoring, SubstitutionMatrix};
+///
+/// let blosum62 = SubstitutionMatrix::blosum62();
+/// assert_eq!(blosum62.score(&b'W', &b'W'), 11);
+/// assert_eq!(blosum62.score(&'w', &'f'), 1);
+///
+/// let dna = SubstitutionMatrix::new("ACGT", vec![
+///     vec![ 2, -1,  1, -1],
+///     vec![-1,  2, -1,  1],
+///     vec![ 1, -1,  2, -1],
+///     vec![-1,  1, -1,  2],
+/// ]).unwrap();
+/// assert_eq!(dna.score(&b'a', &b'G'), 1);
+/// assert_eq!(dna.score(&b'N', &b'A'), -1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubstitutionMatrix {
+    // the row of each ASCII byte, or None if it is not in the alphabet
+    rows: [Option<u8>; 128],
+    scores: Vec<Vec<i32>>,
+    unknown: Option<u8>,
+    lowest: i32,
+}
+
+impl SubstitutionMatrix {
+    /// This will return a new matrix over the residues of the alphabet, with scores[i][j] the score of aligning the
+    /// ith residue against the jth. The alphabet needs to be made of distinct ASCII characters, at most 128 of them,
+    /// and the scores need to be a square with a row for each one.
+    pub fn new(alphabet: &str, scores: Vec<Vec<i32>>) -> Result<Self, String> {
+        if !alphabet.is_ascii() {
+            return Err("The alphabet needs to be ASCII".to_string());
+        }
+        if scores.len() != alphabet.len() || scores.iter().any(|row| row.len() != alphabet.len()) {
+            return Err(format!(
+                "The scores need to be {0} by {0}, one row and column for each residue",
+                alphabet.len()
+            ));
+        }
+
+        let mut rows = [None; 128];
+        for (row, residue) in alphabet.bytes().enumerate() {
+            for key in [residue.to_ascii_uppercase(), residue.to_ascii_lowercase()] {
+                if rows[key as usize].is_some_and(|other| other as usize != row) {
+                    return Err(format!("{} is in the alphabet twice", residue as char));
+                }
+                rows[key as usize] = Some(row as u8);
+            }
+        }
+
+        Ok(SubstitutionMatrix {
+            unknown: rows[b'X' as usize],
+            lowest: scores.iter().flatten().copied().min().unwrap_or(0),
+            rows,
+            scores,
+        })
+    }
+
+    /// This will return the BLOSUM62 matrix, the usual choice for protein alignment, over the 20 amino acids along
+    /// with B, Z, X and the stop "*".
+    pub fn blosum62() -> Self {
+        SubstitutionMatrix::built_in(&BLOSUM62)
+    }
+
+    /// This will return the PAM250 matrix, suited to distantly related proteins, over the 20 amino acids along with B,
+    /// Z, X and the stop "*".
+    pub fn pam250() -> Self {
+        SubstitutionMatrix::built_in(&PAM250)
+    }
+
+    fn built_in(scores: &[[i8; 24]; 24]) -> Self {
+        let scores = scores
+            .iter()
+            .map(|row| row.iter().map(|score| *score as i32).collect())
+            .collect();
+        SubstitutionMatrix::new(AMINO_ACIDS, scores).unwrap()
+    }
+
+    fn row(&self, residue: u32) -> Option<usize> {
+        self.rows
+            .get(residue as usize)
+            .copied()
+            .flatten()
+            .or(self.unknown)
+            .map(|row| row as usize)
+    }
+
+    fn lookup(&self, a: u32, b: u32) -> i32 {
+        match (self.row(a), self.row(b)) {
+            (Some(a), Some(b)) => self.scores[a][b],
+            _ => self.lowest,
+        }
+    }
+}
+
+impl Scoring<u8> for SubstitutionMatrix {
+    fn score(&self, a: &u8, b: &u8) -> i32 {
+        self.lookup(*a as u32, *b as u32)
+    }
+}
+
+impl Scoring<char> for SubstitutionMatrix {
+    fn score(&self, a: &char, b: &char) -> i32 {
+        self.lookup(*a as u32, *b as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn built_in_matrices() {
+        use super::*;
+
+        for matrix in [&BLOSUM62, &PAM250] {
+            for (i, row) in matrix.iter().enumerate() {
+                for (j, score) in row.iter().enumerate() {
+                    assert_eq!(*score, matrix[j][i]);
+                }
+                // nothing scores better against a residue than the residue itself, leaving out X and the stop
+                if i < 22 {
+                    assert!(row[..23].iter().all(|score| *score <= row[i]));
+                }
+            }
+        }
+
+        let blosum62 = SubstitutionMatrix::blosum62();
+        assert_eq!(blosum62.score(&b'C', &b'C'), 9);
+        assert_eq!(blosum62.score(&b'I', &b'V'), 3);
+        assert_eq!(blosum62.score(&b'*', &b'*'), 1);
+        assert_eq!(blosum62.score(&b'U', &b'A'), 0);
+        assert_eq!(blosum62.score(&'é', &'A'), 0);
+
+        let pam250 = SubstitutionMatrix::pam250();
+        assert_eq!(pam250.score(&b'W', &b'W'), 17);
+        assert_eq!(pam250.score(&b'C', &b'W'), -8);
+        assert_eq!(pam250.score(&'y', &'f'), 7);
+
+        assert!(SubstitutionMatrix::new("AB", vec![vec![1, 0]]).is_err());
+        assert!(SubstitutionMatrix::new("Aa", vec![vec![1, 0], vec![0, 1]]).is_err());
+        assert!(SubstitutionMatrix::new("Aé", vec![vec![1, 0], vec![0, 1]]).is_err());
+
+        let binary = SubstitutionMatrix::new("01", vec![vec![1, -3], vec![-3, 1]]).unwrap();
+        assert_eq!(binary.score(&b'2', &b'0'), -3);
+    }
+}
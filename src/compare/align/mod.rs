@@ -0,0 +1,642 @@
+//! Pairwise sequence alignment with affine gap penalties. `Aligner` finds the best scoring global (Needleman-Wunsch),
+//! local (Smith-Waterman) or semi-global alignment of two sequences, with Gotoh's three grids so a gap costs more to
+//! open than to extend. Pairs of values are scored by any `Scoring`, such as the BLOSUM62 and PAM250 substitution
+//! matrices built into `SubstitutionMatrix`, a `MatchMismatch` score for nucleotides, or a closure.
+//!
+//! Every cell of the grids keeps a pointer to the grid it was reached from, and the alignment is read back along
+//! them, in O(nm) time and memory.
+//!
+//! # Examples
+//! ```
+//! use rs_algo::compare::align::{Aligner, SubstitutionMatrix};
+//!
+//! let aligner = Aligner::new(SubstitutionMatrix::blosum62(), 11, 1);
+//! let alignment = aligner.local(b"PAWHEAE", b"HEAGAWGHEE");
+//!
+//! assert_eq!(alignment.score, 17);
+//! assert_eq!(alignment.a_range, 1..5);
+//! assert_eq!(alignment.b_range, 4..9);
+//! print!("{}", alignment.pretty(b"PAWHEAE", b"HEAGAWGHEE"));
+//! ```
+
+pub use self::matrix::SubstitutionMatrix;
+
+use std::ops::Range;
+
+use super::distance::Edit;
+
+mod matrix;
+
+// low enough that nothing reaches it, high enough that taking gap penalties off can not overflow
+const NONE: i32 = i32::MIN / 4;
+
+// how many columns of an alignment are printed on a line
+const LINE: usize = 60;
+
+/// A score for aligning one value against another, higher for better matches
+pub trait Scoring<T> {
+    /// Return the score of aligning a against b
+    fn score(&self, a: &T, b: &T) -> i32;
+}
+
+impl<T, F> Scoring<T> for F
+where
+    F: Fn(&T, &T) -> i32,
+{
+    fn score(&self, a: &T, b: &T) -> i32 {
+        self(a, b)
+    }
+}
+
+/// One score for equal values and another for different ones, as is usual for nucleotides
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::align::{MatchMismatch, Scoring};
+///
+/// let scoring = MatchMismatch { matched: 5, mismatched: -4 };
+/// assert_eq!(scoring.score(&b'A', &b'A'), 5);
+/// assert_eq!(scoring.score(&b'A', &b'T'), -4);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MatchMismatch {
+    /// the score of equal values
+    pub matched: i32,
+    /// the score of different values
+    pub mismatched: i32,
+}
+
+impl<T> Scoring<T> for MatchMismatch
+where
+    T: PartialEq,
+{
+    fn score(&self, a: &T, b: &T) -> i32 {
+        if a == b {
+            self.matched
+        } else {
+            self.mismatched
+        }
+    }
+}
+
+/// An alignment of two sequences a and b, as the edits that line a up against b. `Keep` and `Substitute` put two
+/// values in a column, `Delete` puts a value of a against a gap and `Insert` a value of b against a gap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alignment {
+    /// the score of the alignment
+    pub score: i32,
+    /// the part of a that is aligned, all of it unless the alignment is local
+    pub a_range: Range<usize>,
+    /// the part of b that is aligned, all of it unless the alignment is local
+    pub b_range: Range<usize>,
+    /// the columns of the alignment in order
+    pub path: Vec<Edit>,
+}
+
+impl Alignment {
+    /// Return the share of columns holding two equal values
+    pub fn identity(&self) -> f64 {
+        let kept = self
+            .path
+            .iter()
+            .filter(|edit| matches!(edit, Edit::Keep { .. }))
+            .count();
+
+        if self.path.is_empty() {
+            0.0
+        } else {
+            kept as f64 / self.path.len() as f64
+        }
+    }
+
+    /// Return the alignment printed as rows of a over rows of b, 60 columns to a line, with gaps as "-". The line
+    /// between marks equal values with "|" and different ones with ".", and each row starts and ends with the
+    /// position, counting from 1, of its first and last value.
+    ///
+    /// # Examples
+    /// ```
+    /// use rs_algo::compare::align::{Aligner, MatchMismatch};
+    ///
+    /// let aligner = Aligner::new(MatchMismatch { matched: 1, mismatched: -1 }, 1, 1);
+    /// let a = b"GATTACA";
+    /// let b = b"GCATGCU";
+    /// let alignment = aligner.global(a, b);
+    ///
+    /// assert_eq!(alignment.score, 0);
+    /// assert_eq!(alignment.pretty(a, b), "1 G-ATTACA 7\n  | | |.|.\n1 GCA-TGCU 7\n");
+    /// ```
+    pub fn pretty<T>(&self, a: &[T], b: &[T]) -> String
+    where
+        T: Copy + Into<char>,
+    {
+        let width = a.len().max(b.len()).to_string().len();
+        let (mut next_a, mut next_b) = (self.a_range.start, self.b_range.start);
+        let mut text = String::new();
+
+        for (block, columns) in self.path.chunks(LINE).enumerate() {
+            let (mut top, mut middle, mut bottom) = (String::new(), String::new(), String::new());
+            let (start_a, start_b) = (next_a, next_b);
+
+            for edit in columns.iter() {
+                // the inputs may hold "-" themselves, so the positions follow the edits rather than the chars
+                let (x, mark, y) = match *edit {
+                    Edit::Keep { a: i, b: j } => (a[i].into(), '|', b[j].into()),
+                    Edit::Substitute { a: i, b: j } => (a[i].into(), '.', b[j].into()),
+                    Edit::Delete { a: i } => (a[i].into(), ' ', '-'),
+                    Edit::Insert { b: j } => ('-', ' ', b[j].into()),
+                    Edit::Transpose { .. } => unreachable!("alignments do not swap values"),
+                };
+                next_a += !matches!(edit, Edit::Insert { .. }) as usize;
+                next_b += !matches!(edit, Edit::Delete { .. }) as usize;
+                top.push(x);
+                middle.push(mark);
+                bottom.push(y);
+            }
+
+            if block > 0 {
+                text.push('\n');
+            }
+            text.push_str(&format!(
+                "{:>w$} {} {}\n",
+                start_a + 1,
+                top,
+                next_a,
+                w = width
+            ));
+            text.push_str(format!("{:w$} {}", "", middle, w = width).trim_end());
+            text.push_str(&format!(
+                "\n{:>w$} {} {}\n",
+                start_b + 1,
+                bottom,
+                next_b,
+                w = width
+            ));
+        }
+
+        text
+    }
+}
+
+// the grid a cell was reached from, or the start of the alignment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum From {
+    Start,
+    Pair,
+    Delete,
+    Insert,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Global,
+    Local,
+    SemiGlobal,
+}
+
+// the best of the candidates, the first one on a tie
+fn best(candidates: &[(i32, From)]) -> (i32, From) {
+    let mut best = candidates[0];
+    for candidate in candidates[1..].iter() {
+        if candidate.0 > best.0 {
+            best = *candidate;
+        }
+    }
+    best
+}
+
+/// Aligns pairs of sequences with a scoring and affine gap penalties. A gap of length k costs `gap_open` for its
+/// first value and `gap_extend` for each one after, both given as positive numbers, so equal penalties give linear
+/// gaps.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::align::{Aligner, MatchMismatch};
+///
+/// let aligner = Aligner::new(MatchMismatch { matched: 3, mismatched: -3 }, 2, 2);
+///
+/// let local = aligner.local(b"TGTTACGG", b"GGTTGACTA");
+/// assert_eq!(local.score, 13);
+/// assert_eq!(local.pretty(b"TGTTACGG", b"GGTTGACTA"), "2 GTT-AC 6\n  ||| ||\n2 GTTGAC 7\n");
+///
+/// // the read fits inside the reference, and the ends of the reference around it are free
+/// let semi = aligner.semi_global(b"TACCGG", b"TTTACCGGAA");
+/// assert_eq!(semi.score, 18);
+/// assert_eq!(aligner.global(b"TACCGG", b"TTTACCGGAA").score, 10);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Aligner<S> {
+    scoring: S,
+    gap_open: i32,
+    gap_extend: i32,
+}
+
+impl<S> Aligner<S> {
+    /// This will return a new Aligner scoring pairs with the given scoring and gaps with the given penalties
+    pub fn new(scoring: S, gap_open: i32, gap_extend: i32) -> Self {
+        Aligner {
+            scoring,
+            gap_open,
+            gap_extend,
+        }
+    }
+
+    /// Return the best global alignment of a and b, Needleman-Wunsch, where both sequences are aligned end to end
+    pub fn global<T>(&self, a: &[T], b: &[T]) -> Alignment
+    where
+        S: Scoring<T>,
+        T: PartialEq,
+    {
+        self.align(a, b, Mode::Global)
+    }
+
+    /// Return the best local alignment of a and b, Smith-Waterman, the highest scoring pair of parts of the two
+    /// sequences. The alignment is empty with a score of 0 if no two values score above 0.
+    pub fn local<T>(&self, a: &[T], b: &[T]) -> Alignment
+    where
+        S: Scoring<T>,
+        T: PartialEq,
+    {
+        self.align(a, b, Mode::Local)
+    }
+
+    /// Return the best semi-global alignment of a and b, where the gaps before the start and after the end of either
+    /// sequence are free. This fits a read inside a reference, or two reads that overlap at their ends. The free gaps
+    /// are still in the path, as deletes and inserts.
+    pub fn semi_global<T>(&self, a: &[T], b: &[T]) -> Alignment
+    where
+        S: Scoring<T>,
+        T: PartialEq,
+    {
+        self.align(a, b, Mode::SemiGlobal)
+    }
+
+    fn align<T>(&self, a: &[T], b: &[T], mode: Mode) -> Alignment
+    where
+        S: Scoring<T>,
+        T: PartialEq,
+    {
+        let width = b.len() + 1;
+        let size = (a.len() + 1) * width;
+        // the best score of a path to each cell ending with a pair, a value of a against a gap, and a value of b
+        // against a gap, along with the grid each came from
+        let mut pair = vec![(NONE, From::Start); size];
+        let mut delete = vec![(NONE, From::Start); size];
+        let mut insert = vec![(NONE, From::Start); size];
+        let (open, extend) = (self.gap_open, self.gap_extend);
+
+        for i in 0..=a.len() {
+            for j in 0..=b.len() {
+                let here = i * width + j;
+                if here == 0 || (mode == Mode::SemiGlobal && (i == 0 || j == 0)) {
+                    pair[here] = (0, From::Start);
+                    continue;
+                }
+
+                if i > 0 && j > 0 {
+                    let diagonal = here - width - 1;
+                    let start = if mode == Mode::Local { 0 } else { NONE };
+                    let (score, from) = best(&[
+                        (start, From::Start),
+                        (pair[diagonal].0, From::Pair),
+                        (delete[diagonal].0, From::Delete),
+                        (insert[diagonal].0, From::Insert),
+                    ]);
+                    pair[here] = (score + self.scoring.score(&a[i - 1], &b[j - 1]), from);
+                }
+                if i > 0 {
+                    let above = here - width;
+                    delete[here] = best(&[
+                        (pair[above].0 - open, From::Pair),
+                        (delete[above].0 - extend, From::Delete),
+                        (insert[above].0 - open, From::Insert),
+                    ]);
+                }
+                if j > 0 {
+                    let left = here - 1;
+                    insert[here] = best(&[
+                        (pair[left].0 - open, From::Pair),
+                        (delete[left].0 - open, From::Delete),
+                        (insert[left].0 - extend, From::Insert),
+                    ]);
+                }
+            }
+        }
+
+        let cell = |i: usize, j: usize| {
+            let here = i * width + j;
+            best(&[
+                (pair[here].0, From::Pair),
+                (delete[here].0, From::Delete),
+                (insert[here].0, From::Insert),
+            ])
+        };
+
+        // where the alignment ends and the grid it ends in
+        let (score, mut i, mut j, mut state) = match mode {
+            Mode::Global => {
+                let (score, state) = cell(a.len(), b.len());
+                (score, a.len(), b.len(), state)
+            }
+            Mode::Local => {
+                let mut end = (0, 0, 0, From::Start);
+                for i in 1..=a.len() {
+                    for j in 1..=b.len() {
+                        if pair[i * width + j].0 > end.0 {
+                            end = (pair[i * width + j].0, i, j, From::Pair);
+                        }
+                    }
+                }
+                end
+            }
+            Mode::SemiGlobal => {
+                let last_row = (0..=b.len()).map(|j| (a.len(), j));
+                let last_column = (0..a.len()).map(|i| (i, b.len()));
+                let mut end = (NONE, 0, 0, From::Start);
+                for (i, j) in last_row.chain(last_column) {
+                    let (score, state) = cell(i, j);
+                    if score > end.0 {
+                        end = (score, i, j, state);
+                    }
+                }
+                end
+            }
+        };
+        let end = (i, j);
+
+        // walked from the end, so the path is built backwards
+        let mut path = Vec::new();
+        if mode == Mode::SemiGlobal {
+            path.extend((j..b.len()).rev().map(|b| Edit::Insert { b }));
+            path.extend((i..a.len()).rev().map(|a| Edit::Delete { a }));
+        }
+
+        loop {
+            let here = i * width + j;
+            state = match state {
+                From::Start => break,
+                From::Pair if i == 0 || j == 0 => break,
+                From::Pair => {
+                    i -= 1;
+                    j -= 1;
+                    path.push(if a[i] == b[j] {
+                        Edit::Keep { a: i, b: j }
+                    } else {
+                        Edit::Substitute { a: i, b: j }
+                    });
+                    pair[here].1
+                }
+                From::Delete => {
+                    i -= 1;
+                    path.push(Edit::Delete { a: i });
+                    delete[here].1
+                }
+                From::Insert => {
+                    j -= 1;
+                    path.push(Edit::Insert { b: j });
+                    insert[here].1
+                }
+            };
+        }
+
+        if mode == Mode::SemiGlobal {
+            path.extend((0..j).rev().map(|b| Edit::Insert { b }));
+            path.extend((0..i).rev().map(|a| Edit::Delete { a }));
+        }
+        path.reverse();
+
+        let (a_range, b_range) = match mode {
+            Mode::Local => (i..end.0, j..end.1),
+            _ => (0..a.len(), 0..b.len()),
+        };
+
+        Alignment {
+            score,
+            a_range,
+            b_range,
+            path,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the score of a path, with the gaps before the first pair and after the last one left out if free_ends
+    fn rescore<S>(aligner: &Aligner<S>, a: &[u64], b: &[u64], path: &[Edit], free_ends: bool) -> i32
+    where
+        S: Scoring<u64>,
+    {
+        let is_pair = |edit: &Edit| matches!(edit, Edit::Keep { .. } | Edit::Substitute { .. });
+        let first = path.iter().position(is_pair).unwrap_or(path.len());
+        let last = path.iter().rposition(is_pair).map_or(0, |last| last + 1);
+
+        let mut score = 0;
+        let mut previous: Option<&Edit> = None;
+        for (k, edit) in path.iter().enumerate() {
+            let free = free_ends && (k < first || k >= last);
+            match *edit {
+                Edit::Keep { a: i, b: j } | Edit::Substitute { a: i, b: j } => {
+                    score += aligner.scoring.score(&a[i], &b[j]);
+                }
+                Edit::Delete { .. } if !free => {
+                    score -= match previous {
+                        Some(Edit::Delete { .. }) => aligner.gap_extend,
+                        _ => aligner.gap_open,
+                    };
+                }
+                Edit::Insert { .. } if !free => {
+                    score -= match previous {
+                        Some(Edit::Insert { .. }) => aligner.gap_extend,
+                        _ => aligner.gap_open,
+                    };
+                }
+                _ => {}
+            }
+            previous = Some(edit);
+        }
+        score
+    }
+
+    // check the path walks both ranges in order, one column at a time
+    fn walk(alignment: &Alignment, a: &[u64], b: &[u64]) {
+        let (mut i, mut j) = (alignment.a_range.start, alignment.b_range.start);
+        for edit in alignment.path.iter() {
+            match *edit {
+                Edit::Keep { a: x, b: y } => {
+                    assert_eq!((x, y, a[x]), (i, j, b[y]));
+                    i += 1;
+                    j += 1;
+                }
+                Edit::Substitute { a: x, b: y } => {
+                    assert_eq!((x, y), (i, j));
+                    assert_ne!(a[x], b[y]);
+                    i += 1;
+                    j += 1;
+                }
+                Edit::Delete { a: x } => {
+                    assert_eq!(x, i);
+                    i += 1;
+                }
+                Edit::Insert { b: y } => {
+                    assert_eq!(y, j);
+                    j += 1;
+                }
+                Edit::Transpose { .. } => panic!("alignments do not swap values"),
+            }
+        }
+        assert_eq!((i, j), (alignment.a_range.end, alignment.b_range.end));
+    }
+
+    #[test]
+    fn align_matches_edit_distances() {
+        use crate::compare::distance::levenshtein;
+        use crate::compare::LCSubsequence;
+        use crate::test_util::random_vec;
+
+        let lcs = Aligner::new(
+            MatchMismatch {
+                matched: 1,
+                mismatched: -100,
+            },
+            0,
+            0,
+        );
+        let edit = Aligner::new(
+            MatchMismatch {
+                matched: 0,
+                mismatched: -1,
+            },
+            1,
+            1,
+        );
+
+        let mut state = 47;
+        for _ in 0..300 {
+            let a = random_vec(&mut state, 15, 4);
+            let b = random_vec(&mut state, 15, 4);
+
+            let alignment = lcs.global(&a, &b);
+            assert_eq!(
+                alignment.score as u32,
+                LCSubsequence::new(&a, &b).subsequence_len
+            );
+            walk(&alignment, &a, &b);
+
+            let alignment = edit.global(&a, &b);
+            assert_eq!(-alignment.score as usize, levenshtein(&a, &b));
+            walk(&alignment, &a, &b);
+        }
+    }
+
+    #[test]
+    fn align_affine_paths() {
+        use crate::test_util::{random, random_vec};
+
+        let mut state = 53;
+        for round in 0..400 {
+            let aligner = Aligner::new(
+                MatchMismatch {
+                    matched: 2 + round % 3,
+                    mismatched: -3,
+                },
+                1 + random(&mut state, 6) as i32,
+                random(&mut state, 3) as i32,
+            );
+            let a = random_vec(&mut state, 14, 4);
+            let b = random_vec(&mut state, 14, 4);
+
+            let global = aligner.global(&a, &b);
+            walk(&global, &a, &b);
+            assert_eq!(rescore(&aligner, &a, &b, &global.path, false), global.score);
+
+            let local = aligner.local(&a, &b);
+            walk(&local, &a, &b);
+            assert_eq!(rescore(&aligner, &a, &b, &local.path, false), local.score);
+            assert!(local.score >= 0);
+            assert!(local
+                .path
+                .first()
+                .map_or(true, |edit| matches!(edit, Edit::Keep { .. })));
+
+            let semi = aligner.semi_global(&a, &b);
+            walk(&semi, &a, &b);
+            assert!(rescore(&aligner, &a, &b, &semi.path, true) >= semi.score);
+            assert!(global.score <= semi.score && semi.score <= local.score);
+
+            // a is found whole inside a longer b
+            let mut longer = b.clone();
+            longer.splice(b.len() / 2..b.len() / 2, a.iter().copied());
+            let inside = aligner.semi_global(&a, &longer);
+            let matched = aligner.scoring.score(&0, &0) * a.len() as i32;
+            assert_eq!(inside.score, matched);
+        }
+    }
+
+    #[test]
+    fn align_edges() {
+        let aligner = Aligner::new(SubstitutionMatrix::blosum62(), 10, 1);
+
+        let empty = aligner.global(b"", b"");
+        assert_eq!((empty.score, empty.path.len()), (0, 0));
+        assert_eq!(empty.pretty(b"", b""), "");
+        assert_eq!(empty.identity(), 0.0);
+
+        let gap = aligner.global(b"", b"WWW");
+        assert_eq!(gap.score, -12);
+        assert_eq!(gap.pretty(b"", b"WWW"), "1 --- 0\n\n1 WWW 3\n");
+
+        let none = aligner.local(b"WWW", b"PPP");
+        assert_eq!((none.score, none.a_range, none.b_range), (0, 0..0, 0..0));
+        assert!(none.path.is_empty());
+
+        let semi = aligner.semi_global(b"", b"MK");
+        assert_eq!((semi.score, semi.path.len()), (0, 2));
+
+        // the three values of a left over make one gap, opened once
+        let a = b"MKVLAAAGIVW";
+        let b = b"MKVLGIVW";
+        let alignment = aligner.global(a, b);
+        assert_eq!(alignment.score, 43 - 12);
+        let gaps: Vec<usize> = alignment
+            .path
+            .iter()
+            .filter_map(|edit| match edit {
+                Edit::Delete { a } => Some(*a),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(gaps, vec![4, 5, 6]);
+
+        // long alignments wrap
+        let long = vec![b'A'; 130];
+        let text = aligner.global(&long, &long).pretty(&long, &long);
+        assert_eq!(text.lines().count(), 11);
+        assert!(text.starts_with(&format!("  1 {} 60\n", "A".repeat(60))));
+        assert!(text.ends_with(&format!("121 {} 130\n", "A".repeat(10))));
+
+        // a "-" in gapped input is still a value and is counted
+        let dashes = Aligner::new(
+            MatchMismatch {
+                matched: 1,
+                mismatched: -1,
+            },
+            1,
+            1,
+        );
+        assert_eq!(
+            dashes.global(b"A-C", b"AC").pretty(b"A-C", b"AC"),
+            "1 A-C 3\n  | |\n1 A-C 2\n"
+        );
+        assert_eq!(
+            dashes.global(b"AC", b"-AC").pretty(b"AC", b"-AC"),
+            "1 -AC 2\n   ||\n1 -AC 3\n"
+        );
+
+        let pam = Aligner::new(SubstitutionMatrix::pam250(), 10, 1);
+        assert_eq!(pam.global("WF".as_bytes(), "WY".as_bytes()).score, 24);
+        let closure = Aligner::new(|a: &char, b: &char| if a == b { 1 } else { -1 }, 1, 1);
+        let alignment = closure.global(&['a', 'b'], &['a', 'c']);
+        assert_eq!((alignment.score, alignment.identity()), (0, 0.5));
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+const WORD: usize = 64;
+
+/// A pattern prepared for comparing against many texts with bit-parallel kernels. For each distinct value of the
+/// pattern a bit mask of where it is found is stored, and a column of the comparison grid is then updated a 64 bit
+/// word at a time, so each value of a text costs O(⌈m / 64⌉) word operations rather than the O(m) of a cell by cell
+/// loop. Patterns longer than 64 are split into blocks of 64 with carries passed between them.
+///
+/// The LCS length uses the Allison-Dix recurrence in Hyyrö's form, and the Levenshtein distance uses Myers' 1999
+/// bit-vector algorithm. `distance::levenshtein_str`, `distance::lcs_distance` and the string constructors of
+/// `LCSubsequence` use these kernels. Sequences whose values are only `PartialEq` or `Eq` can't be masked, so
+/// `distance::levenshtein` and `LCSubsequence::new` keep to a cell by cell loop.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::BitPattern;
+///
+/// let pattern = BitPattern::new_str("kitten");
+///
+/// assert_eq!(pattern.levenshtein_str("sitting"), 3);
+/// assert_eq!(pattern.lcs_len_str("sitting"), 4);
+///
+/// let pattern = BitPattern::new(&[1, 2, 3, 4]);
+/// assert_eq!(pattern.levenshtein(&[2, 3, 4, 5]), 2);
+/// assert_eq!(pattern.lcs_len(&[2, 3, 4, 5]), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BitPattern<T> {
+    len: usize,
+    words: usize,
+    // the index into masks of each value's first word
+    index: HashMap<T, usize>,
+    masks: Vec<u64>,
+    // the masks of a value not in the pattern
+    missing: Vec<u64>,
+}
+
+impl<T> BitPattern<T>
+where
+    T: Eq + Hash + Clone,
+{
+    /// This will return a new BitPattern over the given values, building the mask of each distinct value.
+    pub fn new(pattern: &[T]) -> Self {
+        let words = pattern.len().div_ceil(WORD);
+        let mut index = HashMap::new();
+        let mut masks = Vec::new();
+
+        for (i, value) in pattern.iter().enumerate() {
+            let start = *index.entry(value.clone()).or_insert_with(|| {
+                masks.resize(masks.len() + words, 0);
+                masks.len() - words
+            });
+            masks[start + i / WORD] |= 1 << (i % WORD);
+        }
+
+        BitPattern {
+            len: pattern.len(),
+            words,
+            index,
+            masks,
+            missing: vec![0; words],
+        }
+    }
+
+    /// Return the length of the pattern
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return true if the pattern is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the length of the longest common subsequence of the pattern and the text
+    pub fn lcs_len(&self, text: &[T]) -> usize {
+        self.lcs_of(text.iter().map(|value| self.masks_of(value)))
+    }
+
+    /// Return the Levenshtein distance between the pattern and the text
+    pub fn levenshtein(&self, text: &[T]) -> usize {
+        self.levenshtein_of(text.iter().map(|value| self.masks_of(value)))
+    }
+
+    fn masks_of(&self, value: &T) -> &[u64] {
+        match self.index.get(value) {
+            Some(start) => &self.masks[*start..*start + self.words],
+            None => &self.missing,
+        }
+    }
+
+    // Hyyrö: the zero bits of v mark the rows where the LCS grows, across the text's columns
+    fn lcs_of<'a, I>(&self, text: I) -> usize
+    where
+        I: Iterator<Item = &'a [u64]>,
+    {
+        let mut v = vec![!0u64; self.words];
+
+        for eq in text {
+            let mut carry = false;
+            for (word, eq) in v.iter_mut().zip(eq.iter()) {
+                let u = *word & eq;
+                let (sum, first) = word.overflowing_add(u);
+                let (sum, second) = sum.overflowing_add(carry as u64);
+                carry = first || second;
+                *word = sum | (*word - u);
+            }
+        }
+
+        v.iter()
+            .enumerate()
+            .map(|(w, word)| {
+                let bits = (self.len - w * WORD).min(WORD);
+                (!word & low_bits(bits)).count_ones() as usize
+            })
+            .sum()
+    }
+
+    // Myers: the vertical deltas of a column are kept as +1 and -1 bit vectors, and the delta along the bottom row
+    // gives the change in distance per column
+    fn levenshtein_of<'a, I>(&self, text: I) -> usize
+    where
+        I: Iterator<Item = &'a [u64]>,
+    {
+        let mut distance = self.len;
+        let mut plus = vec![!0u64; self.words];
+        let mut minus = vec![0u64; self.words];
+
+        for eq in text {
+            // the top row of the grid grows by one per column
+            let mut carry = 1;
+            for w in 0..self.words {
+                let last = 1 << ((self.len - w * WORD).min(WORD) - 1);
+                carry = advance_block(&mut plus[w], &mut minus[w], eq[w], carry, last);
+            }
+            distance = distance.wrapping_add_signed(carry as isize);
+        }
+
+        distance
+    }
+}
+
+impl BitPattern<char> {
+    /// This will return a new BitPattern over the chars of a string.
+    pub fn new_str(pattern: &str) -> Self {
+        let pattern: Vec<char> = pattern.chars().collect();
+        BitPattern::new(&pattern)
+    }
+
+    /// Return the length of the longest common subsequence of the pattern and the chars of the text
+    pub fn lcs_len_str(&self, text: &str) -> usize {
+        self.lcs_of(text.chars().map(|c| self.masks_of(&c)))
+    }
+
+    /// Return the Levenshtein distance between the pattern and the chars of the text
+    pub fn levenshtein_str(&self, text: &str) -> usize {
+        self.levenshtein_of(text.chars().map(|c| self.masks_of(&c)))
+    }
+}
+
+fn low_bits(bits: usize) -> u64 {
+    if bits == WORD {
+        !0
+    } else {
+        (1 << bits) - 1
+    }
+}
+
+// advance one block of vertical deltas by a column, given the horizontal delta entering at its top, returning the
+// horizontal delta leaving at the row marked by last
+fn advance_block(plus: &mut u64, minus: &mut u64, eq: u64, carry_in: i8, last: u64) -> i8 {
+    let (pv, mv) = (*plus, *minus);
+    let carry_minus = (carry_in < 0) as u64;
+
+    let xv = eq | mv;
+    let eq = eq | carry_minus;
+    let xh = ((eq & pv).wrapping_add(pv) ^ pv) | eq;
+    let mut ph = mv | !(xh | pv);
+    let mut mh = pv & xh;
+
+    let carry_out = if ph & last != 0 {
+        1
+    } else if mh & last != 0 {
+        -1
+    } else {
+        0
+    };
+
+    ph = (ph << 1) | (carry_in > 0) as u64;
+    mh = (mh << 1) | carry_minus;
+    *plus = mh | !(xv | ph);
+    *minus = ph & xv;
+
+    carry_out
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn bit_parallel_matches_grid() {
+        use super::*;
+        use crate::compare::distance::levenshtein;
+        use crate::compare::LCSubsequence;
+        use crate::test_util::{random, random_vec};
+
+        let mut state = 73;
+        for round in 0..400 {
+            // cover one word, several words and exact multiples of a word
+            let max_len = [10, 70, 140, 200][round % 4];
+            let alphabet = 2 + random(&mut state, 5);
+            let pattern = random_vec(&mut state, max_len, alphabet);
+            let bits = BitPattern::new(&pattern);
+
+            for _ in 0..3 {
+                let text = random_vec(&mut state, max_len, alphabet + 1);
+
+                assert_eq!(bits.levenshtein(&text), levenshtein(&pattern, &text));
+                assert_eq!(
+                    bits.lcs_len(&text),
+                    LCSubsequence::new(&pattern, &text).subsequence_len as usize
+                );
+            }
+        }
+
+        for len in [63, 64, 65, 128, 129] {
+            let pattern = vec![1; len];
+            let bits = BitPattern::new(&pattern);
+            assert_eq!(bits.levenshtein(&pattern), 0);
+            assert_eq!(bits.lcs_len(&pattern), len);
+            assert_eq!(bits.levenshtein(&[]), len);
+            assert_eq!(bits.lcs_len(&[2; 300]), 0);
+            assert_eq!(bits.levenshtein(&[2; 300]), 300);
+        }
+    }
+
+    #[test]
+    fn bit_parallel_str() {
+        use super::*;
+
+        let empty = BitPattern::new_str("");
+        assert!(empty.is_empty());
+        assert_eq!(empty.levenshtein_str("abc"), 3);
+        assert_eq!(empty.lcs_len_str("abc"), 0);
+
+        let pattern = BitPattern::new_str("😀a");
+        assert_eq!(pattern.len(), 2);
+        assert_eq!(pattern.levenshtein_str("a😀"), 2);
+        assert_eq!(pattern.lcs_len_str("a😀"), 1);
+    }
+}
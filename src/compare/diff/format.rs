@@ -0,0 +1,426 @@
+use std::fmt::{self, Debug, Formatter};
+use std::rc::Rc;
+
+use super::patch::{Patch, PatchHunk, PatchLine};
+use super::Hunk;
+
+/// Split text into lines, each keeping its line ending. Unlike `str::lines` a last line without a line ending is
+/// kept apart from one with it, so a diff can tell when only the final newline changed. These are the lines
+/// `DiffFormat` expects.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::diff;
+///
+/// assert_eq!(diff::lines("a\nb\r\nc"), vec!["a\n", "b\r\n", "c"]);
+/// assert!(diff::lines("").is_empty());
+/// ```
+pub fn lines(text: &str) -> Vec<&str> {
+    text.split_inclusive('\n').collect()
+}
+
+/// How to render an edit script over lines as text, in the unified or context format of `diff -u` and `diff -c`.
+/// Both formats show each group of changes with a few unchanged lines of context around it.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::diff::{self, DiffFormat};
+///
+/// let old = diff::lines("[server]\nport = 80\nhost = a\n");
+/// let new = diff::lines("[server]\nport = 8080\nhost = a\n");
+/// let hunks = diff::diff(&old, &new);
+///
+/// let format = DiffFormat::new()
+///     .names("app.conf", "app.conf.new")
+///     .context(1)
+///     .heading(|line| line.starts_with('['));
+///
+/// assert_eq!(
+///     format.unified(&old, &new, &hunks),
+///     "--- app.conf\n+++ app.conf.new\n@@ -1,3 +1,3 @@\n [server]\n-port = 80\n+port = 8080\n host = a\n"
+/// );
+/// ```
+#[derive(Clone)]
+pub struct DiffFormat {
+    context: usize,
+    old_name: String,
+    new_name: String,
+    heading: Option<Heading>,
+}
+
+// the pattern a line needs to match to head a hunk
+type Heading = Rc<dyn Fn(&str) -> bool>;
+
+impl Debug for DiffFormat {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("DiffFormat")
+            .field("context", &self.context)
+            .field("old_name", &self.old_name)
+            .field("new_name", &self.new_name)
+            .field("heading", &self.heading.is_some())
+            .finish()
+    }
+}
+
+impl Default for DiffFormat {
+    fn default() -> Self {
+        DiffFormat {
+            context: 3,
+            old_name: "a".to_string(),
+            new_name: "b".to_string(),
+            heading: None,
+        }
+    }
+}
+
+impl DiffFormat {
+    /// This will return a new DiffFormat with 3 lines of context and the file names "a" and "b"
+    pub fn new() -> Self {
+        DiffFormat::default()
+    }
+
+    /// Show the given number of unchanged lines around each change
+    pub fn context(mut self, lines: usize) -> Self {
+        self.context = lines;
+        self
+    }
+
+    /// Name the old and new file in the header
+    pub fn names(mut self, old: &str, new: &str) -> Self {
+        self.old_name = old.to_string();
+        self.new_name = new.to_string();
+        self
+    }
+
+    /// Follow every hunk header with the closest line above the hunk that matches, such as the start of the
+    /// function or section the change is in, like `diff -p` and `diff -F`. The pattern can be any closure, such as
+    /// one picked for the file type at runtime.
+    pub fn heading<F>(mut self, matches: F) -> Self
+    where
+        F: Fn(&str) -> bool + 'static,
+    {
+        self.heading = Some(Rc::new(matches));
+        self
+    }
+
+    /// Return the edit script as a `Patch`, one hunk for each group of changes. The lines are the ones the script
+    /// was made from, split with `lines`.
+    pub fn patch(&self, old: &[&str], new: &[&str], hunks: &[Hunk]) -> Patch {
+        let hunks = groups(hunks, self.context)
+            .into_iter()
+            .map(|group| {
+                let (old_range, new_range) = span(&group);
+                let mut lines = Vec::new();
+
+                for hunk in group.iter() {
+                    match hunk {
+                        Hunk::Equal { .. } => lines.extend(
+                            old[hunk.old_range()]
+                                .iter()
+                                .map(|line| PatchLine::Context(line.to_string())),
+                        ),
+                        Hunk::Delete { .. } => lines.extend(
+                            old[hunk.old_range()]
+                                .iter()
+                                .map(|line| PatchLine::Delete(line.to_string())),
+                        ),
+                        Hunk::Insert { .. } => lines.extend(
+                            new[hunk.new_range()]
+                                .iter()
+                                .map(|line| PatchLine::Insert(line.to_string())),
+                        ),
+                    }
+                }
+
+                PatchHunk {
+                    old_start: header_start(old_range.0, old_range.1),
+                    old_len: old_range.1 - old_range.0,
+                    new_start: header_start(new_range.0, new_range.1),
+                    new_len: new_range.1 - new_range.0,
+                    heading: self.find_heading(old, old_range.0),
+                    lines,
+                }
+            })
+            .collect();
+
+        Patch {
+            old_name: Some(self.old_name.clone()),
+            new_name: Some(self.new_name.clone()),
+            hunks,
+        }
+    }
+
+    /// Render the edit script in the unified format. An empty string is returned if nothing changed.
+    pub fn unified(&self, old: &[&str], new: &[&str], hunks: &[Hunk]) -> String {
+        let patch = self.patch(old, new, hunks);
+        if patch.hunks.is_empty() {
+            return String::new();
+        }
+
+        patch.to_string()
+    }
+
+    /// Render the edit script in the context format. An empty string is returned if nothing changed.
+    ///
+    /// # Examples
+    /// ```
+    /// use rs_algo::compare::diff::{self, DiffFormat};
+    ///
+    /// let old = diff::lines("one\ntwo\nthree\n");
+    /// let new = diff::lines("one\n2\nthree\nfour\n");
+    /// let hunks = diff::diff(&old, &new);
+    ///
+    /// assert_eq!(
+    ///     DiffFormat::new().context_diff(&old, &new, &hunks),
+    ///     "*** a\n--- b\n***************\n*** 1,3 ****\n  one\n! two\n  three\n--- 1,4 ----\n  one\n! 2\n  three\n+ four\n"
+    /// );
+    /// ```
+    pub fn context_diff(&self, old: &[&str], new: &[&str], hunks: &[Hunk]) -> String {
+        let groups = groups(hunks, self.context);
+        if groups.is_empty() {
+            return String::new();
+        }
+
+        let mut out = format!("*** {}\n--- {}\n", self.old_name, self.new_name);
+
+        for group in groups.iter() {
+            let (old_range, new_range) = span(group);
+
+            out.push_str("***************");
+            let heading = self.find_heading(old, old_range.0);
+            if !heading.is_empty() {
+                out.push(' ');
+                out.push_str(&heading);
+            }
+            out.push('\n');
+
+            // each side only lists its lines when something on that side changed
+            out.push_str(&format!("*** {} ****\n", context_range(old_range)));
+            if group.iter().any(|hunk| matches!(hunk, Hunk::Delete { .. })) {
+                for (i, hunk) in group.iter().enumerate() {
+                    let prefix = match hunk {
+                        Hunk::Equal { .. } => "  ",
+                        Hunk::Delete { .. } if is_replace(group, i) => "! ",
+                        Hunk::Delete { .. } => "- ",
+                        Hunk::Insert { .. } => continue,
+                    };
+                    for line in old[hunk.old_range()].iter() {
+                        push_line(&mut out, prefix, line);
+                    }
+                }
+            }
+
+            out.push_str(&format!("--- {} ----\n", context_range(new_range)));
+            if group.iter().any(|hunk| matches!(hunk, Hunk::Insert { .. })) {
+                for (i, hunk) in group.iter().enumerate() {
+                    let prefix = match hunk {
+                        Hunk::Equal { .. } => "  ",
+                        Hunk::Insert { .. } if i > 0 && is_replace(group, i - 1) => "! ",
+                        Hunk::Insert { .. } => "+ ",
+                        Hunk::Delete { .. } => continue,
+                    };
+                    for line in new[hunk.new_range()].iter() {
+                        push_line(&mut out, prefix, line);
+                    }
+                }
+            }
+        }
+
+        out
+    }
+
+    // the closest line above the given one that matches the heading pattern, without its line ending
+    fn find_heading(&self, old: &[&str], before: usize) -> String {
+        let matches = match &self.heading {
+            Some(matches) => matches,
+            None => return String::new(),
+        };
+
+        old[..before]
+            .iter()
+            .rev()
+            .find(|line| matches(line))
+            .map_or(String::new(), |line| {
+                line.trim_end_matches(['\n', '\r']).to_string()
+            })
+    }
+}
+
+// write a line of a diff, marking a line that does not end in a newline the way diff does
+pub(super) fn push_line(out: &mut String, prefix: &str, line: &str) {
+    out.push_str(prefix);
+    out.push_str(line);
+    if !line.ends_with('\n') {
+        out.push_str("\n\\ No newline at end of file\n");
+    }
+}
+
+// a delete followed by an insert is shown as lines that changed
+fn is_replace(group: &[Hunk], i: usize) -> bool {
+    matches!(group[i], Hunk::Delete { .. }) && matches!(group.get(i + 1), Some(Hunk::Insert { .. }))
+}
+
+// the first line number of a range in a hunk header, an empty range is given by the line before it
+fn header_start(start: usize, end: usize) -> usize {
+    if start == end {
+        start
+    } else {
+        start + 1
+    }
+}
+
+// a range in a context diff header, the first and last line numbers
+fn context_range((start, end): (usize, usize)) -> String {
+    match end - start {
+        0 => format!("{}", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, end),
+    }
+}
+
+// the old and new lines a group of hunks covers
+fn span(group: &[Hunk]) -> ((usize, usize), (usize, usize)) {
+    let (first, last) = (group[0], group[group.len() - 1]);
+    (
+        (first.old_range().start, last.old_range().end),
+        (first.new_range().start, last.new_range().end),
+    )
+}
+
+// split the changes of an edit script into groups, each with at most context equal lines around it. Changes with no
+// more than twice the context between them share a group.
+fn groups(hunks: &[Hunk], context: usize) -> Vec<Vec<Hunk>> {
+    let mut groups = Vec::new();
+    let mut group = Vec::new();
+
+    for (i, hunk) in hunks.iter().enumerate() {
+        let (old, new, len) = match *hunk {
+            Hunk::Equal { old, new, len } => (old, new, len),
+            _ => {
+                group.push(*hunk);
+                continue;
+            }
+        };
+
+        let keep_start = if i == 0 { 0 } else { len.min(context) };
+        let keep_end = if i + 1 == hunks.len() {
+            0
+        } else {
+            len.min(context)
+        };
+
+        if i > 0 && i + 1 < hunks.len() && len <= 2 * context {
+            group.push(*hunk);
+            continue;
+        }
+
+        if keep_start > 0 {
+            group.push(Hunk::Equal {
+                old,
+                new,
+                len: keep_start,
+            });
+        }
+        if group.iter().any(|hunk| !matches!(hunk, Hunk::Equal { .. })) {
+            groups.push(std::mem::take(&mut group));
+        }
+        group.clear();
+        if keep_end > 0 {
+            group.push(Hunk::Equal {
+                old: old + len - keep_end,
+                new: new + len - keep_end,
+                len: keep_end,
+            });
+        }
+    }
+
+    if group.iter().any(|hunk| !matches!(hunk, Hunk::Equal { .. })) {
+        groups.push(group);
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn unified_format() {
+        use super::super::diff;
+        use super::*;
+
+        let old_text: String = (1..=20).map(|i| format!("line {}\n", i)).collect();
+        let new_text = old_text
+            .replace("line 2\n", "line two\n")
+            .replace("line 4\n", "")
+            .replace("line 18\n", "line 18\nextra\n");
+
+        let old = lines(&old_text);
+        let new = lines(&new_text);
+        let hunks = diff(&old, &new);
+
+        let expected = "--- a\n+++ b\n\
+            @@ -1,7 +1,6 @@\n line 1\n-line 2\n+line two\n line 3\n-line 4\n line 5\n line 6\n line 7\n\
+            @@ -16,5 +15,6 @@\n line 16\n line 17\n line 18\n+extra\n line 19\n line 20\n";
+        assert_eq!(DiffFormat::new().unified(&old, &new, &hunks), expected);
+
+        // with no context the two nearby changes get their own hunks
+        let expected = "--- a\n+++ b\n@@ -2 +2 @@\n-line 2\n+line two\n@@ -4 +3,0 @@\n-line 4\n@@ -18,0 +18 @@\n+extra\n";
+        assert_eq!(
+            DiffFormat::new().context(0).unified(&old, &new, &hunks),
+            expected
+        );
+
+        assert_eq!(DiffFormat::new().unified(&old, &old, &diff(&old, &old)), "");
+    }
+
+    #[test]
+    fn unified_missing_newline() {
+        use super::super::diff;
+        use super::*;
+
+        let old = lines("a\nb");
+        let new = lines("a\nb\n");
+        let hunks = diff(&old, &new);
+
+        assert_eq!(
+            DiffFormat::new().unified(&old, &new, &hunks),
+            "--- a\n+++ b\n@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+b\n"
+        );
+
+        let empty: Vec<&str> = Vec::new();
+        assert_eq!(
+            DiffFormat::new().unified(&empty, &new, &diff(&empty, &new)),
+            "--- a\n+++ b\n@@ -0,0 +1,2 @@\n+a\n+b\n"
+        );
+    }
+
+    #[test]
+    fn context_format() {
+        use super::super::diff;
+        use super::*;
+
+        let old = lines("fn main() {\n    a();\n    b();\n}\n\nfn other() {\n    c();\n}\n");
+        let new = lines("fn main() {\n    a();\n}\n\nfn other() {\n    c();\n    d();\n}\n");
+        let hunks = diff(&old, &new);
+
+        let format = DiffFormat::new()
+            .names("old.rs", "new.rs")
+            .context(1)
+            .heading(|line| line.starts_with("fn "));
+        let expected = "*** old.rs\n--- new.rs\n\
+            *************** fn main() {\n*** 2,4 ****\n      a();\n-     b();\n  }\n--- 2,3 ----\n\
+            *************** fn other() {\n*** 7,8 ****\n--- 6,8 ----\n      c();\n+     d();\n  }\n";
+        assert_eq!(format.context_diff(&old, &new, &hunks), expected);
+
+        let unified = format.unified(&old, &new, &hunks);
+        assert!(unified.contains("@@ -2,3 +2,2 @@ fn main() {\n"));
+        assert!(unified.contains("@@ -7,2 +6,3 @@ fn other() {\n"));
+
+        // a pattern chosen at runtime, captured by the closure
+        let prefix = String::from("fn other");
+        let format = format.heading(move |line| line.starts_with(prefix.as_str()));
+        let unified = format.clone().unified(&old, &new, &hunks);
+        assert!(unified.contains("@@ -2,3 +2,2 @@\n"));
+        assert!(unified.contains("@@ -7,2 +6,3 @@ fn other() {\n"));
+        assert!(format!("{:?}", format).contains("heading: true"));
+    }
+}
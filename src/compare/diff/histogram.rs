@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Range;
+
+use super::{
+    common_prefix, common_suffix, myers, patience, DiffAlgorithm, Hunk, HunkBuilder, MAX_DEPTH,
+};
+
+// values found more often than this in the old range are never used to split it, as in git
+const MAX_CHAIN: usize = 64;
+
+// finding a region scans both ranges, once the scans add up to this many times the length of the inputs the ranges
+// left are given to patience diff
+const MAX_WORK: usize = 16;
+
+/// Histogram diff, the algorithm behind `git diff --histogram`. It extends patience diff to values that are not
+/// unique: the common run whose rarest value is found the fewest times in the old sequence is matched first, the
+/// longest one on a tie, and the values on either side of it are diffed the same way. Ranges that only share common
+/// values are left to Myers' algorithm, as are the ranges left once the splitting has nested too deeply. Each split
+/// scans the ranges it splits, so once the scans add up to several times the length of the inputs the ranges left
+/// are diffed with patience diff instead, which keeps inputs where every split is one value wide from taking
+/// quadratic time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Histogram;
+
+impl DiffAlgorithm for Histogram {
+    fn diff<T>(&self, old: &[T], new: &[T]) -> Vec<Hunk>
+    where
+        T: Eq + Hash,
+    {
+        let mut hunks = HunkBuilder::default();
+        let mut budget = MAX_WORK * (old.len() + new.len());
+        diff(
+            old,
+            0..old.len(),
+            new,
+            0..new.len(),
+            0,
+            &mut budget,
+            &mut hunks,
+        );
+        hunks.finish()
+    }
+}
+
+// a common run of old[old..old + len] and new[new..new + len], whose rarest value is in the old range count times
+#[derive(Debug, Clone, Copy)]
+struct Region {
+    old: usize,
+    new: usize,
+    len: usize,
+    count: usize,
+}
+
+fn diff<T>(
+    old: &[T],
+    old_range: Range<usize>,
+    new: &[T],
+    new_range: Range<usize>,
+    depth: usize,
+    budget: &mut usize,
+    hunks: &mut HunkBuilder,
+) where
+    T: Eq + Hash,
+{
+    let (mut old_range, mut new_range) = (old_range, new_range);
+
+    let prefix = common_prefix(&old[old_range.clone()], &new[new_range.clone()]);
+    hunks.equal(old_range.start, new_range.start, prefix);
+    old_range.start += prefix;
+    new_range.start += prefix;
+
+    let suffix = common_suffix(&old[old_range.clone()], &new[new_range.clone()]);
+    old_range.end -= suffix;
+    new_range.end -= suffix;
+
+    // as in git, the part before each region is diffed by recursing and the part after it by going round again, so
+    // a long run of regions at the start of their range does not add to the depth
+    let (mut at_old, mut at_new) = (old_range.start, new_range.start);
+    loop {
+        let cost = (old_range.end - at_old) + (new_range.end - at_new);
+        let region = if depth < MAX_DEPTH && cost <= *budget {
+            *budget -= cost;
+            rarest_region(old, at_old..old_range.end, new, at_new..new_range.end)
+        } else {
+            None
+        };
+
+        match region {
+            Some(region) => {
+                diff(
+                    old,
+                    at_old..region.old,
+                    new,
+                    at_new..region.new,
+                    depth + 1,
+                    budget,
+                    hunks,
+                );
+                hunks.equal(region.old, region.new, region.len);
+                at_old = region.old + region.len;
+                at_new = region.new + region.len;
+                continue;
+            }
+            None if depth < MAX_DEPTH && cost > *budget => patience::diff(
+                old,
+                at_old..old_range.end,
+                new,
+                at_new..new_range.end,
+                depth,
+                hunks,
+            ),
+            None => myers::diff(
+                old,
+                at_old..old_range.end,
+                new,
+                at_new..new_range.end,
+                hunks,
+            ),
+        }
+        break;
+    }
+
+    hunks.equal(old_range.end, new_range.end, suffix);
+}
+
+// the common run to split the ranges at, or None if every shared value is too common
+fn rarest_region<T>(
+    old: &[T],
+    old_range: Range<usize>,
+    new: &[T],
+    new_range: Range<usize>,
+) -> Option<Region>
+where
+    T: Eq + Hash,
+{
+    // where each value is in the old range
+    let mut positions: HashMap<&T, Vec<usize>> = HashMap::new();
+    for i in old_range.clone() {
+        positions.entry(&old[i]).or_default().push(i);
+    }
+
+    let mut best: Option<Region> = None;
+    let mut j = new_range.start;
+
+    while j < new_range.end {
+        let mut next = j + 1;
+        let limit = best.map_or(MAX_CHAIN, |best| best.count);
+
+        if let Some(found) = positions.get(&new[j]).filter(|found| found.len() <= limit) {
+            for i in found.iter() {
+                let (mut start_old, mut start_new) = (*i, j);
+                while start_old > old_range.start
+                    && start_new > new_range.start
+                    && old[start_old - 1] == new[start_new - 1]
+                {
+                    start_old -= 1;
+                    start_new -= 1;
+                }
+
+                let (mut end_old, mut end_new) = (i + 1, j + 1);
+                while end_old < old_range.end
+                    && end_new < new_range.end
+                    && old[end_old] == new[end_new]
+                {
+                    end_old += 1;
+                    end_new += 1;
+                }
+
+                let count = old[start_old..end_old]
+                    .iter()
+                    .map(|value| positions[value].len())
+                    .min()
+                    .unwrap();
+                let region = Region {
+                    old: start_old,
+                    new: start_new,
+                    len: end_old - start_old,
+                    count,
+                };
+
+                if best.map_or(true, |best| {
+                    count < best.count || (count == best.count && region.len > best.len)
+                }) {
+                    best = Some(region);
+                }
+                // the rest of this run can not start a better region
+                next = next.max(end_new);
+            }
+        }
+
+        j = next;
+    }
+
+    best
+}
@@ -0,0 +1,377 @@
+use std::ops::Range;
+
+use super::{diff, lines, Hunk};
+
+/// How a conflict is written into the merged text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConflictStyle {
+    /// Our lines and their lines, as `git merge` writes them
+    Merge,
+    /// Our lines, the base lines and their lines, as `diff3 -m` and git's `merge.conflictStyle = diff3` write them
+    Diff3,
+}
+
+/// A part of the merge where ours and theirs both changed the same base lines in different ways. The ranges are line
+/// indices counting from 0.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Conflict {
+    /// the lines of the base both sides changed
+    pub base: Range<usize>,
+    /// what our side has in their place
+    pub ours: Range<usize>,
+    /// what their side has in their place
+    pub theirs: Range<usize>,
+    /// the lines of the merged text the conflict takes up, markers included
+    pub merged: Range<usize>,
+}
+
+/// The outcome of a three-way merge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Merged {
+    /// the merged text, with conflict markers wherever the sides could not be merged
+    pub text: String,
+    /// every conflict, in order
+    pub conflicts: Vec<Conflict>,
+}
+
+impl Merged {
+    /// Return true if the merge had no conflicts
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// A three-way merge of text, like `diff3 -m` and `git merge-file`. Our text and their text are each diffed against
+/// the base they both started from. Lines only one side changed take that side's change, and lines both sides changed
+/// differently become conflicts, written between markers.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::diff::{ConflictStyle, ThreeWayMerge};
+///
+/// let base = "port = 80\nhost = a\nlog = off\n";
+/// let ours = "port = 8080\nhost = a\nlog = off\n";
+/// let theirs = "port = 80\nhost = a\nlog = on\n";
+///
+/// let merged = ThreeWayMerge::new().merge(base, ours, theirs);
+/// assert!(merged.is_clean());
+/// assert_eq!(merged.text, "port = 8080\nhost = a\nlog = on\n");
+///
+/// let theirs = "port = 443\nhost = a\nlog = off\n";
+/// let merged = ThreeWayMerge::new()
+///     .style(ConflictStyle::Diff3)
+///     .labels("local", "base", "upstream")
+///     .merge(base, ours, theirs);
+///
+/// assert_eq!(
+///     merged.text,
+///     "<<<<<<< local\nport = 8080\n||||||| base\nport = 80\n=======\nport = 443\n>>>>>>> upstream\nhost = a\nlog = off\n"
+/// );
+/// assert_eq!(merged.conflicts[0].base, 0..1);
+/// assert_eq!(merged.conflicts[0].merged, 0..7);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ThreeWayMerge {
+    style: ConflictStyle,
+    marker_len: usize,
+    labels: [String; 3],
+    resolve_identical: bool,
+    ignore_whitespace: bool,
+}
+
+impl Default for ThreeWayMerge {
+    fn default() -> Self {
+        ThreeWayMerge {
+            style: ConflictStyle::Merge,
+            marker_len: 7,
+            labels: ["ours".to_string(), "base".to_string(), "theirs".to_string()],
+            resolve_identical: true,
+            ignore_whitespace: false,
+        }
+    }
+}
+
+impl ThreeWayMerge {
+    /// This will return a new ThreeWayMerge writing conflicts in the merge style with 7 character markers labelled
+    /// "ours", "base" and "theirs". Changes both sides made the same way are taken, whitespace counts.
+    pub fn new() -> Self {
+        ThreeWayMerge::default()
+    }
+
+    /// Write conflicts in the given style
+    pub fn style(mut self, style: ConflictStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Make conflict markers the given number of characters long
+    pub fn marker_len(mut self, len: usize) -> Self {
+        self.marker_len = len;
+        self
+    }
+
+    /// Label the conflict markers of each side
+    pub fn labels(mut self, ours: &str, base: &str, theirs: &str) -> Self {
+        self.labels = [ours.to_string(), base.to_string(), theirs.to_string()];
+        self
+    }
+
+    /// Choose whether a change both sides made the same way is taken, or left as a conflict
+    pub fn resolve_identical(mut self, resolve: bool) -> Self {
+        self.resolve_identical = resolve;
+        self
+    }
+
+    /// Choose whether changes to whitespace alone are ignored. When one side only changed whitespace the other
+    /// side's change is taken, and when both did ours is taken.
+    pub fn ignore_whitespace(mut self, ignore: bool) -> Self {
+        self.ignore_whitespace = ignore;
+        self
+    }
+
+    /// Merge our changes and their changes to the base text
+    pub fn merge(&self, base: &str, ours: &str, theirs: &str) -> Merged {
+        let (base, ours, theirs) = (lines(base), lines(ours), lines(theirs));
+        let ours_at = matches(&base, &ours);
+        let theirs_at = matches(&base, &theirs);
+
+        let mut merged = Merged {
+            text: String::new(),
+            conflicts: Vec::new(),
+        };
+        let mut written = 0;
+        let (mut b, mut o, mut t) = (0, 0, 0);
+
+        loop {
+            // lines all three share are copied over
+            while b < base.len() && ours_at[b] == Some(o) && theirs_at[b] == Some(t) {
+                merged.text.push_str(base[b]);
+                written += 1;
+                b += 1;
+                o += 1;
+                t += 1;
+            }
+            if b == base.len() && o == ours.len() && t == theirs.len() {
+                break;
+            }
+
+            // the changed chunk runs up to the next base line both sides kept
+            let (end_b, end_o, end_t) = (b..base.len())
+                .find_map(|i| match (ours_at[i], theirs_at[i]) {
+                    (Some(end_o), Some(end_t)) => Some((i, end_o, end_t)),
+                    _ => None,
+                })
+                .unwrap_or((base.len(), ours.len(), theirs.len()));
+
+            let chunk = [&base[b..end_b], &ours[o..end_o], &theirs[t..end_t]];
+            match self.resolve(chunk) {
+                Some(taken) => {
+                    taken.iter().for_each(|line| merged.text.push_str(line));
+                    written += taken.len();
+                }
+                None => {
+                    let start = written;
+                    written += self.write_conflict(&mut merged.text, chunk);
+                    merged.conflicts.push(Conflict {
+                        base: b..end_b,
+                        ours: o..end_o,
+                        theirs: t..end_t,
+                        merged: start..written,
+                    });
+                }
+            }
+
+            b = end_b;
+            o = end_o;
+            t = end_t;
+        }
+
+        merged
+    }
+
+    // the lines to take for a chunk of base, our and their lines, or None if it is a conflict
+    fn resolve<'a>(&self, [base, ours, theirs]: [&'a [&'a str]; 3]) -> Option<&'a [&'a str]> {
+        if ours == base {
+            return Some(theirs);
+        }
+        if theirs == base {
+            return Some(ours);
+        }
+        if ours == theirs && self.resolve_identical {
+            return Some(ours);
+        }
+
+        if self.ignore_whitespace {
+            let (base, ours_kept, theirs_kept) = (words(base), words(ours), words(theirs));
+            if theirs_kept == base || (ours_kept == theirs_kept && self.resolve_identical) {
+                return Some(ours);
+            }
+            if ours_kept == base {
+                return Some(theirs);
+            }
+        }
+
+        None
+    }
+
+    // write a conflict between markers, returning how many lines it took
+    fn write_conflict(&self, text: &mut String, [base, ours, theirs]: [&[&str]; 3]) -> usize {
+        let [ours_label, base_label, theirs_label] = &self.labels;
+        let mut written = 0;
+        let mut write_lines = |text: &mut String, marker: Option<(char, &str)>, lines: &[&str]| {
+            if let Some((marker, label)) = marker {
+                let line = marker.to_string().repeat(self.marker_len);
+                text.push_str(line.trim_end());
+                if !label.is_empty() {
+                    text.push(' ');
+                    text.push_str(label);
+                }
+                text.push('\n');
+                written += 1;
+            }
+            for line in lines.iter() {
+                text.push_str(line);
+                // a last line without a newline would run into the marker after it
+                if !line.ends_with('\n') {
+                    text.push('\n');
+                }
+            }
+            written += lines.len();
+        };
+
+        write_lines(text, Some(('<', ours_label.as_str())), ours);
+        if self.style == ConflictStyle::Diff3 {
+            write_lines(text, Some(('|', base_label.as_str())), base);
+        }
+        write_lines(text, Some(('=', "")), theirs);
+        write_lines(text, Some(('>', theirs_label.as_str())), &[]);
+
+        written
+    }
+}
+
+// for every line of the base, the line of the other text it is kept as, if it was kept
+fn matches(base: &[&str], other: &[&str]) -> Vec<Option<usize>> {
+    let mut at = vec![None; base.len()];
+    for hunk in diff(base, other).iter() {
+        if let Hunk::Equal { old, new, len } = *hunk {
+            for k in 0..len {
+                at[old + k] = Some(new + k);
+            }
+        }
+    }
+    at
+}
+
+// the words of each line that is not blank, so lines that differ only in whitespace compare equal
+fn words<'a>(lines: &[&'a str]) -> Vec<Vec<&'a str>> {
+    lines
+        .iter()
+        .map(|line| line.split_whitespace().collect::<Vec<&str>>())
+        .filter(|words| !words.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::random;
+
+    #[test]
+    fn merge_one_sided_changes() {
+        use super::*;
+
+        let mut state = 19;
+        let words = ["a\n", "b\n", "c\n", "d\n"];
+        for _ in 0..200 {
+            let text = |state: &mut u64| -> String {
+                (0..random(state, 12))
+                    .map(|_| words[random(state, 4) as usize])
+                    .collect()
+            };
+            let (base, changed) = (text(&mut state), text(&mut state));
+
+            let merged = ThreeWayMerge::new().merge(&base, &base, &changed);
+            assert!(merged.is_clean());
+            assert_eq!(merged.text, changed);
+
+            let merged = ThreeWayMerge::new().merge(&base, &changed, &base);
+            assert!(merged.is_clean());
+            assert_eq!(merged.text, changed);
+
+            let merged = ThreeWayMerge::new().merge(&base, &changed, &changed);
+            assert!(merged.is_clean());
+            assert_eq!(merged.text, changed);
+        }
+    }
+
+    #[test]
+    fn merge_conflicts() {
+        use super::*;
+
+        let base = "a\nb\nc\nd\ne\n";
+        let ours = "a\nB\nc\nd\nE\n";
+        let theirs = "a\nb2\nc\nd\nE\n";
+
+        let merged = ThreeWayMerge::new().merge(base, ours, theirs);
+        assert_eq!(
+            merged.text,
+            "a\n<<<<<<< ours\nB\n=======\nb2\n>>>>>>> theirs\nc\nd\nE\n"
+        );
+        assert_eq!(
+            merged.conflicts,
+            vec![Conflict {
+                base: 1..2,
+                ours: 1..2,
+                theirs: 1..2,
+                merged: 1..6
+            }]
+        );
+
+        // without resolving identical changes, the E both sides made is a conflict too
+        let merged = ThreeWayMerge::new()
+            .resolve_identical(false)
+            .marker_len(3)
+            .labels("", "", "")
+            .merge(base, ours, theirs);
+        assert_eq!(merged.conflicts.len(), 2);
+        assert_eq!(merged.conflicts[1].merged, 8..13);
+        assert_eq!(
+            merged.text,
+            "a\n<<<\nB\n===\nb2\n>>>\nc\nd\n<<<\nE\n===\nE\n>>>\n"
+        );
+
+        // an empty side of a conflict, and a last line without a newline
+        let merged = ThreeWayMerge::new()
+            .style(ConflictStyle::Diff3)
+            .merge("x\n", "", "y");
+        assert_eq!(
+            merged.text,
+            "<<<<<<< ours\n||||||| base\nx\n=======\ny\n>>>>>>> theirs\n"
+        );
+    }
+
+    #[test]
+    fn merge_ignoring_whitespace() {
+        use super::*;
+
+        let base = "[server]\nport = 80\nhost = a\n";
+        let ours = "[server]\nport  =  80\n\nhost = a\n";
+        let theirs = "[server]\nport = 8080\nhost = a\n";
+
+        assert!(!ThreeWayMerge::new().merge(base, ours, theirs).is_clean());
+
+        let merged = ThreeWayMerge::new()
+            .ignore_whitespace(true)
+            .merge(base, ours, theirs);
+        assert!(merged.is_clean());
+        assert_eq!(merged.text, "[server]\nport = 8080\nhost = a\n");
+
+        // both reformatted the line differently, ours is kept
+        let theirs = "[server]\n  port = 80\nhost = a\n";
+        let merged = ThreeWayMerge::new()
+            .ignore_whitespace(true)
+            .merge(base, ours, theirs);
+        assert!(merged.is_clean());
+        assert_eq!(merged.text, ours);
+    }
+}
@@ -0,0 +1,431 @@
+//! Differences between two sequences, as an edit script of hunks that turns the old sequence into the new one. The
+//! sequences can hold any values that compare with `Eq`, such as the lines of two files, tokens or bytes.
+//!
+//! `diff` uses Myers' O(ND) algorithm with its linear space refinement, so the time taken grows with the size of the
+//! inputs times the number of differences D, and only O(N) memory is needed however large the inputs are. Patience
+//! and histogram diff, through the `DiffAlgorithm` trait, give scripts that read better on code at some cost in
+//! length.
+//!
+//! An edit script over lines can be rendered as a unified or context diff with `DiffFormat`, and a unified diff can
+//! be read back and applied to a text that has since changed with `Patch`. Two texts changed from the same base can
+//! be merged with `ThreeWayMerge`.
+//!
+//! # Examples
+//! ```
+//! use rs_algo::compare::diff::{self, Hunk};
+//!
+//! let old: Vec<&str> = "port = 80\nhost = a\nlog = off\n".lines().collect();
+//! let new: Vec<&str> = "port = 80\nhost = b\nlog = off\nretry = 3\n".lines().collect();
+//!
+//! let hunks = diff::diff(&old, &new);
+//! assert_eq!(
+//!     hunks,
+//!     vec![
+//!         Hunk::Equal { old: 0, new: 0, len: 1 },
+//!         Hunk::Delete { old: 1, new: 1, len: 1 },
+//!         Hunk::Insert { old: 2, new: 1, len: 1 },
+//!         Hunk::Equal { old: 2, new: 2, len: 1 },
+//!         Hunk::Insert { old: 3, new: 3, len: 1 },
+//!     ]
+//! );
+//!
+//! let changed: Vec<&str> = hunks
+//!     .iter()
+//!     .filter(|hunk| matches!(hunk, Hunk::Insert { .. }))
+//!     .flat_map(|hunk| new[hunk.new_range()].iter().copied())
+//!     .collect();
+//! assert_eq!(changed, vec!["host = b", "retry = 3"]);
+//! ```
+pub use self::format::{lines, DiffFormat};
+pub use self::histogram::Histogram;
+pub use self::merge::{Conflict, ConflictStyle, Merged, ThreeWayMerge};
+pub use self::myers::Myers;
+pub use self::patch::{Applied, HunkResult, Patch, PatchHunk, PatchLine};
+pub use self::patience::Patience;
+
+use std::hash::Hash;
+use std::ops::Range;
+
+mod format;
+mod histogram;
+mod merge;
+mod myers;
+mod patch;
+mod patience;
+
+/// One step of an edit script. Every hunk covers `len` values and records where it is in both sequences: `old` is an
+/// index into the old sequence and `new` an index into the new one.
+///
+/// A run of changes between two equal hunks is given as at most one `Delete` followed by at most one `Insert`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Hunk {
+    /// old[old..old + len] is the same as new[new..new + len]
+    Equal { old: usize, new: usize, len: usize },
+    /// old[old..old + len] is removed, the new sequence carries on at new
+    Delete { old: usize, new: usize, len: usize },
+    /// new[new..new + len] is added, before old[old]
+    Insert { old: usize, new: usize, len: usize },
+}
+
+impl Hunk {
+    /// The number of values the hunk covers
+    pub fn len(&self) -> usize {
+        match *self {
+            Hunk::Equal { len, .. } | Hunk::Delete { len, .. } | Hunk::Insert { len, .. } => len,
+        }
+    }
+
+    /// Return true if the hunk covers no values
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The values of the old sequence the hunk covers, empty for an insert
+    pub fn old_range(&self) -> Range<usize> {
+        match *self {
+            Hunk::Equal { old, len, .. } | Hunk::Delete { old, len, .. } => old..old + len,
+            Hunk::Insert { old, .. } => old..old,
+        }
+    }
+
+    /// The values of the new sequence the hunk covers, empty for a delete
+    pub fn new_range(&self) -> Range<usize> {
+        match *self {
+            Hunk::Equal { new, len, .. } | Hunk::Insert { new, len, .. } => new..new + len,
+            Hunk::Delete { new, .. } => new..new,
+        }
+    }
+}
+
+/// Return the shortest edit script turning old into new, as hunks in order. Equal runs that are next to each other
+/// are joined, and so are the deletes and inserts between them.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::diff::{self, Hunk};
+///
+/// let hunks = diff::diff(b"ABCABBA", b"CBABAC");
+///
+/// // the common values of a shortest edit script are a longest common subsequence
+/// let kept: usize = hunks.iter().filter(|hunk| matches!(hunk, Hunk::Equal { .. })).map(Hunk::len).sum();
+/// assert_eq!(kept, 4);
+/// ```
+pub fn diff<T>(old: &[T], new: &[T]) -> Vec<Hunk>
+where
+    T: Eq,
+{
+    let mut hunks = HunkBuilder::default();
+    myers::diff(old, 0..old.len(), new, 0..new.len(), &mut hunks);
+    hunks.finish()
+}
+
+/// A way of finding an edit script between two sequences. `Myers` finds a shortest script, `Patience` and
+/// `Histogram` line up rare values first so the script follows the structure of the text, which reads better when
+/// reviewing code.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::diff::{self, DiffAlgorithm, DiffFormat, Histogram, Patience};
+///
+/// let old = diff::lines("fn d() {\n    four();\n}\n\nfn a() {\n    one();\n}\n");
+/// let new = diff::lines("fn a() {\n    one();\n}\n\nfn b() {\n    two();\n}\n");
+/// let format = DiffFormat::new().context(0);
+///
+/// // myers keeps the most lines by pairing up the braces, so fn d turns into fn a and fn a into fn b
+/// let myers = format.unified(&old, &new, &diff::diff(&old, &new));
+/// assert!(myers.contains("-fn d() {\n-    four();\n+fn a() {\n+    one();\n"));
+///
+/// // patience and histogram keep fn a whole, fn d is removed and fn b added
+/// let expected = "--- a\n+++ b\n@@ -1,4 +0,0 @@\n-fn d() {\n-    four();\n-}\n-\n@@ -6,0 +3,4 @@\n+}\n+\n+fn b() {\n+    two();\n";
+/// assert_eq!(format.unified(&old, &new, &Patience.diff(&old, &new)), expected);
+/// assert_eq!(format.unified(&old, &new, &Histogram.diff(&old, &new)), expected);
+/// ```
+pub trait DiffAlgorithm {
+    /// Return an edit script turning old into new, as hunks in the same form `diff` gives
+    fn diff<T>(&self, old: &[T], new: &[T]) -> Vec<Hunk>
+    where
+        T: Eq + Hash;
+}
+
+// how deeply patience and histogram diff split a range inside another before the rest of it is left to Myers'
+// algorithm, which bounds their stack depth
+const MAX_DEPTH: usize = 64;
+
+fn common_prefix<T>(old: &[T], new: &[T]) -> usize
+where
+    T: Eq,
+{
+    old.iter()
+        .zip(new.iter())
+        .take_while(|(a, b)| a == b)
+        .count()
+}
+
+fn common_suffix<T>(old: &[T], new: &[T]) -> usize
+where
+    T: Eq,
+{
+    old.iter()
+        .rev()
+        .zip(new.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count()
+}
+
+// collects the steps of an edit script into hunks, joining neighbours and putting deletes before inserts
+#[derive(Debug, Default)]
+struct HunkBuilder {
+    hunks: Vec<Hunk>,
+    // where the current run of changes starts
+    old: usize,
+    new: usize,
+    deleted: usize,
+    inserted: usize,
+}
+
+impl HunkBuilder {
+    fn equal(&mut self, old: usize, new: usize, len: usize) {
+        if len == 0 {
+            return;
+        }
+        self.flush();
+
+        if let Some(Hunk::Equal { len: last, .. }) = self.hunks.last_mut() {
+            *last += len;
+        } else {
+            self.hunks.push(Hunk::Equal { old, new, len });
+        }
+        self.old = old + len;
+        self.new = new + len;
+    }
+
+    fn delete(&mut self, len: usize) {
+        self.deleted += len;
+    }
+
+    fn insert(&mut self, len: usize) {
+        self.inserted += len;
+    }
+
+    fn flush(&mut self) {
+        if self.deleted > 0 {
+            self.hunks.push(Hunk::Delete {
+                old: self.old,
+                new: self.new,
+                len: self.deleted,
+            });
+        }
+        if self.inserted > 0 {
+            self.hunks.push(Hunk::Insert {
+                old: self.old + self.deleted,
+                new: self.new,
+                len: self.inserted,
+            });
+        }
+
+        self.old += self.deleted;
+        self.new += self.inserted;
+        self.deleted = 0;
+        self.inserted = 0;
+    }
+
+    fn finish(mut self) -> Vec<Hunk> {
+        self.flush();
+        self.hunks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Hunk;
+    use crate::test_util::{random, random_vec};
+
+    // check the hunks are a well formed script that turns old into new, returning how many values were kept
+    fn check_script<T>(old: &[T], new: &[T], hunks: &[Hunk]) -> usize
+    where
+        T: Eq + Clone + std::fmt::Debug,
+    {
+        let mut rebuilt = Vec::new();
+        let (mut at_old, mut at_new) = (0, 0);
+        let mut kept = 0;
+
+        for (i, hunk) in hunks.iter().enumerate() {
+            assert!(!hunk.is_empty());
+            assert_eq!(
+                (hunk.old_range().start, hunk.new_range().start),
+                (at_old, at_new)
+            );
+            match hunk {
+                Hunk::Equal { .. } => {
+                    assert_eq!(old[hunk.old_range()], new[hunk.new_range()]);
+                    rebuilt.extend_from_slice(&old[hunk.old_range()]);
+                    kept += hunk.len();
+                }
+                Hunk::Insert { .. } => rebuilt.extend_from_slice(&new[hunk.new_range()]),
+                Hunk::Delete { .. } => {
+                    // a delete is never straight after an insert
+                    assert!(i == 0 || !matches!(hunks[i - 1], Hunk::Insert { .. }));
+                }
+            }
+            if i > 0 {
+                assert!(std::mem::discriminant(hunk) != std::mem::discriminant(&hunks[i - 1]));
+            }
+            at_old = hunk.old_range().end;
+            at_new = hunk.new_range().end;
+        }
+
+        assert_eq!((at_old, at_new), (old.len(), new.len()));
+        assert_eq!(rebuilt, new);
+        kept
+    }
+
+    #[test]
+    fn diff_is_shortest() {
+        use super::*;
+        use crate::compare::LCSubsequence;
+
+        let mut state = 29;
+        for _ in 0..300 {
+            let old = random_vec(&mut state, 40, 4);
+            let new = random_vec(&mut state, 40, 4);
+
+            let kept = check_script(&old, &new, &diff(&old, &new));
+            assert_eq!(
+                kept,
+                LCSubsequence::new(&old, &new).subsequence_len as usize
+            );
+        }
+    }
+
+    #[test]
+    fn diff_algorithms_give_valid_scripts() {
+        use super::*;
+
+        let mut state = 31;
+        for _ in 0..300 {
+            // a small alphabet makes for many repeated values, a large one for many unique ones
+            let alphabet = 2 + random(&mut state, 30);
+            let old = random_vec(&mut state, 50, alphabet);
+            let new = random_vec(&mut state, 50, alphabet);
+
+            let shortest = check_script(&old, &new, &Myers.diff(&old, &new));
+            assert!(check_script(&old, &new, &Patience.diff(&old, &new)) <= shortest);
+            assert!(check_script(&old, &new, &Histogram.diff(&old, &new)) <= shortest);
+            assert_eq!(Myers.diff(&old, &new), diff(&old, &new));
+        }
+    }
+
+    #[test]
+    fn histogram_prefers_rare_lines() {
+        use super::*;
+
+        // "}" and "" are common, "end" is not, so the histogram diff keeps the run around "end"
+        let old = ["}", "", "a", "}", "", "end", "}"];
+        let new = ["b", "}", "", "end", "}", "c"];
+        let hunks = Histogram.diff(&old, &new);
+        check_script(&old, &new, &hunks);
+        assert!(hunks.contains(&Hunk::Equal {
+            old: 3,
+            new: 1,
+            len: 4
+        }));
+    }
+
+    #[test]
+    fn diff_edges() {
+        use super::*;
+
+        let empty: [char; 0] = [];
+        assert!(diff(&empty, &empty).is_empty());
+        assert_eq!(
+            diff(&empty, &['a', 'b']),
+            vec![Hunk::Insert {
+                old: 0,
+                new: 0,
+                len: 2
+            }]
+        );
+        assert_eq!(
+            diff(&['a', 'b'], &empty),
+            vec![Hunk::Delete {
+                old: 0,
+                new: 0,
+                len: 2
+            }]
+        );
+        assert_eq!(
+            diff(&[1, 2, 3], &[1, 2, 3]),
+            vec![Hunk::Equal {
+                old: 0,
+                new: 0,
+                len: 3
+            }]
+        );
+        assert_eq!(
+            diff(&[1, 2], &[3, 4]),
+            vec![
+                Hunk::Delete {
+                    old: 0,
+                    new: 0,
+                    len: 2
+                },
+                Hunk::Insert {
+                    old: 2,
+                    new: 0,
+                    len: 2
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_large_inputs() {
+        use super::*;
+
+        // far too large for a grid, with a few scattered edits
+        let old: Vec<u32> = (0..200_000).collect();
+        let mut new = old.clone();
+        new.remove(150_000);
+        new.insert(90_000, 7);
+        new[10] = 1_000_000;
+        new.truncate(199_000);
+
+        let hunks = diff(&old, &new);
+        let kept = check_script(&old, &new, &hunks);
+        assert_eq!(kept, 200_000 - 1 - 1 - 1000);
+    }
+
+    #[test]
+    fn patience_histogram_large_inputs() {
+        use super::*;
+
+        let old: Vec<u32> = (0..200_000).collect();
+        let mut new = old.clone();
+        new.remove(150_000);
+        new.insert(90_000, 7);
+        new[10] = 1_000_000;
+        new.truncate(199_000);
+
+        let kept = 200_000 - 1 - 1 - 1000;
+        assert_eq!(check_script(&old, &new, &Patience.diff(&old, &new)), kept);
+        assert_eq!(check_script(&old, &new, &Histogram.diff(&old, &new)), kept);
+
+        // a new line after every line splits the ranges one line at a time, which must neither run out of stack nor
+        // rescan the rest of the inputs for every line
+        let old: Vec<u32> = (0..20_000).collect();
+        let new: Vec<u32> = old.iter().flat_map(|i| [*i, 1_000_000 + i]).collect();
+        assert_eq!(check_script(&old, &new, &Patience.diff(&old, &new)), 20_000);
+        assert_eq!(
+            check_script(&old, &new, &Histogram.diff(&old, &new)),
+            20_000
+        );
+
+        // and the same before every line
+        let new: Vec<u32> = old.iter().flat_map(|i| [1_000_000 + i, *i]).collect();
+        assert_eq!(check_script(&old, &new, &Patience.diff(&old, &new)), 20_000);
+        assert_eq!(
+            check_script(&old, &new, &Histogram.diff(&old, &new)),
+            20_000
+        );
+    }
+}
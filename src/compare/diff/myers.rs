@@ -0,0 +1,202 @@
+use std::ops::{Index, IndexMut, Range};
+
+use std::hash::Hash;
+
+use super::{common_prefix, common_suffix, DiffAlgorithm, Hunk, HunkBuilder};
+
+// the furthest x reached on each diagonal k = x - y, indexed from -max to max
+struct Diagonals {
+    offset: isize,
+    x: Vec<usize>,
+}
+
+impl Diagonals {
+    fn new(max: usize) -> Self {
+        Diagonals {
+            offset: max as isize + 1,
+            x: vec![0; 2 * max + 3],
+        }
+    }
+}
+
+impl Index<isize> for Diagonals {
+    type Output = usize;
+
+    fn index(&self, k: isize) -> &usize {
+        &self.x[(k + self.offset) as usize]
+    }
+}
+
+impl IndexMut<isize> for Diagonals {
+    fn index_mut(&mut self, k: isize) -> &mut usize {
+        &mut self.x[(k + self.offset) as usize]
+    }
+}
+
+// the most differences a path through n old and m new values can need before the two searches meet
+fn max_d(n: usize, m: usize) -> usize {
+    (n + m).div_ceil(2) + 1
+}
+
+/// Myers' O(ND) algorithm with its linear space refinement. It finds a shortest edit script, keeping as many values
+/// as possible, but on code that can mean lining up braces and blank lines from unrelated blocks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Myers;
+
+impl DiffAlgorithm for Myers {
+    fn diff<T>(&self, old: &[T], new: &[T]) -> Vec<Hunk>
+    where
+        T: Eq + Hash,
+    {
+        let mut hunks = HunkBuilder::default();
+        diff(old, 0..old.len(), new, 0..new.len(), &mut hunks);
+        hunks.finish()
+    }
+}
+
+// write the shortest edit script turning old[old_range] into new[new_range] to the builder
+pub(super) fn diff<T>(
+    old: &[T],
+    old_range: Range<usize>,
+    new: &[T],
+    new_range: Range<usize>,
+    hunks: &mut HunkBuilder,
+) where
+    T: Eq,
+{
+    let max = max_d(old_range.len(), new_range.len());
+    let mut forward = Diagonals::new(max);
+    let mut backward = Diagonals::new(max);
+
+    conquer(
+        old,
+        old_range,
+        new,
+        new_range,
+        &mut forward,
+        &mut backward,
+        hunks,
+    );
+}
+
+// split the ranges at the middle snake and solve both halves, the linear space refinement of Myers' algorithm
+fn conquer<T>(
+    old: &[T],
+    old_range: Range<usize>,
+    new: &[T],
+    new_range: Range<usize>,
+    forward: &mut Diagonals,
+    backward: &mut Diagonals,
+    hunks: &mut HunkBuilder,
+) where
+    T: Eq,
+{
+    let (mut old_range, mut new_range) = (old_range, new_range);
+
+    let prefix = common_prefix(&old[old_range.clone()], &new[new_range.clone()]);
+    hunks.equal(old_range.start, new_range.start, prefix);
+    old_range.start += prefix;
+    new_range.start += prefix;
+
+    let suffix = common_suffix(&old[old_range.clone()], &new[new_range.clone()]);
+    old_range.end -= suffix;
+    new_range.end -= suffix;
+
+    if old_range.is_empty() || new_range.is_empty() {
+        hunks.delete(old_range.len());
+        hunks.insert(new_range.len());
+    } else {
+        let (x, y) = middle_snake(
+            &old[old_range.clone()],
+            &new[new_range.clone()],
+            forward,
+            backward,
+        );
+        let (x, y) = (old_range.start + x, new_range.start + y);
+
+        conquer(
+            old,
+            old_range.start..x,
+            new,
+            new_range.start..y,
+            forward,
+            backward,
+            hunks,
+        );
+        conquer(
+            old,
+            x..old_range.end,
+            new,
+            y..new_range.end,
+            forward,
+            backward,
+            hunks,
+        );
+    }
+
+    hunks.equal(old_range.end, new_range.end, suffix);
+}
+
+// search from both ends at once until the paths meet, returning a point on a shortest path that splits the problem
+// in two. The sequences are not empty and differ at both ends.
+fn middle_snake<T>(
+    old: &[T],
+    new: &[T],
+    forward: &mut Diagonals,
+    backward: &mut Diagonals,
+) -> (usize, usize)
+where
+    T: Eq,
+{
+    let (n, m) = (old.len(), new.len());
+    let delta = n as isize - m as isize;
+    let odd = delta & 1 == 1;
+
+    forward[1] = 0;
+    backward[1] = 0;
+
+    for d in 0..max_d(n, m) as isize {
+        for k in (-d..=d).rev().step_by(2) {
+            let mut x = if k == -d || (k != d && forward[k - 1] < forward[k + 1]) {
+                forward[k + 1]
+            } else {
+                forward[k - 1] + 1
+            };
+            let y = (x as isize - k) as usize;
+            let start = (x, y);
+
+            if x < n && y < m {
+                x += common_prefix(&old[x..], &new[y..]);
+            }
+            forward[k] = x;
+
+            // with an odd delta the paths can only meet after a forward step
+            if odd && (k - delta).abs() < d && forward[k] + backward[delta - k] >= n {
+                return start;
+            }
+        }
+
+        for k in (-d..=d).rev().step_by(2) {
+            let mut x = if k == -d || (k != d && backward[k - 1] < backward[k + 1]) {
+                backward[k + 1]
+            } else {
+                backward[k - 1] + 1
+            };
+            let mut y = (x as isize - k) as usize;
+
+            if x < n && y < m {
+                let len = common_suffix(&old[..n - x], &new[..m - y]);
+                x += len;
+                y += len;
+            }
+            backward[k] = x;
+
+            if !odd && (k - delta).abs() <= d && backward[k] + forward[delta - k] >= n {
+                return (n - x, m - y);
+            }
+        }
+    }
+
+    // a path of at most n + m differences always exists, so the searches meet before running out of diagonals
+    unreachable!("the middle snake searches did not meet")
+}
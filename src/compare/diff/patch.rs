@@ -0,0 +1,581 @@
+use std::fmt::{Display, Error, Formatter};
+
+use super::format::{lines, push_line};
+
+/// One line of a patch hunk, holding the text of the line with its line ending
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PatchLine {
+    /// a line the old and new text share
+    Context(String),
+    /// a line only in the old text
+    Delete(String),
+    /// a line only in the new text
+    Insert(String),
+}
+
+impl PatchLine {
+    /// The text of the line
+    pub fn text(&self) -> &str {
+        match self {
+            PatchLine::Context(text) | PatchLine::Delete(text) | PatchLine::Insert(text) => text,
+        }
+    }
+}
+
+/// One hunk of a unified diff. The starts are line numbers counting from 1, as in the `@@ -1,4 +1,5 @@` header, and
+/// a hunk that covers no lines of a side starts at the line before it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PatchHunk {
+    pub old_start: usize,
+    pub old_len: usize,
+    pub new_start: usize,
+    pub new_len: usize,
+    /// the text after the header, such as the function the hunk is in
+    pub heading: String,
+    pub lines: Vec<PatchLine>,
+}
+
+impl PatchHunk {
+    // the lines the hunk expects to find and the lines it puts in their place
+    fn sides(&self) -> (Vec<&str>, Vec<&str>) {
+        let mut old = Vec::new();
+        let mut new = Vec::new();
+
+        for line in self.lines.iter() {
+            match line {
+                PatchLine::Context(text) => {
+                    old.push(text.as_str());
+                    new.push(text.as_str());
+                }
+                PatchLine::Delete(text) => old.push(text.as_str()),
+                PatchLine::Insert(text) => new.push(text.as_str()),
+            }
+        }
+
+        (old, new)
+    }
+}
+
+impl Display for PatchHunk {
+    /// Print the hunk in the unified format
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        let range = |start: usize, len: usize| match len {
+            1 => format!("{}", start),
+            _ => format!("{},{}", start, len),
+        };
+
+        write!(
+            f,
+            "@@ -{} +{} @@",
+            range(self.old_start, self.old_len),
+            range(self.new_start, self.new_len)
+        )?;
+        if !self.heading.is_empty() {
+            write!(f, " {}", self.heading)?;
+        }
+        writeln!(f)?;
+
+        let mut out = String::new();
+        for line in self.lines.iter() {
+            let prefix = match line {
+                PatchLine::Context(_) => " ",
+                PatchLine::Delete(_) => "-",
+                PatchLine::Insert(_) => "+",
+            };
+            push_line(&mut out, prefix, line.text());
+        }
+        write!(f, "{}", out)
+    }
+}
+
+/// How one hunk went when a patch was applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HunkResult {
+    /// The hunk was applied `offset` lines away from where its header put it, ignoring `fuzz` lines of context at
+    /// each end
+    Applied { offset: isize, fuzz: usize },
+    /// The hunk's lines could not be found, it was left out
+    Rejected,
+}
+
+/// The outcome of applying a patch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Applied {
+    /// the patched text, with every hunk that could be placed applied
+    pub text: String,
+    /// how each hunk of the patch went, in order
+    pub results: Vec<HunkResult>,
+    /// the hunks that could not be placed, to be written to a reject file or shown to the user
+    pub rejected: Vec<PatchHunk>,
+}
+
+impl Applied {
+    /// Return true if every hunk applied exactly where its header said, with all of its context
+    pub fn is_clean(&self) -> bool {
+        self.results
+            .iter()
+            .all(|result| *result == HunkResult::Applied { offset: 0, fuzz: 0 })
+    }
+}
+
+/// A unified diff of one file, as made by `DiffFormat::patch` or read with `Patch::parse`. It can be applied to a
+/// text that has drifted from the one it was made from: hunks are looked for near where their header puts them, and
+/// with fuzz some of their context lines may differ.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::diff::{HunkResult, Patch};
+///
+/// let patch = Patch::parse(
+///     "--- app.conf\n+++ app.conf\n@@ -1,3 +1,3 @@\n [server]\n-port = 80\n+port = 8080\n host = a\n",
+/// )
+/// .unwrap();
+///
+/// // the file gained a comment since the patch was made
+/// let applied = patch.apply("# prod\n[server]\nport = 80\nhost = a\n", 0);
+///
+/// assert_eq!(applied.text, "# prod\n[server]\nport = 8080\nhost = a\n");
+/// assert_eq!(applied.results, vec![HunkResult::Applied { offset: 1, fuzz: 0 }]);
+/// assert!(applied.rejected.is_empty());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Patch {
+    /// the name from the `---` line, without any timestamp
+    pub old_name: Option<String>,
+    /// the name from the `+++` line, without any timestamp
+    pub new_name: Option<String>,
+    pub hunks: Vec<PatchHunk>,
+}
+
+impl Display for Patch {
+    /// Print the patch in the unified format
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        if self.old_name.is_some() || self.new_name.is_some() {
+            writeln!(f, "--- {}", self.old_name.as_deref().unwrap_or(""))?;
+            writeln!(f, "+++ {}", self.new_name.as_deref().unwrap_or(""))?;
+        }
+        for hunk in self.hunks.iter() {
+            write!(f, "{}", hunk)?;
+        }
+        Ok(())
+    }
+}
+
+impl Patch {
+    /// Read a unified diff of one file. Lines before the `---` header, like a `diff --git` line, are skipped. An
+    /// error is returned if a hunk is malformed or the text holds patches for more than one file.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut patches = Patch::parse_all(text)?;
+        match patches.len() {
+            0 => Err("no patch found".to_string()),
+            1 => Ok(patches.remove(0)),
+            _ => Err("the text holds patches for more than one file".to_string()),
+        }
+    }
+
+    /// Read a unified diff of any number of files, such as the output of `diff -ru` or `git diff`. An error is
+    /// returned if a hunk is malformed.
+    pub fn parse_all(text: &str) -> Result<Vec<Self>, String> {
+        let lines = lines(text);
+        let mut patches: Vec<Patch> = Vec::new();
+        let mut i = 0;
+
+        while i < lines.len() {
+            let line = lines[i];
+
+            if line.starts_with("--- ")
+                && lines
+                    .get(i + 1)
+                    .is_some_and(|next| next.starts_with("+++ "))
+            {
+                patches.push(Patch {
+                    old_name: Some(file_name(&line[4..])),
+                    new_name: Some(file_name(&lines[i + 1][4..])),
+                    hunks: Vec::new(),
+                });
+                i += 2;
+            } else if line.starts_with("@@ ") {
+                if patches.is_empty() {
+                    patches.push(Patch {
+                        old_name: None,
+                        new_name: None,
+                        hunks: Vec::new(),
+                    });
+                }
+                let (hunk, next) = parse_hunk(&lines, i)?;
+                patches.last_mut().unwrap().hunks.push(hunk);
+                i = next;
+            } else {
+                i += 1;
+            }
+        }
+
+        Ok(patches)
+    }
+
+    /// Apply the patch to a text. Each hunk is looked for at the line its header gives, moved by how far the hunks
+    /// before it were found from theirs, and then at lines further and further away. If its lines can not be found
+    /// anywhere, up to `fuzz` lines of context at each end are ignored and the search is repeated. Hunks that still
+    /// can not be found are rejected and the rest of the patch is applied without them.
+    pub fn apply(&self, text: &str, fuzz: usize) -> Applied {
+        let original = lines(text);
+        let mut text = String::with_capacity(text.len());
+        let mut results = Vec::with_capacity(self.hunks.len());
+        let mut rejected = Vec::new();
+
+        // the original lines before this have been written out, hunks can not be placed before it
+        let mut done = 0;
+        let mut drift: isize = 0;
+
+        for hunk in self.hunks.iter() {
+            match place(&original, hunk, done, drift, fuzz) {
+                Some(place) => {
+                    let (old, new) = hunk.sides();
+                    let end = place.start + old.len() - place.lead - place.trail;
+
+                    original[done..place.start]
+                        .iter()
+                        .for_each(|line| text.push_str(line));
+                    new[place.lead..new.len() - place.trail]
+                        .iter()
+                        .for_each(|line| text.push_str(line));
+
+                    done = end;
+                    drift = place.offset;
+                    results.push(HunkResult::Applied {
+                        offset: place.offset,
+                        fuzz: place.lead.max(place.trail),
+                    });
+                }
+                None => {
+                    results.push(HunkResult::Rejected);
+                    rejected.push(hunk.clone());
+                }
+            }
+        }
+
+        original[done..].iter().for_each(|line| text.push_str(line));
+        Applied {
+            text,
+            results,
+            rejected,
+        }
+    }
+}
+
+// where a hunk was found: the first original line it replaces, how far that is from its header, and how many context
+// lines were left off each end
+struct Place {
+    start: usize,
+    offset: isize,
+    lead: usize,
+    trail: usize,
+}
+
+fn place(
+    original: &[&str],
+    hunk: &PatchHunk,
+    done: usize,
+    drift: isize,
+    fuzz: usize,
+) -> Option<Place> {
+    let (old, _) = hunk.sides();
+
+    let leading = hunk
+        .lines
+        .iter()
+        .take_while(|line| matches!(line, PatchLine::Context(_)))
+        .count();
+    let trailing = hunk
+        .lines
+        .iter()
+        .rev()
+        .take_while(|line| matches!(line, PatchLine::Context(_)))
+        .count();
+
+    // the 0 based line the header puts the hunk at
+    let expected = if hunk.old_len == 0 {
+        hunk.old_start
+    } else {
+        hunk.old_start.saturating_sub(1)
+    } as isize;
+
+    let mut tried = None;
+    for fuzz in 0..=fuzz {
+        let lead = fuzz.min(leading);
+        let trail = fuzz.min(trailing).min(old.len() - lead);
+        // once the context runs out more fuzz would search for the same lines again
+        if tried == Some((lead, trail)) {
+            break;
+        }
+        tried = Some((lead, trail));
+
+        let pattern = &old[lead..old.len() - trail];
+
+        if original.len() < done + pattern.len() {
+            continue;
+        }
+        let (low, high) = (done as isize, (original.len() - pattern.len()) as isize);
+        let target = (expected + drift + lead as isize).clamp(low, high);
+
+        // try the target first, then one line either side of it, then two, until both ends of the text are passed
+        let mut distance = 0;
+        while target - distance >= low || target + distance <= high {
+            for start in [target - distance, target + distance].iter() {
+                if *start < low || *start > high || (distance == 0 && *start != target) {
+                    continue;
+                }
+                let start = *start as usize;
+
+                if original[start..start + pattern.len()] == *pattern {
+                    return Some(Place {
+                        start,
+                        offset: start as isize - lead as isize - expected,
+                        lead,
+                        trail,
+                    });
+                }
+            }
+            distance += 1;
+        }
+    }
+
+    None
+}
+
+// a file name from a --- or +++ line, without the tab separated timestamp diff puts after it
+fn file_name(line: &str) -> String {
+    let line = line.trim_end_matches(['\n', '\r']);
+    line.split('\t').next().unwrap_or("").to_string()
+}
+
+// parse a number from a hunk header range, such as 12 or 12,4
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    let mut parts = range.splitn(2, ',');
+    let start = parts.next()?.parse().ok()?;
+    let len = match parts.next() {
+        Some(len) => len.parse().ok()?,
+        None => 1,
+    };
+    Some((start, len))
+}
+
+// parse the hunk whose header is lines[at], returning it and the index of the line after it
+fn parse_hunk(lines: &[&str], at: usize) -> Result<(PatchHunk, usize), String> {
+    let header = lines[at].trim_end_matches(['\n', '\r']);
+    let malformed = || format!("malformed hunk header on line {}: {}", at + 1, header);
+
+    let rest = &header[3..];
+    let close = rest.find(" @@").ok_or_else(malformed)?;
+    let mut ranges = rest[..close].split(' ');
+    let old = ranges
+        .next()
+        .and_then(|range| range.strip_prefix('-'))
+        .and_then(parse_range);
+    let new = ranges
+        .next()
+        .and_then(|range| range.strip_prefix('+'))
+        .and_then(parse_range);
+    let ((old_start, old_len), (new_start, new_len)) = match (old, new, ranges.next()) {
+        (Some(old), Some(new), None) => (old, new),
+        _ => return Err(malformed()),
+    };
+
+    let mut hunk = PatchHunk {
+        old_start,
+        old_len,
+        new_start,
+        new_len,
+        heading: rest[close + 3..].trim_start().to_string(),
+        lines: Vec::new(),
+    };
+
+    let (mut old_left, mut new_left) = (old_len, new_len);
+    let mut i = at + 1;
+
+    while old_left > 0 || new_left > 0 {
+        let line = *lines
+            .get(i)
+            .ok_or_else(|| format!("the hunk on line {} ends early", at + 1))?;
+
+        let (kind, text) = match line.chars().next() {
+            Some(kind @ ' ') | Some(kind @ '-') | Some(kind @ '+') => (kind, line[1..].to_string()),
+            // some tools strip the space from empty context lines
+            Some('\n') | Some('\r') => (' ', line.to_string()),
+            Some('\\') => {
+                strip_newline(&mut hunk.lines);
+                i += 1;
+                continue;
+            }
+            _ => {
+                return Err(format!(
+                    "unexpected line {} in the hunk on line {}",
+                    i + 1,
+                    at + 1
+                ))
+            }
+        };
+
+        match kind {
+            ' ' if old_left > 0 && new_left > 0 => {
+                old_left -= 1;
+                new_left -= 1;
+                hunk.lines.push(PatchLine::Context(text));
+            }
+            '-' if old_left > 0 => {
+                old_left -= 1;
+                hunk.lines.push(PatchLine::Delete(text));
+            }
+            '+' if new_left > 0 => {
+                new_left -= 1;
+                hunk.lines.push(PatchLine::Insert(text));
+            }
+            _ => {
+                return Err(format!(
+                    "the hunk on line {} has more lines than its header says",
+                    at + 1
+                ))
+            }
+        }
+        i += 1;
+    }
+
+    // a marker for the last line of the hunk comes after the counts run out
+    if lines.get(i).is_some_and(|line| line.starts_with('\\')) {
+        strip_newline(&mut hunk.lines);
+        i += 1;
+    }
+
+    Ok((hunk, i))
+}
+
+// the line before a "\ No newline at end of file" marker has no line ending in the file
+fn strip_newline(lines: &mut [PatchLine]) {
+    if let Some(line) = lines.last_mut() {
+        let text = match line {
+            PatchLine::Context(text) | PatchLine::Delete(text) | PatchLine::Insert(text) => text,
+        };
+        if text.ends_with('\n') {
+            text.pop();
+            if text.ends_with('\r') {
+                text.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::random_vec;
+
+    #[test]
+    fn patch_round_trip() {
+        use super::super::{diff, DiffFormat};
+        use super::*;
+
+        let mut state = 3;
+        for round in 0..200 {
+            let words = ["a\n", "b\n", "c\n", "d\n", "e"];
+            let old: String = random_vec(&mut state, 30, 5)
+                .into_iter()
+                .map(|i| words[i as usize])
+                .collect();
+            let new: String = random_vec(&mut state, 30, 5)
+                .into_iter()
+                .map(|i| words[i as usize])
+                .collect();
+
+            let (old_lines, new_lines) = (lines(&old), lines(&new));
+            let hunks = diff(&old_lines, &new_lines);
+            let format = DiffFormat::new().context(round % 4);
+
+            let text = format.unified(&old_lines, &new_lines, &hunks);
+            if text.is_empty() {
+                assert_eq!(old, new);
+                continue;
+            }
+
+            let patch = Patch::parse(&text).unwrap();
+            assert_eq!(patch, format.patch(&old_lines, &new_lines, &hunks));
+            assert_eq!(patch.to_string(), text);
+
+            let applied = patch.apply(&old, 0);
+            assert!(applied.is_clean(), "{:?}", applied.results);
+            assert_eq!(applied.text, new);
+        }
+    }
+
+    #[test]
+    fn patch_offset_and_fuzz() {
+        use super::*;
+
+        let patch = Patch::parse(
+            "diff --git a/f b/f\nindex 1..2\n--- a/f\t2024-01-01\n+++ b/f\t2024-01-02\n\
+             @@ -2,5 +2,5 @@ section\n two\n three\n-four\n+FOUR\n five\n six\n",
+        )
+        .unwrap();
+        assert_eq!(patch.old_name.as_deref(), Some("a/f"));
+        assert_eq!(patch.hunks[0].heading, "section");
+
+        // two lines were added above the hunk
+        let text = "zero\nzero\none\ntwo\nthree\nfour\nfive\nsix\nseven\n";
+        let applied = patch.apply(text, 0);
+        assert_eq!(
+            applied.text,
+            "zero\nzero\none\ntwo\nthree\nFOUR\nfive\nsix\nseven\n"
+        );
+        assert_eq!(
+            applied.results,
+            vec![HunkResult::Applied { offset: 2, fuzz: 0 }]
+        );
+
+        // the first and last context lines changed, only fuzz can place the hunk
+        let text = "one\n2\nthree\nfour\nfive\n6\n";
+        let applied = patch.apply(text, 0);
+        assert_eq!(applied.results, vec![HunkResult::Rejected]);
+        assert_eq!(applied.text, text);
+        assert_eq!(applied.rejected, patch.hunks);
+
+        let applied = patch.apply(text, 1);
+        assert_eq!(applied.text, "one\n2\nthree\nFOUR\nfive\n6\n");
+        assert_eq!(
+            applied.results,
+            vec![HunkResult::Applied { offset: 0, fuzz: 1 }]
+        );
+        assert!(!applied.is_clean());
+    }
+
+    #[test]
+    fn patch_rejects_and_errors() {
+        use super::*;
+
+        let patch = Patch::parse("@@ -1,2 +1,2 @@\n a\n-b\n+B\n@@ -5 +5 @@\n-x\n+y\n@@ -8,0 +9 @@\n+end\n\\ No newline at end of file\n")
+            .unwrap();
+        assert_eq!(patch.old_name, None);
+        assert_eq!(patch.hunks.len(), 3);
+        assert_eq!(
+            patch.hunks[2].lines,
+            vec![PatchLine::Insert("end".to_string())]
+        );
+
+        let applied = patch.apply("a\nb\nc\nd\ne\nf\ng\nh\n", 0);
+        assert_eq!(applied.results[1], HunkResult::Rejected);
+        assert_eq!(applied.rejected.len(), 1);
+        assert_eq!(applied.text, "a\nB\nc\nd\ne\nf\ng\nh\nend");
+        assert_eq!(applied.rejected[0].to_string(), "@@ -5 +5 @@\n-x\n+y\n");
+
+        assert!(Patch::parse("").is_err());
+        assert!(Patch::parse("@@ -1,2 +1 @@\n a\n").is_err());
+        assert!(Patch::parse("@@ -1 +1 @@\n-a\n-b\n+c\n").is_err());
+        assert!(Patch::parse("@@ -x +1 @@\n").is_err());
+        assert!(
+            Patch::parse("--- a\n+++ b\n@@ -1 +1 @@\n a\n--- c\n+++ d\n@@ -1 +1 @@\n a\n").is_err()
+        );
+        assert_eq!(
+            Patch::parse_all("--- a\n+++ b\n@@ -1 +1 @@\n a\n--- c\n+++ d\n@@ -1 +1 @@\n a\n")
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+}
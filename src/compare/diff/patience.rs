@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Range;
+
+use super::{common_prefix, common_suffix, myers, DiffAlgorithm, Hunk, HunkBuilder, MAX_DEPTH};
+use crate::search::binary;
+
+/// Patience diff. Values that appear exactly once in both sequences are matched first, keeping the longest run of
+/// them that is in the same order in both, and the gaps between those anchors are diffed the same way. Lines like
+/// braces and blank lines are rarely unique, so they never decide the alignment. Gaps with no unique values are left
+/// to Myers' algorithm, as are the gaps left once the anchors have nested too deeply.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Patience;
+
+impl DiffAlgorithm for Patience {
+    fn diff<T>(&self, old: &[T], new: &[T]) -> Vec<Hunk>
+    where
+        T: Eq + Hash,
+    {
+        let mut hunks = HunkBuilder::default();
+        diff(old, 0..old.len(), new, 0..new.len(), 0, &mut hunks);
+        hunks.finish()
+    }
+}
+
+pub(super) fn diff<T>(
+    old: &[T],
+    old_range: Range<usize>,
+    new: &[T],
+    new_range: Range<usize>,
+    depth: usize,
+    hunks: &mut HunkBuilder,
+) where
+    T: Eq + Hash,
+{
+    let (mut old_range, mut new_range) = (old_range, new_range);
+
+    let prefix = common_prefix(&old[old_range.clone()], &new[new_range.clone()]);
+    hunks.equal(old_range.start, new_range.start, prefix);
+    old_range.start += prefix;
+    new_range.start += prefix;
+
+    let suffix = common_suffix(&old[old_range.clone()], &new[new_range.clone()]);
+    old_range.end -= suffix;
+    new_range.end -= suffix;
+
+    let anchors = if depth < MAX_DEPTH {
+        unique_anchors(old, old_range.clone(), new, new_range.clone())
+    } else {
+        Vec::new()
+    };
+    if anchors.is_empty() {
+        myers::diff(old, old_range.clone(), new, new_range.clone(), hunks);
+    } else {
+        let (mut at_old, mut at_new) = (old_range.start, new_range.start);
+        for (i, j) in anchors {
+            diff(old, at_old..i, new, at_new..j, depth + 1, hunks);
+            hunks.equal(i, j, 1);
+            at_old = i + 1;
+            at_new = j + 1;
+        }
+        diff(
+            old,
+            at_old..old_range.end,
+            new,
+            at_new..new_range.end,
+            depth + 1,
+            hunks,
+        );
+    }
+
+    hunks.equal(old_range.end, new_range.end, suffix);
+}
+
+// the values found exactly once in each range, as index pairs, cut down to the longest chain in the same order in
+// both ranges
+fn unique_anchors<T>(
+    old: &[T],
+    old_range: Range<usize>,
+    new: &[T],
+    new_range: Range<usize>,
+) -> Vec<(usize, usize)>
+where
+    T: Eq + Hash,
+{
+    // for each value: how often it is in old, where, how often it is in new, where
+    let mut seen: HashMap<&T, (usize, usize, usize, usize)> = HashMap::new();
+    for i in old_range.clone() {
+        let entry = seen.entry(&old[i]).or_insert((0, i, 0, 0));
+        entry.0 += 1;
+    }
+    for j in new_range {
+        if let Some(entry) = seen.get_mut(&new[j]) {
+            entry.2 += 1;
+            entry.3 = j;
+        }
+    }
+
+    let pairs: Vec<(usize, usize)> = old_range
+        .filter_map(|i| match seen[&old[i]] {
+            (1, _, 1, j) => Some((i, j)),
+            _ => None,
+        })
+        .collect();
+
+    // patience sorting: the top of each pile and the pair each pair was placed on
+    let mut tops: Vec<usize> = Vec::new();
+    let mut below: Vec<Option<usize>> = vec![None; pairs.len()];
+    for (k, (_, j)) in pairs.iter().enumerate() {
+        let pile = binary::partition_point(&tops, |top| pairs[*top].1 < *j);
+        if pile > 0 {
+            below[k] = Some(tops[pile - 1]);
+        }
+        if pile == tops.len() {
+            tops.push(k);
+        } else {
+            tops[pile] = k;
+        }
+    }
+
+    let mut chain = Vec::with_capacity(tops.len());
+    let mut next = tops.last().copied();
+    while let Some(k) = next {
+        chain.push(pairs[k]);
+        next = below[k];
+    }
+
+    chain.reverse();
+    chain
+}
@@ -0,0 +1,864 @@
+//! Distances between two sequences. Every distance that is a true metric implements the `Metric` trait so the search
+//! structures in this crate can be built over any of them and still agree with the plain functions here. Every
+//! distance also implements `Similarity`, normalized to between 0.0 and 1.0, along with Jaro and Jaro-Winkler which
+//! are only defined as similarities. `weighted` finds the cheapest alignment under costs of your own, such as the
+//! keyboard model of `Qwerty`.
+//!
+//! # Examples
+//! ```
+//! use rs_algo::compare::distance::{self, Damerau, JaroWinkler, Metric, Osa, Similarity};
+//!
+//! assert_eq!(distance::levenshtein_str("ca", "abc"), 3);
+//! assert_eq!(distance::osa_str("ca", "abc"), 3);
+//! assert_eq!(Damerau.distance("ca", "abc"), 2);
+//!
+//! assert_eq!(Osa.similarity("teh", "the"), 1.0 - 1.0 / 3.0);
+//! assert!(JaroWinkler::default().similarity("martha", "marhta") > 0.96);
+//! ```
+
+pub use self::weighted::{weighted, weighted_str, CostFns, CostModel, CostTable, Qwerty};
+
+use std::cmp::{max, min};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use super::BitPattern;
+
+mod weighted;
+
+/// A distance between two values. It needs to be a true metric for search structures such as `search::BkTree`:
+/// zero only for equal values, symmetric, and the triangle inequality needs to hold.
+pub trait Metric<T: ?Sized> {
+    /// Return the distance between a and b
+    fn distance(&self, a: &T, b: &T) -> usize;
+}
+
+/// A similarity between two values, from 0.0 for values with nothing in common to 1.0 for equal values. It is
+/// symmetric, but unlike a `Metric` nothing holds across three values.
+pub trait Similarity<T: ?Sized> {
+    /// Return the similarity of a and b
+    fn similarity(&self, a: &T, b: &T) -> f64;
+}
+
+// 1.0 less the distance as a share of the largest distance possible, so two empty sequences are equal
+fn normalized(distance: usize, largest: usize) -> f64 {
+    if largest == 0 {
+        1.0
+    } else {
+        1.0 - distance as f64 / largest as f64
+    }
+}
+
+// implement Metric, if the distance is one, and a normalized Similarity for strings by chars and slices. The largest
+// distance possible is the longer length for edit distances, or both lengths added for the LCS distance.
+macro_rules! distance {
+    ($name:ty, $str:path, $slice:path, $largest:path, metric, [$($bound:tt)*]) => {
+        impl Metric<str> for $name {
+            fn distance(&self, a: &str, b: &str) -> usize {
+                $str(a, b)
+            }
+        }
+
+        impl Metric<String> for $name {
+            fn distance(&self, a: &String, b: &String) -> usize {
+                $str(a, b)
+            }
+        }
+
+        impl<T> Metric<[T]> for $name
+        where
+            T: $($bound)*,
+        {
+            fn distance(&self, a: &[T], b: &[T]) -> usize {
+                $slice(a, b)
+            }
+        }
+
+        distance!($name, $str, $slice, $largest, [$($bound)*]);
+    };
+    ($name:ty, $str:path, $slice:path, $largest:path, [$($bound:tt)*]) => {
+        impl Similarity<str> for $name {
+            fn similarity(&self, a: &str, b: &str) -> f64 {
+                normalized($str(a, b), $largest(a.chars().count(), b.chars().count()))
+            }
+        }
+
+        impl Similarity<String> for $name {
+            fn similarity(&self, a: &String, b: &String) -> f64 {
+                Similarity::<str>::similarity(self, a, b)
+            }
+        }
+
+        impl<T> Similarity<[T]> for $name
+        where
+            T: $($bound)*,
+        {
+            fn similarity(&self, a: &[T], b: &[T]) -> f64 {
+                normalized($slice(a, b), $largest(a.len(), b.len()))
+            }
+        }
+    };
+}
+
+// implement Similarity for strings by chars and slices
+macro_rules! similarity {
+    ($name:ty, $str:expr, $slice:expr) => {
+        impl Similarity<str> for $name {
+            fn similarity(&self, a: &str, b: &str) -> f64 {
+                $str(self, a, b)
+            }
+        }
+
+        impl Similarity<String> for $name {
+            fn similarity(&self, a: &String, b: &String) -> f64 {
+                $str(self, a, b)
+            }
+        }
+
+        impl<T> Similarity<[T]> for $name
+        where
+            T: PartialEq,
+        {
+            fn similarity(&self, a: &[T], b: &[T]) -> f64 {
+                $slice(self, a, b)
+            }
+        }
+    };
+}
+
+fn sum(a: usize, b: usize) -> usize {
+    a + b
+}
+
+/// The Levenshtein distance, the fewest single value inserts, deletes and substitutions that turn one sequence into
+/// the other. Strings are compared by chars with a `BitPattern`, slices cell by cell.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance::{Levenshtein, Metric, Similarity};
+///
+/// assert_eq!(Levenshtein.distance("kitten", "sitting"), 3);
+/// assert_eq!(Levenshtein.distance(&[1, 2, 3][..], &[1, 3][..]), 1);
+/// assert_eq!(Levenshtein.similarity("book", "back"), 0.5);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Levenshtein;
+
+distance!(
+    Levenshtein,
+    levenshtein_str,
+    levenshtein,
+    max,
+    metric,
+    [PartialEq]
+);
+
+/// The optimal string alignment distance, the Levenshtein distance with swaps of two neighbouring values counted as
+/// one edit. No part of the sequence can be edited twice, so "ca" is three edits from "abc" rather than the two of
+/// `Damerau`. That also breaks the triangle inequality, so this is only a `Similarity` and not a `Metric`.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance::{Osa, Similarity};
+///
+/// assert_eq!(Osa.similarity("abcd", "acbd"), 0.75);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Osa;
+
+distance!(Osa, osa_str, osa, max, [PartialEq]);
+
+/// The true Damerau-Levenshtein distance, the fewest inserts, deletes, substitutions and swaps of two neighbouring
+/// values, where values may be edited again after they are swapped. Strings are compared by chars.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance::{Damerau, Metric};
+///
+/// assert_eq!(Damerau.distance("ca", "abc"), 2);
+/// assert_eq!(Damerau.distance(&[1, 2, 3][..], &[2, 1, 3][..]), 1);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Damerau;
+
+distance!(
+    Damerau,
+    damerau_str,
+    damerau,
+    max,
+    metric,
+    [Eq + Hash + Clone]
+);
+
+/// The Hamming distance, the number of positions holding different values. When the lengths differ, every value past
+/// the end of the shorter sequence counts as different.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance::{Hamming, Metric};
+///
+/// assert_eq!(Hamming.distance("karolin", "kathrin"), 3);
+/// assert_eq!(Hamming.distance("abc", "ab"), 1);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Hamming;
+
+distance!(Hamming, hamming_str, hamming, max, metric, [PartialEq]);
+
+/// The LCS distance, the fewest inserts and deletes that turn one sequence into the other, which is both lengths less
+/// twice the length of their longest common subsequence. Its similarity is the share of both sequences that is in the
+/// longest common subsequence.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance::{LcsDistance, Metric, Similarity};
+///
+/// assert_eq!(LcsDistance.distance("kitten", "sitting"), 5);
+/// assert_eq!(LcsDistance.similarity("abcd", "abxd"), 0.75);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LcsDistance;
+
+distance!(
+    LcsDistance,
+    lcs_distance_str,
+    lcs_distance,
+    sum,
+    metric,
+    [Eq + Hash + Clone]
+);
+
+/// The Jaro similarity. Values are matched when they are equal and no further apart than half the longer length less
+/// one, and the similarity is worked out from how many matched and how many of the matches are out of order.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance::{Jaro, Similarity};
+///
+/// assert!((Jaro.similarity("martha", "marhta") - 0.9444).abs() < 1e-4);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Jaro;
+
+similarity!(Jaro, |_, a, b| jaro_str(a, b), |_, a, b| jaro(a, b));
+
+/// The Jaro-Winkler similarity, the Jaro similarity raised for sequences that start the same. Up to 4 shared leading
+/// values each close `prefix_scale` of the gap to 1.0, which is 0.1 by default and should be no more than 0.25.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance::{JaroWinkler, Similarity};
+///
+/// let similarity = JaroWinkler::default().similarity("dixon", "dicksonx");
+/// assert!((similarity - 0.8133).abs() < 1e-4);
+///
+/// let similarity = JaroWinkler { prefix_scale: 0.0 }.similarity("dixon", "dicksonx");
+/// assert!((similarity - 0.7667).abs() < 1e-4);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JaroWinkler {
+    /// how much of the gap to 1.0 each shared leading value closes
+    pub prefix_scale: f64,
+}
+
+impl Default for JaroWinkler {
+    fn default() -> Self {
+        JaroWinkler { prefix_scale: 0.1 }
+    }
+}
+
+similarity!(
+    JaroWinkler,
+    |w: &JaroWinkler, a: &str, b: &str| {
+        let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+        winkler(&a, &b, w.prefix_scale)
+    },
+    |w: &JaroWinkler, a, b| winkler(a, b, w.prefix_scale)
+);
+
+/// One step of an edit path, with the indices of the values it is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Edit {
+    /// a[a] is kept as b[b]
+    Keep { a: usize, b: usize },
+    /// a[a] is replaced by b[b]
+    Substitute { a: usize, b: usize },
+    /// a[a] is deleted
+    Delete { a: usize },
+    /// b[b] is inserted
+    Insert { b: usize },
+    /// a[a] and a[a + 1] are swapped to give b[b] and b[b + 1], only found by `weighted`
+    Transpose { a: usize, b: usize },
+}
+
+/// Return the Levenshtein distance between two sequences. Only two rows of the grid are kept, so this uses
+/// O(min(n, m)) memory, but every cell is still visited since the values only compare with `PartialEq`. Values
+/// that can be hashed can use `BitPattern::levenshtein` for the bit-parallel kernel that `levenshtein_str` uses.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance;
+///
+/// assert_eq!(distance::levenshtein(b"flaw", b"lawn"), 2);
+/// ```
+pub fn levenshtein<T>(a: &[T], b: &[T]) -> usize
+where
+    T: PartialEq,
+{
+    // the shorter sequence runs along the rows
+    let (a, b) = if a.len() < b.len() { (b, a) } else { (a, b) };
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, x) in a.iter().enumerate() {
+        current[0] = i + 1;
+
+        for (j, y) in b.iter().enumerate() {
+            let substitute = previous[j] + (x != y) as usize;
+            current[j + 1] = min(substitute, min(previous[j + 1], current[j]) + 1);
+        }
+
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// Return the Levenshtein distance between two strings, comparing by chars. The shorter string is made a
+/// `BitPattern`, so this runs Myers' bit-vector algorithm in O(⌈m / 64⌉ n) time.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance;
+///
+/// assert_eq!(distance::levenshtein_str("café", "cafe"), 1);
+/// ```
+pub fn levenshtein_str(a: &str, b: &str) -> usize {
+    let (a, b) = if a.chars().count() < b.chars().count() {
+        (b, a)
+    } else {
+        (a, b)
+    };
+
+    BitPattern::new_str(b).levenshtein_str(a)
+}
+
+/// Return the Levenshtein distance between two sequences along with the edits that turn a into b, in order. The whole
+/// grid is kept to walk the path back, so this uses O(nm) memory.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance::{self, Edit};
+///
+/// let (distance, path) = distance::levenshtein_path(b"cat", b"cut");
+///
+/// assert_eq!(distance, 1);
+/// assert_eq!(
+///     path,
+///     vec![
+///         Edit::Keep { a: 0, b: 0 },
+///         Edit::Substitute { a: 1, b: 1 },
+///         Edit::Keep { a: 2, b: 2 },
+///     ]
+/// );
+/// ```
+pub fn levenshtein_path<T>(a: &[T], b: &[T]) -> (usize, Vec<Edit>)
+where
+    T: PartialEq,
+{
+    let width = b.len() + 1;
+    let mut grid = vec![0; (a.len() + 1) * width];
+    for (j, cell) in grid[..width].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        grid[i * width] = i;
+        for j in 1..width {
+            let substitute = grid[(i - 1) * width + j - 1] + (a[i - 1] != b[j - 1]) as usize;
+            let skip = min(grid[(i - 1) * width + j], grid[i * width + j - 1]) + 1;
+            grid[i * width + j] = min(substitute, skip);
+        }
+    }
+
+    let mut path = Vec::new();
+    let (mut i, mut j) = (a.len(), b.len());
+    while i > 0 || j > 0 {
+        let here = grid[i * width + j];
+        if i > 0 && j > 0 {
+            let equal = a[i - 1] == b[j - 1];
+            if grid[(i - 1) * width + j - 1] + (!equal) as usize == here {
+                i -= 1;
+                j -= 1;
+                path.push(if equal {
+                    Edit::Keep { a: i, b: j }
+                } else {
+                    Edit::Substitute { a: i, b: j }
+                });
+                continue;
+            }
+        }
+        if i > 0 && grid[(i - 1) * width + j] + 1 == here {
+            i -= 1;
+            path.push(Edit::Delete { a: i });
+        } else {
+            j -= 1;
+            path.push(Edit::Insert { b: j });
+        }
+    }
+
+    path.reverse();
+    (grid[grid.len() - 1], path)
+}
+
+/// Return the optimal string alignment distance between two sequences, keeping three rows of the grid.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance;
+///
+/// assert_eq!(distance::osa(b"abcd", b"acbd"), 1);
+/// ```
+pub fn osa<T>(a: &[T], b: &[T]) -> usize
+where
+    T: PartialEq,
+{
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        current[0] = i;
+
+        for j in 1..=b.len() {
+            let substitute = previous[j - 1] + (a[i - 1] != b[j - 1]) as usize;
+            current[j] = min(substitute, min(previous[j], current[j - 1]) + 1);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = min(current[j], before[j - 2] + 1);
+            }
+        }
+
+        std::mem::swap(&mut before, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// Return the optimal string alignment distance between two strings, comparing by chars.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance;
+///
+/// assert_eq!(distance::osa_str("teh", "the"), 1);
+/// ```
+pub fn osa_str(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    osa(&a, &b)
+}
+
+/// Return the true Damerau-Levenshtein distance between two sequences. This is Lowrance and Wagner's algorithm,
+/// which keeps the whole grid and the last row each value was seen in, in O(nm) time and memory.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance;
+///
+/// assert_eq!(distance::damerau(b"ca", b"abc"), 2);
+/// ```
+pub fn damerau<T>(a: &[T], b: &[T]) -> usize
+where
+    T: Eq + Hash + Clone,
+{
+    // the grid has an extra row and column on top holding a distance larger than any real one
+    let width = b.len() + 2;
+    let far = a.len() + b.len();
+    let mut grid = vec![0; (a.len() + 2) * width];
+    grid[0] = far;
+    for i in 0..=a.len() {
+        grid[(i + 1) * width] = far;
+        grid[(i + 1) * width + 1] = i;
+    }
+    for j in 0..=b.len() {
+        grid[j + 1] = far;
+        grid[width + j + 1] = j;
+    }
+
+    let mut last_row: HashMap<T, usize> = HashMap::new();
+    for i in 1..=a.len() {
+        // the last column in this row where the values were equal
+        let mut last_column = 0;
+
+        for j in 1..=b.len() {
+            let k = last_row.get(&b[j - 1]).copied().unwrap_or(0);
+            let l = last_column;
+            let cost = if a[i - 1] == b[j - 1] {
+                last_column = j;
+                0
+            } else {
+                1
+            };
+
+            let substitute = grid[i * width + j] + cost;
+            let skip = min(grid[(i + 1) * width + j], grid[i * width + j + 1]) + 1;
+            // swap a[k - 1] and a[i - 1], with everything between them deleted or inserted
+            let swap = grid[k * width + l] + (i - k - 1) + 1 + (j - l - 1);
+            grid[(i + 1) * width + j + 1] = min(min(substitute, skip), swap);
+        }
+
+        last_row.insert(a[i - 1].clone(), i);
+    }
+
+    grid[grid.len() - 1]
+}
+
+/// Return the true Damerau-Levenshtein distance between two strings, comparing by chars.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance;
+///
+/// assert_eq!(distance::damerau_str("ca", "abc"), 2);
+/// ```
+pub fn damerau_str(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    damerau(&a, &b)
+}
+
+/// Return the Hamming distance between two sequences, counting the values past the end of the shorter one as
+/// different.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance;
+///
+/// assert_eq!(distance::hamming(&[1, 0, 1, 1], &[1, 1, 1, 0]), 2);
+/// ```
+pub fn hamming<T>(a: &[T], b: &[T]) -> usize
+where
+    T: PartialEq,
+{
+    let different = a.iter().zip(b.iter()).filter(|(x, y)| x != y).count();
+    different + max(a.len(), b.len()) - min(a.len(), b.len())
+}
+
+/// Return the Hamming distance between two strings, comparing by chars.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance;
+///
+/// assert_eq!(distance::hamming_str("karolin", "kerstin"), 3);
+/// ```
+pub fn hamming_str(a: &str, b: &str) -> usize {
+    let (mut a, mut b) = (a.chars(), b.chars());
+    let mut different = 0;
+
+    loop {
+        match (a.next(), b.next()) {
+            (Some(x), Some(y)) => different += (x != y) as usize,
+            (None, None) => return different,
+            (Some(_), None) | (None, Some(_)) => different += 1,
+        }
+    }
+}
+
+/// Return the LCS distance between two sequences, found with a `BitPattern` over the shorter one.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance;
+///
+/// assert_eq!(distance::lcs_distance(b"abcd", b"acbd"), 2);
+/// ```
+pub fn lcs_distance<T>(a: &[T], b: &[T]) -> usize
+where
+    T: Eq + Hash + Clone,
+{
+    let (long, short) = if a.len() < b.len() { (b, a) } else { (a, b) };
+    a.len() + b.len() - 2 * BitPattern::new(short).lcs_len(long)
+}
+
+/// Return the LCS distance between two strings, comparing by chars.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance;
+///
+/// assert_eq!(distance::lcs_distance_str("kitten", "sitting"), 5);
+/// ```
+pub fn lcs_distance_str(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    lcs_distance(&a, &b)
+}
+
+/// Return the Jaro similarity of two sequences, 1.0 when both are empty and 0.0 when only one is.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance;
+///
+/// assert_eq!(distance::jaro(b"abc", b"abc"), 1.0);
+/// assert_eq!(distance::jaro(b"abc", b"xyz"), 0.0);
+/// ```
+pub fn jaro<T>(a: &[T], b: &[T]) -> f64
+where
+    T: PartialEq,
+{
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+
+    let window = (max(a.len(), b.len()) / 2).saturating_sub(1);
+    let mut matched_a = vec![false; a.len()];
+    let mut matched_b = vec![false; b.len()];
+    let mut matches = 0;
+
+    for (i, x) in a.iter().enumerate() {
+        let end = min(i + window + 1, b.len());
+        for j in i.saturating_sub(window)..end {
+            if !matched_b[j] && *x == b[j] {
+                matched_a[i] = true;
+                matched_b[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+    if matches == 0 {
+        return 0.0;
+    }
+
+    // matches that are not in the same order on both sides, counted once per pair
+    let in_a = a.iter().zip(matched_a.iter()).filter(|(_, m)| **m);
+    let in_b = b.iter().zip(matched_b.iter()).filter(|(_, m)| **m);
+    let transpositions = in_a.zip(in_b).filter(|((x, _), (y, _))| x != y).count() / 2;
+
+    let matches = matches as f64;
+    (matches / a.len() as f64
+        + matches / b.len() as f64
+        + (matches - transpositions as f64) / matches)
+        / 3.0
+}
+
+/// Return the Jaro similarity of two strings, comparing by chars.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance;
+///
+/// assert!((distance::jaro_str("dwayne", "duane") - 0.8222).abs() < 1e-4);
+/// ```
+pub fn jaro_str(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    jaro(&a, &b)
+}
+
+/// Return the Jaro-Winkler similarity of two sequences, with the usual prefix scale of 0.1.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance;
+///
+/// assert!(distance::jaro_winkler(b"prefix", b"prefab") > distance::jaro(b"prefix", b"prefab"));
+/// ```
+pub fn jaro_winkler<T>(a: &[T], b: &[T]) -> f64
+where
+    T: PartialEq,
+{
+    winkler(a, b, JaroWinkler::default().prefix_scale)
+}
+
+/// Return the Jaro-Winkler similarity of two strings, comparing by chars.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance;
+///
+/// assert!((distance::jaro_winkler_str("martha", "marhta") - 0.9611).abs() < 1e-4);
+/// ```
+pub fn jaro_winkler_str(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    jaro_winkler(&a, &b)
+}
+
+fn winkler<T>(a: &[T], b: &[T], prefix_scale: f64) -> f64
+where
+    T: PartialEq,
+{
+    let similarity = jaro(a, b);
+    let prefix = a.iter().zip(b.iter()).take(4).take_while(|(x, y)| x == y);
+
+    similarity + prefix.count() as f64 * prefix_scale * (1.0 - similarity)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn levenshtein_distance() {
+        use super::*;
+
+        assert_eq!(levenshtein_str("kitten", "sitting"), 3);
+        assert_eq!(levenshtein_str("sitting", "kitten"), 3);
+        assert_eq!(levenshtein_str("", "abc"), 3);
+        assert_eq!(levenshtein_str("abc", ""), 3);
+        assert_eq!(levenshtein_str("", ""), 0);
+        assert_eq!(levenshtein_str("same", "same"), 0);
+        assert_eq!(levenshtein_str("😀a", "a😀"), 2);
+        assert_eq!(levenshtein(&[1, 2, 3, 4], &[2, 3, 4, 5]), 2);
+    }
+
+    #[test]
+    fn levenshtein_metric() {
+        use super::*;
+
+        let words = ["book", "books", "boo", "cake", "cape", "cart", ""];
+        for a in words.iter() {
+            for b in words.iter() {
+                let ab = Levenshtein.distance(*a, *b);
+                assert_eq!(ab, Levenshtein.distance(*b, *a));
+                assert_eq!(ab == 0, a == b);
+
+                for c in words.iter() {
+                    assert!(ab <= Levenshtein.distance(*a, *c) + Levenshtein.distance(*c, *b));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn levenshtein_path_is_shortest() {
+        use super::*;
+        use crate::test_util::random_vec;
+
+        let mut state = 29;
+        for _ in 0..300 {
+            let a = random_vec(&mut state, 15, 3);
+            let b = random_vec(&mut state, 15, 3);
+
+            let (distance, path) = levenshtein_path(&a, &b);
+            assert_eq!(distance, levenshtein(&a, &b));
+
+            // the path walks both sequences in order and rebuilds b
+            let (mut i, mut j) = (0, 0);
+            let mut edits = 0;
+            let mut rebuilt = Vec::new();
+            for edit in path {
+                match edit {
+                    Edit::Keep { a: x, b: y } => {
+                        assert_eq!((x, y), (i, j));
+                        assert_eq!(a[x], b[y]);
+                        rebuilt.push(a[x]);
+                        i += 1;
+                        j += 1;
+                    }
+                    Edit::Substitute { a: x, b: y } => {
+                        assert_eq!((x, y), (i, j));
+                        rebuilt.push(b[y]);
+                        edits += 1;
+                        i += 1;
+                        j += 1;
+                    }
+                    Edit::Delete { a: x } => {
+                        assert_eq!(x, i);
+                        edits += 1;
+                        i += 1;
+                    }
+                    Edit::Insert { b: y } => {
+                        assert_eq!(y, j);
+                        rebuilt.push(b[y]);
+                        edits += 1;
+                        j += 1;
+                    }
+                    Edit::Transpose { .. } => panic!("levenshtein does not swap"),
+                }
+            }
+            assert_eq!((i, j), (a.len(), b.len()));
+            assert_eq!(rebuilt, b);
+            assert_eq!(edits, distance);
+        }
+    }
+
+    #[test]
+    fn edit_distance_family() {
+        use super::*;
+        use crate::test_util::random_vec;
+
+        assert_eq!(osa_str("ca", "abc"), 3);
+        assert_eq!(damerau_str("ca", "abc"), 2);
+        assert_eq!(osa_str("", "ab"), 2);
+        assert_eq!(damerau_str("ab", ""), 2);
+        assert_eq!(damerau_str("abcdef", "badcfe"), 3);
+        assert_eq!(hamming_str("😀bc", "abc"), 1);
+        assert_eq!(hamming_str("", "abc"), 3);
+        assert_eq!(lcs_distance_str("", ""), 0);
+
+        let mut state = 31;
+        for _ in 0..300 {
+            let a = random_vec(&mut state, 12, 3);
+            let b = random_vec(&mut state, 12, 3);
+
+            // each distance allows at least the edits of the next
+            let (d, o, l) = (damerau(&a, &b), osa(&a, &b), levenshtein(&a, &b));
+            assert!(d <= o && o <= l && l <= lcs_distance(&a, &b));
+            assert!(l <= 2 * d);
+            assert_eq!(o, osa(&b, &a));
+            if a.len() == b.len() {
+                assert!(l <= hamming(&a, &b));
+            }
+        }
+    }
+
+    #[test]
+    fn metrics_and_similarities() {
+        use super::*;
+
+        fn check<M>(metric: M)
+        where
+            M: Metric<str> + Similarity<str>,
+        {
+            let words = ["abc", "acb", "bac", "ab", "ca", "abcd", "", "xyz"];
+            for a in words.iter() {
+                for b in words.iter() {
+                    let ab = metric.distance(a, b);
+                    assert_eq!(ab, metric.distance(b, a));
+                    assert_eq!(ab == 0, a == b);
+
+                    let similarity = metric.similarity(a, b);
+                    assert!((0.0..=1.0).contains(&similarity));
+                    assert_eq!(similarity == 1.0, a == b);
+
+                    for c in words.iter() {
+                        assert!(ab <= metric.distance(a, c) + metric.distance(c, b));
+                    }
+                }
+            }
+        }
+
+        check(Levenshtein);
+        check(Damerau);
+        check(Hamming);
+        check(LcsDistance);
+
+        assert_eq!(Osa.similarity("", ""), 1.0);
+        assert_eq!(Osa.similarity("ab", "cd"), 0.0);
+        assert_eq!(Jaro.similarity("", "a"), 0.0);
+        assert_eq!(Jaro.similarity(&[1, 2][..], &[1, 2][..]), 1.0);
+        assert!((Jaro.similarity("crate", "trace") - 0.7333).abs() < 1e-4);
+        assert!((jaro_winkler_str("dwayne", "duane") - 0.84).abs() < 1e-4);
+        assert_eq!(JaroWinkler::default().similarity("abc", "abc"), 1.0);
+    }
+}
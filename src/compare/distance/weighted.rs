@@ -0,0 +1,554 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use super::Edit;
+
+/// The cost of each edit for `weighted`. Costs should not be negative, and an infinite cost rules the edit out.
+pub trait CostModel<T> {
+    /// Return the cost of inserting the value
+    fn insert(&self, value: &T) -> f64;
+
+    /// Return the cost of deleting the value
+    fn delete(&self, value: &T) -> f64;
+
+    /// Return the cost of replacing one value with a different one
+    fn substitute(&self, from: &T, to: &T) -> f64;
+
+    /// Return the cost of swapping two different neighbouring values, so first then second becomes second then first.
+    /// By default swaps are ruled out.
+    fn transpose(&self, first: &T, second: &T) -> f64 {
+        let _ = (first, second);
+        f64::INFINITY
+    }
+}
+
+/// A cost model made of four closures, one for each edit.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance::{self, CostFns};
+///
+/// // vowels are cheap to get wrong
+/// let vowel = |c: &char| "aeiou".contains(*c);
+/// let costs = CostFns {
+///     insert: |_: &char| 1.0,
+///     delete: |_: &char| 1.0,
+///     substitute: |a: &char, b: &char| if vowel(a) && vowel(b) { 0.25 } else { 1.0 },
+///     transpose: |_: &char, _: &char| 1.0,
+/// };
+///
+/// assert_eq!(distance::weighted_str("color", "colour", &costs).0, 1.0);
+/// assert_eq!(distance::weighted_str("grey", "gray", &costs).0, 0.25);
+/// assert_eq!(distance::weighted_str("form", "from", &costs).0, 1.0);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CostFns<I, D, S, X> {
+    /// the cost of inserting a value
+    pub insert: I,
+    /// the cost of deleting a value
+    pub delete: D,
+    /// the cost of replacing one value with a different one
+    pub substitute: S,
+    /// the cost of swapping two neighbouring values
+    pub transpose: X,
+}
+
+impl<T, I, D, S, X> CostModel<T> for CostFns<I, D, S, X>
+where
+    I: Fn(&T) -> f64,
+    D: Fn(&T) -> f64,
+    S: Fn(&T, &T) -> f64,
+    X: Fn(&T, &T) -> f64,
+{
+    fn insert(&self, value: &T) -> f64 {
+        (self.insert)(value)
+    }
+
+    fn delete(&self, value: &T) -> f64 {
+        (self.delete)(value)
+    }
+
+    fn substitute(&self, from: &T, to: &T) -> f64 {
+        (self.substitute)(from, to)
+    }
+
+    fn transpose(&self, first: &T, second: &T) -> f64 {
+        (self.transpose)(first, second)
+    }
+}
+
+/// A cost model from tables of costs for particular values and pairs of values, falling back to a default cost for
+/// each edit. Every default starts at 1.0, so a new table gives the optimal string alignment distance.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance::{self, CostTable};
+///
+/// // characters OCR often confuses
+/// let costs = CostTable::new()
+///     .substitute_pair('0', 'O', 0.1)
+///     .substitute_pair('1', 'l', 0.1)
+///     .substitute_pair('5', 'S', 0.2)
+///     .delete_value(' ', 0.5);
+///
+/// let (cost, _) = distance::weighted_str("HELL0 W0RLD", "HELLO WORLD", &costs);
+/// assert!((cost - 0.2).abs() < 1e-9);
+///
+/// let (cost, _) = distance::weighted_str("5 1ines", "Slines", &costs);
+/// assert!((cost - 0.8).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CostTable<T> {
+    insert: f64,
+    delete: f64,
+    substitute: f64,
+    transpose: f64,
+    inserts: HashMap<T, f64>,
+    deletes: HashMap<T, f64>,
+    substitutes: HashMap<(T, T), f64>,
+    transposes: HashMap<(T, T), f64>,
+}
+
+impl<T> Default for CostTable<T> {
+    fn default() -> Self {
+        CostTable {
+            insert: 1.0,
+            delete: 1.0,
+            substitute: 1.0,
+            transpose: 1.0,
+            inserts: HashMap::new(),
+            deletes: HashMap::new(),
+            substitutes: HashMap::new(),
+            transposes: HashMap::new(),
+        }
+    }
+}
+
+impl<T> CostTable<T>
+where
+    T: Eq + Hash + Clone,
+{
+    /// This will return a new CostTable where every edit costs 1.0
+    pub fn new() -> Self {
+        CostTable::default()
+    }
+
+    /// Set the cost of inserting a value not in the table
+    pub fn insert(mut self, cost: f64) -> Self {
+        self.insert = cost;
+        self
+    }
+
+    /// Set the cost of deleting a value not in the table
+    pub fn delete(mut self, cost: f64) -> Self {
+        self.delete = cost;
+        self
+    }
+
+    /// Set the cost of a substitution not in the table
+    pub fn substitute(mut self, cost: f64) -> Self {
+        self.substitute = cost;
+        self
+    }
+
+    /// Set the cost of a swap not in the table, infinity rules swaps out
+    pub fn transpose(mut self, cost: f64) -> Self {
+        self.transpose = cost;
+        self
+    }
+
+    /// Set the cost of inserting the value
+    pub fn insert_value(mut self, value: T, cost: f64) -> Self {
+        self.inserts.insert(value, cost);
+        self
+    }
+
+    /// Set the cost of deleting the value
+    pub fn delete_value(mut self, value: T, cost: f64) -> Self {
+        self.deletes.insert(value, cost);
+        self
+    }
+
+    /// Set the cost of replacing a with b, and b with a
+    pub fn substitute_pair(mut self, a: T, b: T, cost: f64) -> Self {
+        self.substitutes.insert((a.clone(), b.clone()), cost);
+        self.substitutes.insert((b, a), cost);
+        self
+    }
+
+    /// Set the cost of swapping neighbouring a and b, in either order
+    pub fn transpose_pair(mut self, a: T, b: T, cost: f64) -> Self {
+        self.transposes.insert((a.clone(), b.clone()), cost);
+        self.transposes.insert((b, a), cost);
+        self
+    }
+}
+
+impl<T> CostModel<T> for CostTable<T>
+where
+    T: Eq + Hash + Clone,
+{
+    fn insert(&self, value: &T) -> f64 {
+        *self.inserts.get(value).unwrap_or(&self.insert)
+    }
+
+    fn delete(&self, value: &T) -> f64 {
+        *self.deletes.get(value).unwrap_or(&self.delete)
+    }
+
+    fn substitute(&self, from: &T, to: &T) -> f64 {
+        let pair = (from.clone(), to.clone());
+        *self.substitutes.get(&pair).unwrap_or(&self.substitute)
+    }
+
+    fn transpose(&self, first: &T, second: &T) -> f64 {
+        let pair = (first.clone(), second.clone());
+        *self.transposes.get(&pair).unwrap_or(&self.transpose)
+    }
+}
+
+// the keys of a US QWERTY keyboard by row, without and with shift held. Each row sits half a key right of the one
+// above it.
+const ROWS: [&str; 4] = [
+    "1234567890-=",
+    "qwertyuiop[]\\",
+    "asdfghjkl;'",
+    "zxcvbnm,./",
+];
+const SHIFTED: [&str; 4] = [
+    "!@#$%^&*()_+",
+    "QWERTYUIOP{}|",
+    "ASDFGHJKL:\"",
+    "ZXCVBNM<>?",
+];
+
+// the row and column of the key typing c
+fn key(c: char) -> Option<(usize, usize)> {
+    ROWS.iter()
+        .chain(SHIFTED.iter())
+        .enumerate()
+        .find_map(|(row, keys)| keys.find(c).map(|column| (row % ROWS.len(), column)))
+}
+
+/// A cost model for typing errors on a US QWERTY keyboard. Hitting a key next to the right one costs `adjacent`, and
+/// the right key with shift wrong, like "a" for "A", costs `shift`. Every other substitution, insert, delete and swap
+/// costs 1.0.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance::{self, Qwerty};
+///
+/// let qwerty = Qwerty::default();
+///
+/// // s sits next to a, p is across the keyboard
+/// assert_eq!(distance::weighted_str("cat", "cst", &qwerty).0, 0.5);
+/// assert_eq!(distance::weighted_str("cat", "cpt", &qwerty).0, 1.0);
+/// assert_eq!(distance::weighted_str("Cat", "cat", &qwerty).0, 0.25);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Qwerty {
+    /// the cost of hitting a neighbouring key, 0.5 by default
+    pub adjacent: f64,
+    /// the cost of the right key with shift wrong, 0.25 by default
+    pub shift: f64,
+}
+
+impl Default for Qwerty {
+    fn default() -> Self {
+        Qwerty {
+            adjacent: 0.5,
+            shift: 0.25,
+        }
+    }
+}
+
+impl Qwerty {
+    /// Return true if the keys typing a and b are next to each other, in the same row or the rows above and below
+    ///
+    /// # Examples
+    /// ```
+    /// use rs_algo::compare::distance::Qwerty;
+    ///
+    /// assert!(Qwerty::is_adjacent('g', 'h'));
+    /// assert!(Qwerty::is_adjacent('g', 'T'));
+    /// assert!(Qwerty::is_adjacent('g', 'b'));
+    /// assert!(!Qwerty::is_adjacent('g', 'g'));
+    /// assert!(!Qwerty::is_adjacent('g', 'r'));
+    /// ```
+    pub fn is_adjacent(a: char, b: char) -> bool {
+        match (key(a), key(b)) {
+            (Some((row_a, column_a)), Some((row_b, column_b))) => {
+                if row_a == row_b {
+                    column_a.abs_diff(column_b) == 1
+                } else if row_b + 1 == row_a {
+                    column_b == column_a || column_b == column_a + 1
+                } else if row_a + 1 == row_b {
+                    column_b == column_a || column_b + 1 == column_a
+                } else {
+                    false
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+impl CostModel<char> for Qwerty {
+    fn insert(&self, _: &char) -> f64 {
+        1.0
+    }
+
+    fn delete(&self, _: &char) -> f64 {
+        1.0
+    }
+
+    fn substitute(&self, from: &char, to: &char) -> f64 {
+        if key(*from).is_some() && key(*from) == key(*to) {
+            self.shift
+        } else if Qwerty::is_adjacent(*from, *to) {
+            self.adjacent
+        } else {
+            1.0
+        }
+    }
+
+    fn transpose(&self, _: &char, _: &char) -> f64 {
+        1.0
+    }
+}
+
+// the last edit on the cheapest path to a cell of the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Start,
+    Diagonal,
+    Delete,
+    Insert,
+    Transpose,
+}
+
+/// Return the lowest total cost of edits turning a into b under the cost model, with the edits of a cheapest
+/// alignment in order. Equal values are kept for free, and a swap of two neighbouring values can not be edited again,
+/// as with the optimal string alignment distance. The whole grid of costs and steps is kept, in O(nm) memory.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance::{self, CostTable, Edit};
+///
+/// let costs = CostTable::new().insert(0.5).substitute(2.0);
+/// let (cost, path) = distance::weighted(b"ba", b"abc", &costs);
+///
+/// assert_eq!(cost, 1.5);
+/// assert_eq!(path, vec![Edit::Transpose { a: 0, b: 0 }, Edit::Insert { b: 2 }]);
+/// ```
+pub fn weighted<T, C>(a: &[T], b: &[T], costs: &C) -> (f64, Vec<Edit>)
+where
+    T: PartialEq,
+    C: CostModel<T>,
+{
+    let width = b.len() + 1;
+    let mut grid = vec![0.0; (a.len() + 1) * width];
+    let mut steps = vec![Step::Start; grid.len()];
+
+    for i in 1..=a.len() {
+        grid[i * width] = grid[(i - 1) * width] + costs.delete(&a[i - 1]);
+        steps[i * width] = Step::Delete;
+    }
+    for j in 1..width {
+        grid[j] = grid[j - 1] + costs.insert(&b[j - 1]);
+        steps[j] = Step::Insert;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..width {
+            let (x, y) = (&a[i - 1], &b[j - 1]);
+            let here = i * width + j;
+
+            let substitute = if x == y { 0.0 } else { costs.substitute(x, y) };
+            let mut best = (grid[here - width - 1] + substitute, Step::Diagonal);
+
+            for candidate in [
+                (grid[here - width] + costs.delete(x), Step::Delete),
+                (grid[here - 1] + costs.insert(y), Step::Insert),
+            ] {
+                if candidate.0 < best.0 {
+                    best = candidate;
+                }
+            }
+
+            if i > 1 && j > 1 && *x == b[j - 2] && a[i - 2] == *y && *x != *y {
+                let swap = grid[here - 2 * width - 2] + costs.transpose(&a[i - 2], x);
+                if swap < best.0 {
+                    best = (swap, Step::Transpose);
+                }
+            }
+
+            grid[here] = best.0;
+            steps[here] = best.1;
+        }
+    }
+
+    let mut path = Vec::new();
+    let (mut i, mut j) = (a.len(), b.len());
+    loop {
+        match steps[i * width + j] {
+            Step::Start => break,
+            Step::Diagonal => {
+                i -= 1;
+                j -= 1;
+                path.push(if a[i] == b[j] {
+                    Edit::Keep { a: i, b: j }
+                } else {
+                    Edit::Substitute { a: i, b: j }
+                });
+            }
+            Step::Delete => {
+                i -= 1;
+                path.push(Edit::Delete { a: i });
+            }
+            Step::Insert => {
+                j -= 1;
+                path.push(Edit::Insert { b: j });
+            }
+            Step::Transpose => {
+                i -= 2;
+                j -= 2;
+                path.push(Edit::Transpose { a: i, b: j });
+            }
+        }
+    }
+
+    path.reverse();
+    (grid[grid.len() - 1], path)
+}
+
+/// Return the lowest cost of edits turning one string into another under the cost model, comparing by chars. The
+/// indices of the edits count chars.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance::{self, Qwerty};
+///
+/// let (cost, _) = distance::weighted_str("teh", "the", &Qwerty::default());
+/// assert_eq!(cost, 1.0);
+/// ```
+pub fn weighted_str<C>(a: &str, b: &str, costs: &C) -> (f64, Vec<Edit>)
+where
+    C: CostModel<char>,
+{
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    weighted(&a, &b, costs)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn weighted_unit_costs() {
+        use super::super::{levenshtein, osa};
+        use super::*;
+        use crate::test_util::random_vec;
+
+        let levenshtein_costs = CostTable::new().transpose(f64::INFINITY);
+        let osa_costs = CostTable::new();
+
+        let mut state = 37;
+        for _ in 0..300 {
+            let a = random_vec(&mut state, 12, 3);
+            let b = random_vec(&mut state, 12, 3);
+
+            assert_eq!(
+                weighted(&a, &b, &levenshtein_costs).0,
+                levenshtein(&a, &b) as f64
+            );
+            assert_eq!(weighted(&a, &b, &osa_costs).0, osa(&a, &b) as f64);
+        }
+    }
+
+    #[test]
+    fn weighted_path_adds_up() {
+        use super::*;
+        use crate::test_util::random_vec;
+
+        let costs = CostFns {
+            insert: |x: &u64| 1.0 + *x as f64,
+            delete: |x: &u64| 0.5 + *x as f64,
+            substitute: |x: &u64, y: &u64| 0.25 * (*x + *y) as f64,
+            transpose: |_: &u64, _: &u64| 0.75,
+        };
+
+        let mut state = 43;
+        for _ in 0..300 {
+            let a = random_vec(&mut state, 12, 4);
+            let b = random_vec(&mut state, 12, 4);
+
+            let (cost, path) = weighted(&a, &b, &costs);
+            let (mut i, mut j) = (0, 0);
+            let mut total = 0.0;
+            let mut rebuilt = Vec::new();
+            for edit in path {
+                match edit {
+                    Edit::Keep { a: x, b: y } => {
+                        assert_eq!((x, y, a[x]), (i, j, b[y]));
+                        rebuilt.push(a[x]);
+                        i += 1;
+                        j += 1;
+                    }
+                    Edit::Substitute { a: x, b: y } => {
+                        assert_eq!((x, y), (i, j));
+                        total += costs.substitute(&a[x], &b[y]);
+                        rebuilt.push(b[y]);
+                        i += 1;
+                        j += 1;
+                    }
+                    Edit::Delete { a: x } => {
+                        assert_eq!(x, i);
+                        total += costs.delete(&a[x]);
+                        i += 1;
+                    }
+                    Edit::Insert { b: y } => {
+                        assert_eq!(y, j);
+                        total += costs.insert(&b[y]);
+                        rebuilt.push(b[y]);
+                        j += 1;
+                    }
+                    Edit::Transpose { a: x, b: y } => {
+                        assert_eq!((x, y), (i, j));
+                        assert_eq!((a[x], a[x + 1]), (b[y + 1], b[y]));
+                        total += costs.transpose(&a[x], &a[x + 1]);
+                        rebuilt.extend([a[x + 1], a[x]]);
+                        i += 2;
+                        j += 2;
+                    }
+                }
+            }
+
+            assert_eq!((i, j), (a.len(), b.len()));
+            assert_eq!(rebuilt, b);
+            assert!((total - cost).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn qwerty_costs() {
+        use super::*;
+
+        let qwerty = Qwerty::default();
+        assert_eq!(qwerty.substitute(&'q', &'w'), 0.5);
+        assert_eq!(qwerty.substitute(&'q', &'a'), 0.5);
+        assert_eq!(qwerty.substitute(&'q', &'2'), 0.5);
+        assert_eq!(qwerty.substitute(&'q', &'s'), 1.0);
+        assert_eq!(qwerty.substitute(&'1', &'!'), 0.25);
+        assert_eq!(qwerty.substitute(&'é', &'e'), 1.0);
+        assert_eq!(qwerty.substitute(&'é', &'ü'), 1.0);
+        assert!(!Qwerty::is_adjacent('p', 'a'));
+        assert!(Qwerty::is_adjacent('m', 'K'));
+        assert!(Qwerty::is_adjacent('/', '\''));
+
+        assert_eq!(weighted_str("", "", &qwerty), (0.0, Vec::new()));
+        assert_eq!(weighted_str("helo", "hello", &qwerty).0, 1.0);
+        assert_eq!(weighted_str("hwllo", "hello", &qwerty).0, 0.5);
+        assert_eq!(weighted_str("hrllp", "hello", &qwerty).0, 1.0);
+    }
+}
@@ -1,5 +1,17 @@
-pub use self::subsequence::LCSubsequence;
+pub use self::bit_parallel::BitPattern;
+pub use self::subsequence::{LCSubsequence, LcsMode};
 pub use self::substring::LCSubstring;
+pub use self::suffix_array::{
+    longest_common_substring, longest_common_substring_str, CommonSubstring, SuffixArray,
+};
+pub use self::suffix_automaton::SuffixAutomaton;
 
+pub mod align;
+pub mod diff;
+pub mod distance;
+
+mod bit_parallel;
 mod subsequence;
 mod substring;
+mod suffix_array;
+mod suffix_automaton;
@@ -1,8 +1,32 @@
 use std::cmp::max;
 use std::fmt::{Display, Error, Formatter};
+use std::mem::swap;
+use std::sync::OnceLock;
 
-/// An objecft that will hold the given two strings and their grid so you can later ask for the longest common subsequence
-/// without having to re-create the 2d grid
+use super::BitPattern;
+
+/// How an LCSubsequence finds the longest common subsequence of sequences of length n and m
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LcsMode {
+    /// Store the full n by m grid and read the subsequence back from it. This needs O(nm) memory, two 100k long
+    /// inputs need about 40 GB. Strings only build the grid once the subsequence is asked for.
+    Grid,
+    /// Hirschberg's algorithm, which finds the subsequence up front by splitting the left sequence in half and the
+    /// right one where the halves meet on the subsequence. About twice the work of the grid in O(n + m) memory.
+    Hirschberg,
+    /// Only find the length, with two rolling rows as long as the shorter sequence. Asking for the subsequence
+    /// afterwards runs Hirschberg's algorithm at that time.
+    LengthOnly,
+}
+
+/// An objecft that will hold the given two sequences and their grid so you can later ask for the longest common
+/// subsequence without having to re-create the 2d grid. The sequences can hold any values that compare with `Eq`,
+/// like tokens, lines or bytes, and `new_subsequence` compares two strings by their chars. Large inputs should use
+/// `LcsMode::Hirschberg` or `LcsMode::LengthOnly`, which need no grid.
+///
+/// Strings find the length with a `BitPattern`, a 64 bit word of the grid at a time, in every mode but
+/// `LcsMode::Hirschberg`. Other values are only known to be `Eq`, so `new` and `with_mode` fill the grid or its rows
+/// cell by cell, use `BitPattern::lcs_len` for the faster length of values that can be hashed.
 ///
 /// # Examples
 /// ```
@@ -12,17 +36,41 @@ use std::fmt::{Display, Error, Formatter};
 ///
 /// println!("length of the lcs is {}", lcs);
 /// assert_eq!(lcs.get_longest_subsequence(), Some("aba".to_string()));
+///
+/// let old = ["fn", "main", "(", ")", "{", "}"];
+/// let new = ["pub", "fn", "run", "(", "x", ")", "{", "}"];
+/// let lcs = LCSubsequence::new(&old, &new);
+///
+/// assert_eq!(lcs.subsequence(), vec!["fn", "(", ")", "{", "}"]);
+/// assert_eq!(lcs.subsequence_indices()[..2], [(0, 1), (2, 3)]);
 /// ```
-#[derive(Debug)]
-pub struct LCSubsequence {
+///
+/// ```
+/// use rs_algo::compare::{LCSubsequence, LcsMode};
+///
+/// let left = "ab".repeat(2_000);
+/// let right = "ba".repeat(2_000);
+///
+/// let lcs = LCSubsequence::new_subsequence_with_mode(&left, &right, LcsMode::LengthOnly);
+/// assert_eq!(lcs.subsequence_len, 3_999);
+///
+/// let lcs = LCSubsequence::new_subsequence_with_mode("abttac", "taccba", LcsMode::Hirschberg);
+/// assert_eq!(lcs.subsequence_len, 3);
+/// assert_eq!(lcs.get_longest_subsequence().map(|s| s.len()), Some(3));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LCSubsequence<T = char> {
     /// the length of the found longest common subsequence
     pub subsequence_len: u32,
-    grid: Vec<Vec<u32>>,
-    left_str: String,
-    right_str: String,
+    mode: LcsMode,
+    // filled up front for sequences, and on the first call to subsequence_indices for strings
+    grid: OnceLock<Vec<Vec<u32>>>,
+    pairs: Vec<(usize, usize)>,
+    left: Vec<T>,
+    right: Vec<T>,
 }
 
-impl Display for LCSubsequence {
+impl<T> Display for LCSubsequence<T> {
     /// Print the size of the longest subsequence
     /// # Examples
     /// ```
@@ -37,72 +85,246 @@ impl Display for LCSubsequence {
     }
 }
 
-impl LCSubsequence {
-    /// This will return a new LCS object. The subsequence grid will be created and stored, the longest subsequence length
-    /// will be known at this time.
-    pub fn new_subsequence<T>(left: T, right: T) -> Self
+impl LCSubsequence<char> {
+    /// This will return a new LCS object comparing the chars of two strings. The longest subsequence length will be
+    /// known at this time, the subsequence grid is created and stored when the subsequence is first asked for.
+    pub fn new_subsequence<S>(left: S, right: S) -> Self
     where
-        T: AsRef<str>,
+        S: AsRef<str>,
     {
-        let left_chars: Vec<char> = left.as_ref().chars().collect();
-        let right_chars: Vec<char> = right.as_ref().chars().collect();
-        let left_len = left_chars.len();
-        let right_len = right_chars.len();
+        LCSubsequence::new_subsequence_with_mode(left, right, LcsMode::Grid)
+    }
+
+    /// This will return a new LCS object comparing the chars of two strings in the given mode. In `LcsMode::Grid`
+    /// and `LcsMode::LengthOnly` the length is found with a `BitPattern`, a 64 bit word of the grid at a time.
+    pub fn new_subsequence_with_mode<S>(left: S, right: S, mode: LcsMode) -> Self
+    where
+        S: AsRef<str>,
+    {
+        let left: Vec<char> = left.as_ref().chars().collect();
+        let right: Vec<char> = right.as_ref().chars().collect();
+        if mode == LcsMode::Hirschberg {
+            return LCSubsequence::with_mode(&left, &right, mode);
+        }
+
+        // chars can be hashed, so the length comes from the bit-parallel kernel
+        let (long, short) = if left.len() < right.len() {
+            (&right, &left)
+        } else {
+            (&left, &right)
+        };
+        LCSubsequence {
+            subsequence_len: BitPattern::new(short).lcs_len(long) as u32,
+            mode,
+            grid: OnceLock::new(),
+            pairs: Vec::new(),
+            left,
+            right,
+        }
+    }
+
+    /// Return the longest subsequence string as an option.
+    /// If there was no longest common subsequence found during the new_subsequence call, this will return None,
+    /// otherwise the lcs as a string will be returned.
+    pub fn get_longest_subsequence(&self) -> Option<String> {
+        if self.subsequence_len == 0 {
+            return None;
+        }
+
+        Some(self.subsequence().into_iter().collect())
+    }
+}
 
+impl<T> LCSubsequence<T>
+where
+    T: Eq + Clone,
+{
+    /// This will return a new LCS object over two sequences. The subsequence grid will be created and stored cell by
+    /// cell, the longest subsequence length will be known at this time.
+    pub fn new(left: &[T], right: &[T]) -> Self {
+        LCSubsequence::with_mode(left, right, LcsMode::Grid)
+    }
+
+    /// This will return a new LCS object over two sequences in the given mode. The longest subsequence length will be
+    /// known at this time.
+    pub fn with_mode(left: &[T], right: &[T], mode: LcsMode) -> Self {
         let mut l = LCSubsequence {
             subsequence_len: 0,
-            grid: vec![vec![0; right_len + 1]; left_len + 1],
-            left_str: left.as_ref().to_string(),
-            right_str: right.as_ref().to_string(),
+            mode,
+            grid: OnceLock::new(),
+            pairs: Vec::new(),
+            left: left.to_vec(),
+            right: right.to_vec(),
         };
 
-        for i in 1..left_len + 1 {
-            for j in 1..right_len + 1 {
-                if left_chars[i - 1] == right_chars[j - 1] {
-                    l.grid[i][j] = l.grid[i - 1][j - 1] + 1;
+        match mode {
+            LcsMode::Grid => {
+                let grid = fill_grid(left, right);
+                l.subsequence_len = grid[left.len()][right.len()];
+                l.grid = OnceLock::from(grid);
+            }
+            LcsMode::Hirschberg => {
+                hirschberg(left, 0, right, 0, &mut l.pairs);
+                l.subsequence_len = l.pairs.len() as u32;
+            }
+            LcsMode::LengthOnly => {
+                // the rows run along the shorter sequence
+                let (long, short) = if left.len() < right.len() {
+                    (right, left)
                 } else {
-                    l.grid[i][j] = max(l.grid[i][j - 1], l.grid[i - 1][j]);
-                }
+                    (left, right)
+                };
+                l.subsequence_len = lcs_row(long, short, false)[short.len()];
             }
         }
 
-        l.subsequence_len = l.grid[left_len][right_len];
         l
     }
 
-    /// Return the longest subsequence string as an option.
-    /// If there was no longest common subsequence found during the new_subsequence call, this will return None,
-    /// otherwise the lcs as a string will be returned.
-    pub fn get_longest_subsequence(&self) -> Option<String> {
-        if self.subsequence_len == 0 {
-            return None;
+    /// Return the index pairs of the longest common subsequence. Each pair is the index of a value in the left
+    /// sequence and the index of the equal value in the right one, in increasing order.
+    pub fn subsequence_indices(&self) -> Vec<(usize, usize)> {
+        match self.mode {
+            LcsMode::Grid => self.grid_indices(),
+            LcsMode::Hirschberg => self.pairs.clone(),
+            LcsMode::LengthOnly => {
+                let mut pairs = Vec::with_capacity(self.subsequence_len as usize);
+                hirschberg(&self.left, 0, &self.right, 0, &mut pairs);
+                pairs
+            }
         }
+    }
 
-        let mut i = self.grid.len() - 1;
-        let mut j = self.grid[0].len() - 1;
-        let mut output: Vec<u16> = Vec::new();
-        let left_chars: Vec<char> = self.left_str.chars().collect();
-        let right_chars: Vec<char> = self.right_str.chars().collect();
+    fn grid_indices(&self) -> Vec<(usize, usize)> {
+        let grid = self.grid.get_or_init(|| fill_grid(&self.left, &self.right));
+        let mut i = self.left.len();
+        let mut j = self.right.len();
+        let mut pairs = Vec::with_capacity(self.subsequence_len as usize);
 
         while i > 0 && j > 0 {
-            if left_chars[i - 1] == right_chars[j - 1] {
-                output.push(left_chars[i - 1] as u16);
+            if self.left[i - 1] == self.right[j - 1] {
+                pairs.push((i - 1, j - 1));
                 i -= 1;
                 j -= 1;
+            } else if grid[i - 1][j] >= grid[i][j - 1] {
+                i -= 1;
             } else {
-                if self.grid[i - 1][j] >= self.grid[i][j - 1] {
-                    i -= 1;
-                } else {
-                    j -= 1;
-                }
+                j -= 1;
             }
         }
 
-        output.reverse();
-        Some(String::from_utf16(output.as_ref()).unwrap())
+        pairs.reverse();
+        pairs
+    }
+
+    /// Return the values of the longest common subsequence, empty if the sequences have nothing in common.
+    pub fn subsequence(&self) -> Vec<T> {
+        self.subsequence_indices()
+            .into_iter()
+            .map(|(i, _)| self.left[i].clone())
+            .collect()
     }
 }
 
+fn fill_grid<T>(left: &[T], right: &[T]) -> Vec<Vec<u32>>
+where
+    T: Eq,
+{
+    let mut grid = vec![vec![0; right.len() + 1]; left.len() + 1];
+
+    for i in 1..left.len() + 1 {
+        for j in 1..right.len() + 1 {
+            if left[i - 1] == right[j - 1] {
+                grid[i][j] = grid[i - 1][j - 1] + 1;
+            } else {
+                grid[i][j] = max(grid[i][j - 1], grid[i - 1][j]);
+            }
+        }
+    }
+
+    grid
+}
+
+// the LCS lengths of left against each prefix of right, or with reverse against each suffix of right counting from
+// the end, kept in two rows
+fn lcs_row<T>(left: &[T], right: &[T], reverse: bool) -> Vec<u32>
+where
+    T: Eq,
+{
+    let m = right.len();
+    let mut above = vec![0; m + 1];
+    let mut row = vec![0; m + 1];
+
+    for i in 0..left.len() {
+        let a = if reverse {
+            &left[left.len() - 1 - i]
+        } else {
+            &left[i]
+        };
+        for j in 0..m {
+            let b = if reverse {
+                &right[m - 1 - j]
+            } else {
+                &right[j]
+            };
+            row[j + 1] = if a == b {
+                above[j] + 1
+            } else {
+                max(row[j], above[j + 1])
+            };
+        }
+        swap(&mut above, &mut row);
+    }
+
+    above
+}
+
+// push the index pairs of a longest common subsequence of left and right, offset by where they start
+fn hirschberg<T>(
+    left: &[T],
+    left_start: usize,
+    right: &[T],
+    right_start: usize,
+    pairs: &mut Vec<(usize, usize)>,
+) where
+    T: Eq,
+{
+    if left.is_empty() || right.is_empty() {
+        return;
+    }
+    if left.len() == 1 {
+        if let Some(j) = right.iter().position(|b| *b == left[0]) {
+            pairs.push((left_start, right_start + j));
+        }
+        return;
+    }
+
+    // split the right side where the best subsequence of the top half meets the best of the bottom half, the rows
+    // are dropped before recursing so only one pair is alive at a time
+    let mid = left.len() / 2;
+    let split = {
+        let top = lcs_row(&left[..mid], right, false);
+        let bottom = lcs_row(&left[mid..], right, true);
+        let m = right.len();
+        (0..=m).max_by_key(|j| top[*j] + bottom[m - j]).unwrap()
+    };
+
+    hirschberg(
+        &left[..mid],
+        left_start,
+        &right[..split],
+        right_start,
+        pairs,
+    );
+    hirschberg(
+        &left[mid..],
+        left_start + mid,
+        &right[split..],
+        right_start + split,
+        pairs,
+    );
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -140,4 +362,71 @@ mod tests {
         assert_eq!(lcs.subsequence_len, 0);
         assert_eq!(lcs.get_longest_subsequence(), None);
     }
+
+    #[test]
+    fn lc_subsequence_outside_bmp_test() {
+        use super::*;
+
+        // these chars do not fit in a u16
+        let lcs = LCSubsequence::new_subsequence("a😀b𝄞c", "😀xb𝄞");
+
+        assert_eq!(lcs.subsequence_len, 3);
+        assert_eq!(lcs.get_longest_subsequence(), Some("😀b𝄞".to_string()));
+    }
+
+    #[test]
+    fn lc_subsequence_generic_test() {
+        use super::*;
+
+        let lcs = LCSubsequence::new(b"GAATTCAG", b"GGATCGA");
+        assert_eq!(lcs.subsequence_len, 5);
+        assert_eq!(lcs.subsequence().len(), 5);
+
+        for (i, j) in lcs.subsequence_indices() {
+            assert_eq!(b"GAATTCAG"[i], b"GGATCGA"[j]);
+        }
+        let pairs = lcs.subsequence_indices();
+        assert!(pairs.windows(2).all(|w| w[0].0 < w[1].0 && w[0].1 < w[1].1));
+
+        let left = ["a", "b"];
+        let right = ["c"];
+        let lcs = LCSubsequence::new(&left, &right);
+        assert!(lcs.subsequence().is_empty());
+        assert!(lcs.subsequence_indices().is_empty());
+    }
+
+    #[test]
+    fn lc_subsequence_modes_test() {
+        use super::*;
+        use crate::test_util::random_vec;
+
+        let mut state = 41;
+        for _ in 0..300 {
+            let left = random_vec(&mut state, 40, 4);
+            let right = random_vec(&mut state, 40, 4);
+            let grid = LCSubsequence::new(&left, &right);
+
+            for mode in [LcsMode::Hirschberg, LcsMode::LengthOnly] {
+                let lcs = LCSubsequence::with_mode(&left, &right, mode);
+                assert_eq!(lcs.subsequence_len, grid.subsequence_len);
+
+                let pairs = lcs.subsequence_indices();
+                assert_eq!(pairs.len(), grid.subsequence_len as usize);
+                assert!(pairs.iter().all(|(i, j)| left[*i] == right[*j]));
+                assert!(pairs.windows(2).all(|w| w[0].0 < w[1].0 && w[0].1 < w[1].1));
+            }
+
+            // strings take the length from a BitPattern and build the same grid when it is needed
+            let left: String = left.iter().map(|v| (b'a' + *v as u8) as char).collect();
+            let right: String = right.iter().map(|v| (b'a' + *v as u8) as char).collect();
+            let lcs = LCSubsequence::new_subsequence(&left, &right);
+            assert_eq!(lcs.subsequence_len, grid.subsequence_len);
+            assert_eq!(lcs.subsequence_indices(), grid.subsequence_indices());
+        }
+
+        let lcs = LCSubsequence::new_subsequence_with_mode("", "abc", LcsMode::Hirschberg);
+        assert_eq!(lcs.get_longest_subsequence(), None);
+        let lcs = LCSubsequence::new_subsequence_with_mode("a😀b𝄞c", "😀xb𝄞", LcsMode::LengthOnly);
+        assert_eq!(lcs.get_longest_subsequence(), Some("😀b𝄞".to_string()));
+    }
 }
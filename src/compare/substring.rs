@@ -1,7 +1,8 @@
 use std::fmt::{Display, Error, Formatter};
 
-/// An objecft that will hold the given two strings and their grid so you can later ask for the longest common substring
-/// without having to re-create the 2d grid
+/// An objecft that will find the longest common substring of the given two sequences, so you can later ask for it
+/// without comparing them again. The sequences can hold any values that compare with `Eq`, like tokens, lines or
+/// bytes, and `new_substring` compares two strings by their chars.
 ///
 /// # Examples
 /// ```
@@ -11,22 +12,25 @@ use std::fmt::{Display, Error, Formatter};
 ///
 /// println!("length of the lc substring is {}", lcs);
 /// assert_eq!(lcs.get_longest_substring(), Some("today ".to_string()));
+///
+/// let lcs = LCSubstring::new(&[3, 1, 4, 1, 5, 9, 2, 6], &[2, 7, 1, 4, 1, 5, 8]);
+///
+/// assert_eq!(lcs.substring(), vec![1, 4, 1, 5]);
+/// assert_eq!(lcs.substring_indices(), vec![(1, 2), (2, 3), (3, 4), (4, 5)]);
 /// ```
-#[derive(Debug)]
-pub struct LCSubstring {
+#[derive(Debug, Clone)]
+pub struct LCSubstring<T = char> {
     /// the length of the found longest common substring
     pub substring_len: u32,
 
-    grid: Vec<Vec<u32>>,
+    // the index one past the end of the substring in the left and right sequence
     max_substring_index: (usize, usize),
-    left_str: String,
-    right_str: String,
+    left: Vec<T>,
 }
 
-impl Display for LCSubstring {
+impl<T> Display for LCSubstring<T> {
     /// Print the size of the longest substring
     /// # Examples
-    /// # Examples
     /// ```
     /// use rs_algo::compare::LCSubstring;
     ///
@@ -39,61 +43,81 @@ impl Display for LCSubstring {
     }
 }
 
-impl LCSubstring {
-    /// This will return a new LCSubstring object. The substring grid will be created and stored, the longest substring length
-    /// will be known at this time.
-    pub fn new_substring<T>(left: T, right: T) -> Self
+impl LCSubstring<char> {
+    /// This will return a new LCSubstring object comparing the chars of two strings. The longest substring will be
+    /// found at this time.
+    pub fn new_substring<S>(left: S, right: S) -> Self
     where
-        T: AsRef<str>,
+        S: AsRef<str>,
     {
-        let left_chars: Vec<char> = left.as_ref().chars().collect();
-        let right_chars: Vec<char> = right.as_ref().chars().collect();
-        let left_len = left_chars.len();
-        let right_len = right_chars.len();
+        let left: Vec<char> = left.as_ref().chars().collect();
+        let right: Vec<char> = right.as_ref().chars().collect();
+        LCSubstring::new(&left, &right)
+    }
+
+    /// Return the longest substring as an option.
+    /// If there was no longest common substring found during the new_substring call, this will return None,
+    /// otherwise the lc substring as a string will be returned.
+    pub fn get_longest_substring(&self) -> Option<String> {
+        if self.substring_len == 0 {
+            return None;
+        }
+
+        Some(self.substring().into_iter().collect())
+    }
+}
 
+impl<T> LCSubstring<T>
+where
+    T: Eq + Clone,
+{
+    /// This will return a new LCSubstring object over two sequences. The longest substring will be found at this time,
+    /// the first one in the left sequence if there are several.
+    pub fn new(left: &[T], right: &[T]) -> Self {
         let mut sub_string = LCSubstring {
             substring_len: 0,
-            grid: vec![vec![0; right_len + 1]; left_len + 1],
             max_substring_index: (0, 0),
-            left_str: left.as_ref().to_string(),
-            right_str: right.as_ref().to_string(),
+            left: left.to_vec(),
         };
 
-        for i in 1..left_len + 1 {
-            for j in 1..right_len + 1 {
-                if left_chars[i - 1] == right_chars[j - 1] {
-                    sub_string.grid[i][j] = sub_string.grid[i - 1][j - 1] + 1;
-
-                    if sub_string.substring_len < sub_string.grid[i][j] {
-                        sub_string.max_substring_index = (i, j);
-                        sub_string.substring_len = sub_string.grid[i][j];
-                    }
+        // only the previous row of the grid is needed to fill the next one
+        let mut previous = vec![0u32; right.len() + 1];
+        let mut row = vec![0u32; right.len() + 1];
+
+        for i in 1..left.len() + 1 {
+            for j in 1..right.len() + 1 {
+                row[j] = if left[i - 1] == right[j - 1] {
+                    previous[j - 1] + 1
+                } else {
+                    0
+                };
+
+                if sub_string.substring_len < row[j] {
+                    sub_string.max_substring_index = (i, j);
+                    sub_string.substring_len = row[j];
                 }
             }
+            std::mem::swap(&mut previous, &mut row);
         }
 
         sub_string
     }
 
-    /// Return the longest substring as an option.
-    /// If there was no longest common substring found during the new_substring call, this will return None,
-    /// otherwise the lc substring as a string will be returned.
-    pub fn get_longest_substring(&self) -> Option<String> {
-        if self.substring_len == 0 {
-            return None;
-        }
+    /// Return the index pairs of the longest common substring. Each pair is the index of a value in the left sequence
+    /// and the index of the equal value in the right one, both counting up by one.
+    pub fn substring_indices(&self) -> Vec<(usize, usize)> {
+        let len = self.substring_len as usize;
+        let (left_end, right_end) = self.max_substring_index;
 
-        let mut lcs: Vec<u16> = Vec::new();
-        let left_str: Vec<char> = self.left_str.chars().collect();
-        let offset: usize = self.max_substring_index.0;
-
-        for i in 0..self.substring_len {
-            let index = (offset - 1) - i as usize;
-            lcs.push(left_str[index as usize] as u16);
-        }
+        (0..len)
+            .map(|k| (left_end - len + k, right_end - len + k))
+            .collect()
+    }
 
-        lcs.reverse();
-        Some(String::from_utf16(lcs.as_ref()).unwrap())
+    /// Return the values of the longest common substring, empty if the sequences have nothing in common.
+    pub fn substring(&self) -> Vec<T> {
+        let end = self.max_substring_index.0;
+        self.left[end - self.substring_len as usize..end].to_vec()
     }
 }
 
@@ -120,6 +144,8 @@ mod tests {
 
         assert_eq!(lcs.substring_len, 0);
         assert_eq!(lcs.get_longest_substring(), None);
+        assert!(lcs.substring().is_empty());
+        assert!(lcs.substring_indices().is_empty());
     }
 
     #[test]
@@ -132,4 +158,32 @@ mod tests {
 
         assert_eq!(lcs.get_longest_substring(), Some("Why hello ".to_string()));
     }
+
+    #[test]
+    fn lc_substring_outside_bmp_test() {
+        use super::*;
+
+        // these chars do not fit in a u16
+        let lcs = LCSubstring::new_substring("x🦀🦀y", "🦀🦀z");
+
+        assert_eq!(lcs.substring_len, 2);
+        assert_eq!(lcs.get_longest_substring(), Some("🦀🦀".to_string()));
+    }
+
+    #[test]
+    fn lc_substring_generic_test() {
+        use super::*;
+
+        let left = ["let", "x", "=", "1", ";"];
+        let right = ["const", "x", "=", "1", ";", "x"];
+        let lcs = LCSubstring::new(&left, &right);
+
+        assert_eq!(lcs.substring_len, 4);
+        assert_eq!(lcs.substring(), vec!["x", "=", "1", ";"]);
+        assert_eq!(lcs.substring_indices()[0], (1, 1));
+
+        let lcs = LCSubstring::new(b"abcabc".as_ref(), b"zabcz".as_ref());
+        assert_eq!(lcs.substring(), b"abc".to_vec());
+        assert_eq!(lcs.substring_indices(), vec![(0, 1), (1, 2), (2, 3)]);
+    }
 }
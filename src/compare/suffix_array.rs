@@ -0,0 +1,490 @@
+use std::cmp::Ordering;
+
+// an empty slot while inducing the suffix array
+const EMPTY: usize = usize::MAX;
+
+/// A suffix array over a sequence, along with its longest common prefix (LCP) array. The suffix array is built with
+/// SA-IS in O(n) time and the LCP array with Kasai's algorithm, also in O(n), so this works on texts far larger than
+/// the quadratic comparison in `LCSubstring` allows.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::SuffixArray;
+///
+/// let sa = SuffixArray::from("banana");
+///
+/// assert_eq!(sa.suffixes(), &[5, 3, 1, 0, 4, 2]);
+/// assert_eq!(sa.lcp(), &[0, 1, 3, 0, 0, 2]);
+/// assert_eq!(sa.count(&['a', 'n']), 2);
+/// assert_eq!(sa.locate(&['a', 'n', 'a']), vec![1, 3]);
+/// assert_eq!(sa.longest_repeated_substring(), Some(&['a', 'n', 'a'][..]));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SuffixArray<T> {
+    text: Vec<T>,
+    suffixes: Vec<usize>,
+    lcp: Vec<usize>,
+}
+
+/// The longest common substring of two sequences, found by `longest_common_substring`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommonSubstring {
+    /// where the substring starts in the left sequence
+    pub left: usize,
+    /// where the substring starts in the right sequence
+    pub right: usize,
+    /// the length of the substring
+    pub len: usize,
+}
+
+impl<T> SuffixArray<T>
+where
+    T: Ord + Clone,
+{
+    /// This will return a new SuffixArray over the given sequence. The suffix and LCP arrays are built at this time.
+    pub fn new(text: &[T]) -> Self {
+        let (symbols, alphabet) = rank_symbols(&[text]);
+        let suffixes = build_suffixes(symbols, alphabet);
+        let lcp = kasai(text, &suffixes);
+
+        SuffixArray {
+            text: text.to_vec(),
+            suffixes,
+            lcp,
+        }
+    }
+
+    /// The sequence the suffix array was built over
+    pub fn text(&self) -> &[T] {
+        &self.text
+    }
+
+    /// The start of every suffix of the text, in sorted order
+    pub fn suffixes(&self) -> &[usize] {
+        &self.suffixes
+    }
+
+    /// The longest common prefix array, entry i is the length of the prefix shared by the suffixes at i - 1 and i in
+    /// sorted order. The first entry is always 0.
+    pub fn lcp(&self) -> &[usize] {
+        &self.lcp
+    }
+
+    // the range of sorted suffixes that start with the pattern
+    fn matching(&self, pattern: &[T]) -> (usize, usize) {
+        let prefix = |suffix: usize| {
+            let end = (suffix + pattern.len()).min(self.text.len());
+            self.text[suffix..end].cmp(pattern)
+        };
+
+        let low = self
+            .suffixes
+            .partition_point(|suffix| prefix(*suffix) == Ordering::Less);
+        let high = self
+            .suffixes
+            .partition_point(|suffix| prefix(*suffix) != Ordering::Greater);
+
+        (low, high)
+    }
+
+    /// Return how many times the pattern occurs in the text, in O(m log n) time.
+    pub fn count(&self, pattern: &[T]) -> usize {
+        let (low, high) = self.matching(pattern);
+        high - low
+    }
+
+    /// Return where the pattern occurs in the text, in ascending order.
+    pub fn locate(&self, pattern: &[T]) -> Vec<usize> {
+        let (low, high) = self.matching(pattern);
+        let mut found = self.suffixes[low..high].to_vec();
+
+        found.sort_unstable();
+        found
+    }
+
+    /// Return true if the pattern occurs in the text
+    pub fn contains(&self, pattern: &[T]) -> bool {
+        self.count(pattern) > 0
+    }
+
+    /// Return the longest substring that occurs at least twice in the text, the occurrences may overlap. None is
+    /// returned if no value repeats.
+    pub fn longest_repeated_substring(&self) -> Option<&[T]> {
+        let (i, len) = self
+            .lcp
+            .iter()
+            .enumerate()
+            .max_by_key(|(i, len)| (**len, std::cmp::Reverse(*i)))?;
+
+        if *len == 0 {
+            return None;
+        }
+
+        let start = self.suffixes[i];
+        Some(&self.text[start..start + len])
+    }
+}
+
+impl From<&str> for SuffixArray<char> {
+    /// Build a suffix array over the chars of a string, positions count chars rather than bytes
+    fn from(text: &str) -> Self {
+        let chars: Vec<char> = text.chars().collect();
+        SuffixArray::new(&chars)
+    }
+}
+
+/// Find the longest common substring of two sequences in O(n + m) time. Both sequences are joined into a single
+/// suffix array, the answer is the longest LCP between neighbouring suffixes that come from different sequences.
+/// None is returned if the sequences have no value in common.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::{longest_common_substring, CommonSubstring};
+///
+/// let left: Vec<char> = "sunny today outside".chars().collect();
+/// let right: Vec<char> = "today is cold".chars().collect();
+///
+/// let common = longest_common_substring(&left, &right).unwrap();
+/// assert_eq!(common, CommonSubstring { left: 6, right: 0, len: 6 });
+/// ```
+pub fn longest_common_substring<T>(left: &[T], right: &[T]) -> Option<CommonSubstring>
+where
+    T: Ord + Clone,
+{
+    let (symbols, alphabet) = rank_symbols(&[left, right]);
+    let suffixes = build_suffixes(symbols.clone(), alphabet);
+
+    // both sequences with the separator between them, the separator never matches so no prefix runs across it
+    let joined = &symbols[..symbols.len() - 1];
+    let lcp = kasai(joined, &suffixes);
+
+    let mut best: Option<CommonSubstring> = None;
+    for i in 1..suffixes.len() {
+        let (a, b) = (suffixes[i - 1], suffixes[i]);
+        let a_left = a < left.len();
+        let b_left = b < left.len();
+
+        if a_left == b_left || lcp[i] <= best.map_or(0, |found| found.len) {
+            continue;
+        }
+
+        let (l, r) = if a_left { (a, b) } else { (b, a) };
+        best = Some(CommonSubstring {
+            left: l,
+            right: r - left.len() - 1,
+            len: lcp[i],
+        });
+    }
+
+    best
+}
+
+/// Find the longest common substring of two strings in O(n + m) time, comparing by chars.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::longest_common_substring_str;
+///
+/// let common = longest_common_substring_str("!!!!Hello WorldXXXXX", "XXX   Hello World@cvcvcvc");
+/// assert_eq!(common, Some("Hello World".to_string()));
+/// ```
+pub fn longest_common_substring_str(left: &str, right: &str) -> Option<String> {
+    let left: Vec<char> = left.chars().collect();
+    let right: Vec<char> = right.chars().collect();
+
+    longest_common_substring(&left, &right)
+        .map(|found| left[found.left..found.left + found.len].iter().collect())
+}
+
+// map each value to its rank among the distinct values, starting at 2. Sequences are joined with a 1 between them and
+// a 0 sentinel is added at the end. The number of symbols used is returned with them.
+fn rank_symbols<T>(sequences: &[&[T]]) -> (Vec<usize>, usize)
+where
+    T: Ord + Clone,
+{
+    let mut distinct: Vec<&T> = sequences.iter().flat_map(|s| s.iter()).collect();
+    distinct.sort_unstable();
+    distinct.dedup();
+
+    let mut symbols = Vec::new();
+    for (i, sequence) in sequences.iter().enumerate() {
+        if i > 0 {
+            symbols.push(1);
+        }
+        for value in sequence.iter() {
+            symbols.push(distinct.binary_search(&value).unwrap() + 2);
+        }
+    }
+    symbols.push(0);
+
+    (symbols, distinct.len() + 2)
+}
+
+// the suffix array of the symbols without the sentinel's own suffix
+fn build_suffixes(symbols: Vec<usize>, alphabet: usize) -> Vec<usize> {
+    let mut suffixes = sais(&symbols, alphabet);
+    suffixes.remove(0);
+    suffixes
+}
+
+// Kasai's algorithm, walking the suffixes in text order lets each LCP start from one less than the previous one
+fn kasai<T>(text: &[T], suffixes: &[usize]) -> Vec<usize>
+where
+    T: PartialEq,
+{
+    let n = suffixes.len();
+    let mut rank = vec![0; n];
+    for (i, suffix) in suffixes.iter().enumerate() {
+        rank[*suffix] = i;
+    }
+
+    let mut lcp = vec![0; n];
+    let mut len = 0;
+
+    for i in 0..n {
+        if rank[i] == 0 {
+            len = 0;
+            continue;
+        }
+
+        let previous = suffixes[rank[i] - 1];
+        while i + len < n && previous + len < n && text[i + len] == text[previous + len] {
+            len += 1;
+        }
+
+        lcp[rank[i]] = len;
+        len = len.saturating_sub(1);
+    }
+
+    lcp
+}
+
+// SA-IS suffix array construction. The symbols need to end with a 0 sentinel that appears nowhere else, alphabet is
+// one more than the largest symbol.
+fn sais(symbols: &[usize], alphabet: usize) -> Vec<usize> {
+    let n = symbols.len();
+    if n == 1 {
+        return vec![0];
+    }
+
+    // true for S-type suffixes, smaller than the suffix after them
+    let mut s_type = vec![false; n];
+    s_type[n - 1] = true;
+    for i in (0..n - 1).rev() {
+        s_type[i] = symbols[i] < symbols[i + 1] || (symbols[i] == symbols[i + 1] && s_type[i + 1]);
+    }
+
+    let is_lms = |i: usize| i > 0 && s_type[i] && !s_type[i - 1];
+    let lms: Vec<usize> = (1..n).filter(|i| is_lms(*i)).collect();
+
+    let mut buckets = vec![0; alphabet];
+    for symbol in symbols.iter() {
+        buckets[*symbol] += 1;
+    }
+
+    // sort the LMS substrings
+    let mut suffixes = vec![EMPTY; n];
+    induce(symbols, &s_type, &buckets, &lms, &mut suffixes);
+
+    // name each LMS substring by its rank, equal substrings share a name
+    let mut names = vec![EMPTY; n];
+    let mut name = 0;
+    let mut previous = EMPTY;
+
+    for suffix in suffixes.iter().filter(|suffix| is_lms(**suffix)) {
+        if previous == EMPTY || !lms_equal(symbols, &s_type, previous, *suffix) {
+            name += 1;
+        }
+        names[*suffix] = name - 1;
+        previous = *suffix;
+    }
+
+    let reduced: Vec<usize> = lms.iter().map(|i| names[*i]).collect();
+
+    // with repeated names the order of the LMS suffixes needs a recursive sort
+    let order = if name < lms.len() {
+        sais(&reduced, name)
+    } else {
+        let mut order = vec![0; lms.len()];
+        for (i, name) in reduced.iter().enumerate() {
+            order[*name] = i;
+        }
+        order
+    };
+
+    let sorted_lms: Vec<usize> = order.iter().map(|i| lms[*i]).collect();
+    induce(symbols, &s_type, &buckets, &sorted_lms, &mut suffixes);
+
+    suffixes
+}
+
+// place the LMS suffixes at the ends of their buckets, then induce the L-type and S-type suffixes from them
+fn induce(
+    symbols: &[usize],
+    s_type: &[bool],
+    buckets: &[usize],
+    lms: &[usize],
+    suffixes: &mut [usize],
+) {
+    let bucket_starts = || {
+        let mut sum = 0;
+        buckets
+            .iter()
+            .map(|size| {
+                sum += size;
+                sum - size
+            })
+            .collect::<Vec<usize>>()
+    };
+    let bucket_ends = || {
+        let mut sum = 0;
+        buckets
+            .iter()
+            .map(|size| {
+                sum += size;
+                sum
+            })
+            .collect::<Vec<usize>>()
+    };
+
+    suffixes.fill(EMPTY);
+
+    let mut ends = bucket_ends();
+    for i in lms.iter().rev() {
+        ends[symbols[*i]] -= 1;
+        suffixes[ends[symbols[*i]]] = *i;
+    }
+
+    let mut starts = bucket_starts();
+    for index in 0..suffixes.len() {
+        let suffix = suffixes[index];
+        if suffix != EMPTY && suffix > 0 && !s_type[suffix - 1] {
+            let symbol = symbols[suffix - 1];
+            suffixes[starts[symbol]] = suffix - 1;
+            starts[symbol] += 1;
+        }
+    }
+
+    let mut ends = bucket_ends();
+    for index in (0..suffixes.len()).rev() {
+        let suffix = suffixes[index];
+        if suffix != EMPTY && suffix > 0 && s_type[suffix - 1] {
+            let symbol = symbols[suffix - 1];
+            ends[symbol] -= 1;
+            suffixes[ends[symbol]] = suffix - 1;
+        }
+    }
+}
+
+// compare the LMS substrings starting at a and b, each runs up to and including the next LMS position
+fn lms_equal(symbols: &[usize], s_type: &[bool], a: usize, b: usize) -> bool {
+    let is_lms = |i: usize| i > 0 && s_type[i] && !s_type[i - 1];
+    let mut i = 0;
+
+    loop {
+        if symbols[a + i] != symbols[b + i] || s_type[a + i] != s_type[b + i] {
+            return false;
+        }
+        if i > 0 && (is_lms(a + i) || is_lms(b + i)) {
+            return is_lms(a + i) && is_lms(b + i);
+        }
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    fn naive_suffixes(text: &[u8]) -> Vec<usize> {
+        let mut suffixes: Vec<usize> = (0..text.len()).collect();
+        suffixes.sort_by(|a, b| text[*a..].cmp(&text[*b..]));
+        suffixes
+    }
+
+    #[test]
+    fn suffix_array_matches_naive() {
+        use super::*;
+        use crate::test_util::random;
+
+        let mut state = 7;
+        for len in 0..200 {
+            let text: Vec<u8> = (0..len)
+                .map(|_| b'a' + random(&mut state, 1 + len % 4) as u8)
+                .collect();
+            let sa = SuffixArray::new(&text);
+
+            assert_eq!(sa.suffixes(), &naive_suffixes(&text)[..]);
+
+            for i in 1..text.len() {
+                let (a, b) = (sa.suffixes()[i - 1], sa.suffixes()[i]);
+                let common = text[a..]
+                    .iter()
+                    .zip(text[b..].iter())
+                    .take_while(|(x, y)| x == y)
+                    .count();
+                assert_eq!(sa.lcp()[i], common);
+            }
+        }
+    }
+
+    #[test]
+    fn suffix_array_queries() {
+        use super::*;
+
+        let text = "abracadabra abracadabra";
+        let sa = SuffixArray::from(text);
+        let pattern: Vec<char> = "abra".chars().collect();
+
+        assert_eq!(sa.count(&pattern), 4);
+        assert_eq!(sa.locate(&pattern), vec![0, 7, 12, 19]);
+        assert_eq!(sa.count(&['z']), 0);
+        assert!(!sa.contains(&['c', 'a', 'c']));
+        assert_eq!(sa.count(&[]), text.len());
+
+        let repeated: String = sa.longest_repeated_substring().unwrap().iter().collect();
+        assert_eq!(repeated, "abracadabra");
+
+        assert_eq!(SuffixArray::from("abc").longest_repeated_substring(), None);
+        assert_eq!(SuffixArray::from("").suffixes().len(), 0);
+    }
+
+    #[test]
+    fn suffix_array_unicode() {
+        use super::*;
+
+        let sa = SuffixArray::from("😀a😀a😀");
+        assert_eq!(sa.locate(&['😀', 'a']), vec![0, 2]);
+        assert_eq!(
+            sa.longest_repeated_substring(),
+            Some(&['😀', 'a', '😀'][..])
+        );
+    }
+
+    #[test]
+    fn suffix_array_common_substring() {
+        use super::*;
+
+        assert_eq!(
+            longest_common_substring_str(
+                "Why hello world you world hello",
+                "subby world Why hello red"
+            ),
+            Some("Why hello ".to_string())
+        );
+        assert_eq!(longest_common_substring_str("abc", "xyz"), None);
+        assert_eq!(longest_common_substring_str("", "xyz"), None);
+        assert_eq!(
+            longest_common_substring_str("né😀é", "😀é"),
+            Some("😀é".to_string())
+        );
+
+        let common = longest_common_substring(&[1, 2, 3, 4, 5], &[9, 3, 4, 5, 9]).unwrap();
+        assert_eq!(
+            common,
+            CommonSubstring {
+                left: 2,
+                right: 1,
+                len: 3
+            }
+        );
+    }
+}
@@ -0,0 +1,376 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use super::CommonSubstring;
+
+#[derive(Debug, Clone)]
+struct State<T> {
+    // the length of the longest string that ends in this state
+    len: usize,
+    // the suffix link, None only for the root
+    link: Option<usize>,
+    next: BTreeMap<T, usize>,
+    // where the first occurrence of this state's strings ends in the text
+    first_end: usize,
+    // how many times this state's strings occur in the text
+    occurrences: usize,
+    // how many distinct non-empty strings can be read starting from this state
+    paths: u64,
+}
+
+/// A suffix automaton, the smallest automaton that accepts every substring of a text. It is built online in O(n)
+/// time and has at most 2n states, every question below is answered by walking it.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::SuffixAutomaton;
+///
+/// let sam = SuffixAutomaton::from("abab");
+///
+/// assert_eq!(sam.distinct_substrings(), 7);
+/// assert_eq!(sam.occurrences(&['a', 'b']), 2);
+/// assert_eq!(sam.kth_substring(3), Some(vec!['a', 'b', 'a']));
+/// assert_eq!(sam.shortest_absent(&['a', 'b']), Some(vec!['a', 'a']));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SuffixAutomaton<T> {
+    states: Vec<State<T>>,
+    text_len: usize,
+}
+
+impl<T> SuffixAutomaton<T>
+where
+    T: Ord + Clone,
+{
+    /// This will return a new SuffixAutomaton over the given sequence. Occurrence and path counts are worked out at
+    /// this time.
+    pub fn new(text: &[T]) -> Self {
+        let mut sam = SuffixAutomaton {
+            states: vec![State {
+                len: 0,
+                link: None,
+                next: BTreeMap::new(),
+                first_end: 0,
+                occurrences: 0,
+                paths: 0,
+            }],
+            text_len: text.len(),
+        };
+
+        let mut last = 0;
+        for (i, value) in text.iter().enumerate() {
+            last = sam.extend(last, value, i);
+        }
+
+        sam.count();
+        sam
+    }
+
+    // add one value to the end of the text, returning the state for the whole text
+    fn extend(&mut self, last: usize, value: &T, position: usize) -> usize {
+        let current = self.states.len();
+        self.states.push(State {
+            len: self.states[last].len + 1,
+            link: None,
+            next: BTreeMap::new(),
+            first_end: position,
+            occurrences: 1,
+            paths: 0,
+        });
+
+        let mut p = Some(last);
+        while let Some(state) = p {
+            if self.states[state].next.contains_key(value) {
+                break;
+            }
+            self.states[state].next.insert(value.clone(), current);
+            p = self.states[state].link;
+        }
+
+        let state = match p {
+            None => {
+                self.states[current].link = Some(0);
+                return current;
+            }
+            Some(state) => state,
+        };
+
+        let q = self.states[state].next[value];
+        if self.states[state].len + 1 == self.states[q].len {
+            self.states[current].link = Some(q);
+            return current;
+        }
+
+        // q holds longer strings too, split off a clone for the shorter ones
+        let clone = self.states.len();
+        let mut cloned = self.states[q].clone();
+        cloned.len = self.states[state].len + 1;
+        cloned.occurrences = 0;
+        self.states.push(cloned);
+
+        let mut p = Some(state);
+        while let Some(state) = p {
+            if self.states[state].next.get(value) != Some(&q) {
+                break;
+            }
+            self.states[state].next.insert(value.clone(), clone);
+            p = self.states[state].link;
+        }
+
+        self.states[q].link = Some(clone);
+        self.states[current].link = Some(clone);
+        current
+    }
+
+    // fill in occurrence and path counts, longest states first so every state is done before the ones it depends on
+    fn count(&mut self) {
+        let mut order: Vec<usize> = (0..self.states.len()).collect();
+        order.sort_unstable_by_key(|state| std::cmp::Reverse(self.states[*state].len));
+
+        for state in order.iter() {
+            if let Some(link) = self.states[*state].link {
+                self.states[link].occurrences += self.states[*state].occurrences;
+            }
+
+            let paths = self.states[*state]
+                .next
+                .values()
+                .map(|next| 1 + self.states[*next].paths)
+                .sum();
+            self.states[*state].paths = paths;
+        }
+    }
+
+    // the state reached by reading the pattern from the root
+    fn walk(&self, pattern: &[T]) -> Option<usize> {
+        pattern.iter().try_fold(0, |state, value| {
+            self.states[state].next.get(value).copied()
+        })
+    }
+
+    /// The number of states in the automaton, at most 2n for a text of length n
+    pub fn state_count(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Return true if the pattern is a substring of the text
+    pub fn contains(&self, pattern: &[T]) -> bool {
+        self.walk(pattern).is_some()
+    }
+
+    /// Return the number of distinct non-empty substrings of the text
+    pub fn distinct_substrings(&self) -> u64 {
+        self.states[0].paths
+    }
+
+    /// Return how many times the pattern occurs in the text, occurrences may overlap. An empty pattern is counted
+    /// once for every value in the text.
+    pub fn occurrences(&self, pattern: &[T]) -> usize {
+        if pattern.is_empty() {
+            return self.text_len;
+        }
+
+        self.walk(pattern)
+            .map_or(0, |state| self.states[state].occurrences)
+    }
+
+    /// Return the k-th distinct non-empty substring of the text in lexicographic order, counting from 1. None is
+    /// returned if k is 0 or larger than the number of distinct substrings.
+    pub fn kth_substring(&self, k: u64) -> Option<Vec<T>> {
+        if k == 0 || k > self.distinct_substrings() {
+            return None;
+        }
+
+        let mut k = k;
+        let mut state = 0;
+        let mut found = Vec::new();
+
+        while k > 0 {
+            for (value, next) in self.states[state].next.iter() {
+                let below = 1 + self.states[*next].paths;
+
+                if k > below {
+                    k -= below;
+                } else {
+                    found.push(value.clone());
+                    state = *next;
+                    k -= 1;
+                    break;
+                }
+            }
+        }
+
+        Some(found)
+    }
+
+    /// Return the shortest sequence made from the alphabet that does not occur in the text, the lexicographically
+    /// smallest one when there are several. None is returned for an empty alphabet.
+    pub fn shortest_absent(&self, alphabet: &[T]) -> Option<Vec<T>> {
+        let mut alphabet = alphabet.to_vec();
+        alphabet.sort_unstable();
+        alphabet.dedup();
+
+        // breadth first over the states, remembering how each one was first reached
+        let mut parent: Vec<Option<(usize, usize)>> = vec![None; self.states.len()];
+        let mut seen = vec![false; self.states.len()];
+        let mut queue = VecDeque::new();
+        seen[0] = true;
+        queue.push_back(0);
+
+        while let Some(state) = queue.pop_front() {
+            for (i, value) in alphabet.iter().enumerate() {
+                match self.states[state].next.get(value) {
+                    None => {
+                        let mut found = vec![value.clone()];
+                        let mut at = state;
+
+                        while let Some((previous, symbol)) = parent[at] {
+                            found.push(alphabet[symbol].clone());
+                            at = previous;
+                        }
+
+                        found.reverse();
+                        return Some(found);
+                    }
+                    Some(next) if !seen[*next] => {
+                        seen[*next] = true;
+                        parent[*next] = Some((state, i));
+                        queue.push_back(*next);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Find the longest common substring between the text and another sequence. The other sequence is read once,
+    /// value by value, so it can be a stream that is never held in memory. None is returned if the two have no value
+    /// in common.
+    ///
+    /// # Examples
+    /// ```
+    /// use rs_algo::compare::{CommonSubstring, SuffixAutomaton};
+    ///
+    /// let sam = SuffixAutomaton::from("sunny today outside");
+    /// let common = sam.longest_common_substring("today is cold".chars()).unwrap();
+    ///
+    /// assert_eq!(common, CommonSubstring { left: 6, right: 0, len: 6 });
+    /// ```
+    pub fn longest_common_substring<I>(&self, other: I) -> Option<CommonSubstring>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut state = 0;
+        let mut len = 0;
+        let mut best: Option<CommonSubstring> = None;
+
+        for (i, value) in other.into_iter().enumerate() {
+            // follow suffix links until the value can extend the current match
+            while state != 0 && !self.states[state].next.contains_key(&value) {
+                state = self.states[state].link.unwrap_or(0);
+                len = self.states[state].len;
+            }
+
+            match self.states[state].next.get(&value) {
+                Some(next) => {
+                    state = *next;
+                    len += 1;
+                }
+                None => continue,
+            }
+
+            if len > best.map_or(0, |found| found.len) {
+                best = Some(CommonSubstring {
+                    left: self.states[state].first_end + 1 - len,
+                    right: i + 1 - len,
+                    len,
+                });
+            }
+        }
+
+        best
+    }
+}
+
+impl From<&str> for SuffixAutomaton<char> {
+    /// Build a suffix automaton over the chars of a string
+    fn from(text: &str) -> Self {
+        let chars: Vec<char> = text.chars().collect();
+        SuffixAutomaton::new(&chars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    fn substrings(text: &[u8]) -> BTreeSet<Vec<u8>> {
+        let mut found = BTreeSet::new();
+        for i in 0..text.len() {
+            for j in i + 1..=text.len() {
+                found.insert(text[i..j].to_vec());
+            }
+        }
+        found
+    }
+
+    #[test]
+    fn suffix_automaton_matches_naive() {
+        use super::*;
+
+        let texts: Vec<&[u8]> = vec![b"", b"a", b"aaaa", b"abcbc", b"abracadabra", b"mississippi"];
+        for text in texts.iter() {
+            let sam = SuffixAutomaton::new(text);
+            let all = substrings(text);
+
+            assert!(sam.state_count() <= 2 * text.len().max(1));
+            assert_eq!(sam.distinct_substrings(), all.len() as u64);
+
+            for (k, substring) in all.iter().enumerate() {
+                assert_eq!(sam.kth_substring(k as u64 + 1).as_ref(), Some(substring));
+
+                let expected = (0..text.len())
+                    .filter(|i| text[*i..].starts_with(substring))
+                    .count();
+                assert_eq!(sam.occurrences(substring), expected);
+            }
+            assert_eq!(sam.kth_substring(all.len() as u64 + 1), None);
+            assert_eq!(sam.kth_substring(0), None);
+        }
+    }
+
+    #[test]
+    fn suffix_automaton_shortest_absent() {
+        use super::*;
+
+        let sam = SuffixAutomaton::new(b"aababbabb");
+        assert_eq!(sam.shortest_absent(b"ab"), Some(b"aaa".to_vec()));
+        assert_eq!(sam.shortest_absent(b"abc"), Some(b"c".to_vec()));
+        assert_eq!(sam.shortest_absent(b""), None);
+
+        let sam = SuffixAutomaton::new(b"aabba");
+        assert_eq!(sam.shortest_absent(b"ba"), Some(b"aaa".to_vec()));
+        assert_eq!(
+            SuffixAutomaton::new(b"").shortest_absent(b"z"),
+            Some(b"z".to_vec())
+        );
+    }
+
+    #[test]
+    fn suffix_automaton_common_substring() {
+        use super::*;
+
+        let sam = SuffixAutomaton::from("Why hello world you world hello");
+        let common = sam
+            .longest_common_substring("subby world Why hello red car go fast".chars())
+            .unwrap();
+        assert_eq!(common.len, 10);
+        assert_eq!((common.left, common.right), (0, 12));
+
+        assert_eq!(sam.longest_common_substring("qzx".chars()), None);
+        assert!(sam.contains(&['w', 'o', 'r', 'l', 'd']));
+        assert_eq!(sam.occurrences(&['h', 'e', 'l', 'l', 'o']), 2);
+        assert_eq!(sam.occurrences(&[]), 31);
+    }
+}
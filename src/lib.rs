@@ -4,7 +4,8 @@
 pub mod sort;
 
 /// # compare
-/// `compare` comparing two strings to find either the longest common subsequence or substring
+/// `compare` comparing two strings to find either the longest common subsequence or substring, along with suffix
+/// structures for analysing the substrings of a text, distances between sequences and sequence alignment
 ///
 pub mod compare;
 
@@ -17,3 +18,6 @@ pub mod search;
 /// `math` common math operations
 ///
 pub mod math;
+
+#[cfg(test)]
+mod test_util;
@@ -1,8 +1,8 @@
 fn order_ascending(a: u64, b: u64) -> (u64, u64) {
     if a < b {
-        return (a, b);
+        (a, b)
     } else {
-        return (b, a);
+        (b, a)
     }
 }
 
@@ -48,10 +48,10 @@ pub fn gcd(a: u64, b: u64) -> Result<u64, String> {
 /// use rs_algo::math::*;
 ///
 /// let rel_prime = relatively_prime(10, 19);
-/// assert_eq!(true, rel_prime);
+/// assert!(rel_prime);
 /// ```
 pub fn relatively_prime(a: u64, b: u64) -> bool {
-    return gcd(a, b) == Ok(1);
+    gcd(a, b) == Ok(1)
 }
 
 /// This will return a vector of factors if any exists. This will exlclude the obvious factors of one and the number itself.
@@ -99,7 +99,7 @@ mod tests {
 
         assert_eq!(Ok(3), gcd(30, 21));
         assert_eq!(Ok(30), gcd(30, 30));
-        assert_eq!(true, gcd(0, 21).is_err());
+        assert!(gcd(0, 21).is_err());
         assert_eq!(Ok(2), gcd(22123322, 4223234));
         assert_eq!(Ok(1), gcd(4, 11));
     }
@@ -108,9 +108,9 @@ mod tests {
     fn relative_prime_test() {
         use super::*;
 
-        assert_eq!(false, relatively_prime(30, 21));
-        assert_eq!(true, relatively_prime(4, 15));
-        assert_eq!(true, relatively_prime(10, 19));
+        assert!(!relatively_prime(30, 21));
+        assert!(relatively_prime(4, 15));
+        assert!(relatively_prime(10, 19));
     }
 
     #[test]
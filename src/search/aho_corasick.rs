@@ -0,0 +1,980 @@
+use std::collections::VecDeque;
+use std::io::{self, Read};
+
+use super::bytes::ByteReader;
+
+// the root state of every automaton
+const ROOT: u32 = 0;
+
+// marks a state without a dictionary suffix link
+const NO_LINK: u32 = u32::MAX;
+
+// the header of a serialized automaton, followed by a format version
+const MAGIC: &[u8; 4] = b"RSAC";
+const VERSION: u8 = 1;
+
+// how many bytes are read from a stream at a time
+const CHUNK: usize = 8 * 1024;
+
+/// Which matches an AhoCorasick automaton reports when patterns overlap each other in the text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchKind {
+    /// Every match of every pattern, including matches that overlap each other. They are reported in the order
+    /// they end in the text.
+    Overlapping,
+    /// Non-overlapping matches, at each point the match that starts first wins. When several patterns start at the
+    /// same place, the one given first when the automaton was built wins.
+    LeftmostFirst,
+    /// Non-overlapping matches, at each point the match that starts first wins. When several patterns start at the
+    /// same place, the longest one wins.
+    LeftmostLongest,
+}
+
+/// A match reported by an AhoCorasick automaton
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PatternMatch {
+    /// the index of the matched pattern, in the order the patterns were given
+    pub pattern: usize,
+    /// the offset of the first byte of the match
+    pub start: usize,
+    /// the offset just past the last byte of the match
+    pub end: usize,
+}
+
+// the outcome of looking for a leftmost match in a text that may not be complete yet
+enum Scan {
+    Found(PatternMatch),
+    NotFound,
+    // more text is needed, everything before this offset can be dropped
+    Incomplete(usize),
+}
+
+/// An Aho-Corasick automaton, it finds any number of patterns in a single pass over the text. The patterns are built
+/// into a trie with failure links, each byte of text then moves the automaton by one state.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::{AhoCorasick, MatchKind};
+///
+/// let ac = AhoCorasick::new(["error", "warn", "err"], MatchKind::LeftmostLongest);
+/// let found = ac.find_all("warn: error 42");
+///
+/// assert_eq!(found.len(), 2);
+/// assert_eq!((found[0].pattern, found[0].start, found[0].end), (1, 0, 4));
+/// assert_eq!((found[1].pattern, found[1].start, found[1].end), (0, 6, 11));
+/// ```
+#[derive(Debug, Clone)]
+pub struct AhoCorasick {
+    kind: MatchKind,
+    ascii_case_insensitive: bool,
+    pattern_lens: Vec<usize>,
+    // sparse trie transitions of each state, sorted by byte
+    transitions: Vec<Vec<(u8, u32)>>,
+    fail: Vec<u32>,
+    depth: Vec<u32>,
+    // the patterns that end exactly at each state
+    outputs: Vec<Vec<u32>>,
+    // the closest state down the failure chain that has outputs
+    dictionary: Vec<u32>,
+    // the root's transitions are looked up on almost every byte, so they are kept dense
+    root: Vec<u32>,
+}
+
+impl AhoCorasick {
+    /// This will return a new AhoCorasick automaton for the given patterns. Matches report patterns by their index
+    /// in this list.
+    pub fn new<I, P>(patterns: I, kind: MatchKind) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<[u8]>,
+    {
+        AhoCorasick::build(patterns, kind, false)
+    }
+
+    /// This will return a new AhoCorasick automaton that ignores ASCII case, both in the patterns and in the text.
+    /// Bytes outside of ASCII still need to match exactly.
+    pub fn new_ascii_case_insensitive<I, P>(patterns: I, kind: MatchKind) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<[u8]>,
+    {
+        AhoCorasick::build(patterns, kind, true)
+    }
+
+    fn build<I, P>(patterns: I, kind: MatchKind, ascii_case_insensitive: bool) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<[u8]>,
+    {
+        let mut ac = AhoCorasick {
+            kind,
+            ascii_case_insensitive,
+            pattern_lens: Vec::new(),
+            transitions: vec![Vec::new()],
+            fail: vec![ROOT],
+            depth: vec![0],
+            outputs: vec![Vec::new()],
+            dictionary: vec![NO_LINK],
+            root: Vec::new(),
+        };
+
+        for (id, pattern) in patterns.into_iter().enumerate() {
+            let mut state = ROOT;
+
+            for byte in pattern.as_ref() {
+                let byte = ac.fold_case(*byte);
+
+                state = match ac.goto(state, byte) {
+                    Some(next) => next,
+                    None => {
+                        let next = ac.transitions.len() as u32;
+                        let transitions = &mut ac.transitions[state as usize];
+                        let at = transitions.partition_point(|(b, _)| *b < byte);
+                        transitions.insert(at, (byte, next));
+
+                        ac.transitions.push(Vec::new());
+                        ac.fail.push(ROOT);
+                        ac.depth.push(ac.depth[state as usize] + 1);
+                        ac.outputs.push(Vec::new());
+                        ac.dictionary.push(NO_LINK);
+                        next
+                    }
+                };
+            }
+
+            ac.outputs[state as usize].push(id as u32);
+            ac.pattern_lens.push(pattern.as_ref().len());
+        }
+
+        ac.link();
+        ac
+    }
+
+    // fill in the failure and dictionary links breadth first, then the dense root row
+    fn link(&mut self) {
+        let mut queue: VecDeque<u32> = self.transitions[ROOT as usize]
+            .iter()
+            .map(|(_, child)| *child)
+            .collect();
+
+        if !self.outputs[ROOT as usize].is_empty() {
+            for child in queue.iter() {
+                self.dictionary[*child as usize] = ROOT;
+            }
+        }
+
+        while let Some(state) = queue.pop_front() {
+            for i in 0..self.transitions[state as usize].len() {
+                let (byte, child) = self.transitions[state as usize][i];
+                let mut fallback = self.fail[state as usize];
+
+                let fail = loop {
+                    if let Some(next) = self.goto(fallback, byte) {
+                        break next;
+                    }
+                    if fallback == ROOT {
+                        break ROOT;
+                    }
+                    fallback = self.fail[fallback as usize];
+                };
+
+                self.fail[child as usize] = fail;
+                self.dictionary[child as usize] = if self.outputs[fail as usize].is_empty() {
+                    self.dictionary[fail as usize]
+                } else {
+                    fail
+                };
+                queue.push_back(child);
+            }
+        }
+
+        self.root = (0..=255u8)
+            .map(|byte| self.goto(ROOT, byte).unwrap_or(ROOT))
+            .collect();
+    }
+
+    fn fold_case(&self, byte: u8) -> u8 {
+        if self.ascii_case_insensitive {
+            byte.to_ascii_lowercase()
+        } else {
+            byte
+        }
+    }
+
+    fn goto(&self, state: u32, byte: u8) -> Option<u32> {
+        let transitions = &self.transitions[state as usize];
+
+        transitions
+            .binary_search_by_key(&byte, |(b, _)| *b)
+            .ok()
+            .map(|i| transitions[i].1)
+    }
+
+    fn next_state(&self, mut state: u32, byte: u8) -> u32 {
+        let byte = self.fold_case(byte);
+
+        loop {
+            if state == ROOT {
+                return self.root[byte as usize];
+            }
+            if let Some(next) = self.goto(state, byte) {
+                return next;
+            }
+            state = self.fail[state as usize];
+        }
+    }
+
+    // call found with every pattern that ends at the given state, longest first
+    fn for_each_output<F>(&self, state: u32, mut found: F)
+    where
+        F: FnMut(usize),
+    {
+        let mut state = if self.outputs[state as usize].is_empty() {
+            self.dictionary[state as usize]
+        } else {
+            state
+        };
+
+        while state != NO_LINK {
+            for pattern in self.outputs[state as usize].iter() {
+                found(*pattern as usize);
+            }
+            state = self.dictionary[state as usize];
+        }
+    }
+
+    fn better(&self, candidate: &PatternMatch, best: &PatternMatch) -> bool {
+        if candidate.start != best.start {
+            return candidate.start < best.start;
+        }
+
+        match self.kind {
+            MatchKind::LeftmostLongest if candidate.end != best.end => candidate.end > best.end,
+            _ => candidate.pattern < best.pattern,
+        }
+    }
+
+    // look for the leftmost match starting at or after start. When the text is not complete the search can stop
+    // short, asking for more text.
+    fn leftmost_scan(&self, text: &[u8], start: usize, complete: bool) -> Scan {
+        let mut state = ROOT;
+        let mut best: Option<PatternMatch> = None;
+
+        self.for_each_output(ROOT, |pattern| {
+            let candidate = PatternMatch {
+                pattern,
+                start,
+                end: start,
+            };
+            if best.map_or(true, |b| self.better(&candidate, &b)) {
+                best = Some(candidate);
+            }
+        });
+
+        for (i, byte) in text.iter().enumerate().skip(start) {
+            // no match can still start at or before the best one found so far
+            if let Some(found) = best {
+                if i - self.depth[state as usize] as usize > found.start {
+                    return Scan::Found(found);
+                }
+            }
+
+            state = self.next_state(state, *byte);
+
+            self.for_each_output(state, |pattern| {
+                let candidate = PatternMatch {
+                    pattern,
+                    start: i + 1 - self.pattern_lens[pattern],
+                    end: i + 1,
+                };
+                if best.map_or(true, |b| self.better(&candidate, &b)) {
+                    best = Some(candidate);
+                }
+            });
+        }
+
+        // any match still to come starts at or after the text matched by the current state
+        let live = text.len().max(start) - self.depth[state as usize] as usize;
+
+        match best {
+            Some(found) if complete || live > found.start => Scan::Found(found),
+            None if complete => Scan::NotFound,
+            _ => Scan::Incomplete(live),
+        }
+    }
+
+    /// Return the first match in the text, or None if no pattern was found. For the overlapping match kind this is
+    /// the match that ends first.
+    pub fn find<T>(&self, text: T) -> Option<PatternMatch>
+    where
+        T: AsRef<[u8]>,
+    {
+        match self.kind {
+            MatchKind::Overlapping => self.find_all(text).into_iter().next(),
+            _ => match self.leftmost_scan(text.as_ref(), 0, true) {
+                Scan::Found(found) => Some(found),
+                _ => None,
+            },
+        }
+    }
+
+    /// Return every match in the text, following the automaton's match kind.
+    pub fn find_all<T>(&self, text: T) -> Vec<PatternMatch>
+    where
+        T: AsRef<[u8]>,
+    {
+        let text = text.as_ref();
+        let mut found = Vec::new();
+
+        match self.kind {
+            MatchKind::Overlapping => {
+                let mut state = ROOT;
+
+                self.for_each_output(ROOT, |pattern| {
+                    found.push(PatternMatch {
+                        pattern,
+                        start: 0,
+                        end: 0,
+                    })
+                });
+
+                for (i, byte) in text.iter().enumerate() {
+                    state = self.next_state(state, *byte);
+                    self.for_each_output(state, |pattern| {
+                        found.push(PatternMatch {
+                            pattern,
+                            start: i + 1 - self.pattern_lens[pattern],
+                            end: i + 1,
+                        })
+                    });
+                }
+            }
+            _ => {
+                let mut start = 0;
+
+                while start <= text.len() {
+                    match self.leftmost_scan(text, start, true) {
+                        Scan::Found(next) => {
+                            // an empty match would be found again, move past it
+                            start = if next.end == next.start {
+                                next.end + 1
+                            } else {
+                                next.end
+                            };
+                            found.push(next);
+                        }
+                        _ => break,
+                    }
+                }
+            }
+        }
+
+        found
+    }
+
+    /// Iterate over the matches in a stream, the stream is read in chunks and matches that cross from one chunk to
+    /// the next are still found. The match offsets count from the start of the stream.
+    ///
+    /// # Examples
+    /// ```
+    /// use rs_algo::search::{AhoCorasick, MatchKind};
+    ///
+    /// let ac = AhoCorasick::new(["needle"], MatchKind::LeftmostFirst);
+    /// let stream = "hay hay needle hay".as_bytes();
+    ///
+    /// let found: Vec<usize> = ac.stream_find_iter(stream).map(|m| m.unwrap().start).collect();
+    /// assert_eq!(found, vec![8]);
+    /// ```
+    pub fn stream_find_iter<R>(&self, reader: R) -> StreamMatches<'_, R>
+    where
+        R: Read,
+    {
+        StreamMatches {
+            automaton: self,
+            reader,
+            buffer: Vec::new(),
+            offset: 0,
+            position: 0,
+            state: ROOT,
+            started: false,
+            eof: false,
+            failed: false,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// The number of patterns the automaton was built from
+    pub fn pattern_count(&self) -> usize {
+        self.pattern_lens.len()
+    }
+
+    /// The match kind the automaton was built with
+    pub fn match_kind(&self) -> MatchKind {
+        self.kind
+    }
+
+    /// Serialize the built automaton so it can be stored and later restored with `from_bytes`, without building it
+    /// again from the patterns.
+    ///
+    /// # Examples
+    /// ```
+    /// use rs_algo::search::{AhoCorasick, MatchKind};
+    ///
+    /// let ac = AhoCorasick::new_ascii_case_insensitive(["GET", "POST"], MatchKind::LeftmostFirst);
+    /// let restored = AhoCorasick::from_bytes(&ac.to_bytes()).unwrap();
+    ///
+    /// assert_eq!(restored.find("post /index").map(|m| m.pattern), Some(1));
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let push = |bytes: &mut Vec<u8>, value: u32| bytes.extend_from_slice(&value.to_le_bytes());
+
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.push(match self.kind {
+            MatchKind::Overlapping => 0,
+            MatchKind::LeftmostFirst => 1,
+            MatchKind::LeftmostLongest => 2,
+        });
+        bytes.push(self.ascii_case_insensitive as u8);
+
+        push(&mut bytes, self.pattern_lens.len() as u32);
+        for len in self.pattern_lens.iter() {
+            bytes.extend_from_slice(&(*len as u64).to_le_bytes());
+        }
+
+        push(&mut bytes, self.transitions.len() as u32);
+        for state in 0..self.transitions.len() {
+            push(&mut bytes, self.fail[state]);
+            push(&mut bytes, self.depth[state]);
+            push(&mut bytes, self.dictionary[state]);
+
+            push(&mut bytes, self.transitions[state].len() as u32);
+            for (byte, next) in self.transitions[state].iter() {
+                bytes.push(*byte);
+                push(&mut bytes, *next);
+            }
+
+            push(&mut bytes, self.outputs[state].len() as u32);
+            for pattern in self.outputs[state].iter() {
+                push(&mut bytes, *pattern);
+            }
+        }
+
+        bytes
+    }
+
+    /// Restore an automaton serialized with `to_bytes`. An error is returned if the bytes are not a valid
+    /// automaton.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = ByteReader::new(bytes);
+
+        if reader.take(4)? != MAGIC {
+            return Err("not a serialized AhoCorasick automaton".to_string());
+        }
+        if reader.byte()? != VERSION {
+            return Err("unsupported AhoCorasick serialization version".to_string());
+        }
+
+        let kind = match reader.byte()? {
+            0 => MatchKind::Overlapping,
+            1 => MatchKind::LeftmostFirst,
+            2 => MatchKind::LeftmostLongest,
+            _ => return Err("unknown match kind".to_string()),
+        };
+        let ascii_case_insensitive = reader.byte()? != 0;
+
+        let pattern_count = reader.u32()? as usize;
+        let mut pattern_lens = Vec::new();
+        for _ in 0..pattern_count {
+            pattern_lens.push(reader.u64()? as usize);
+        }
+
+        let state_count = reader.u32()? as usize;
+        if state_count == 0 {
+            return Err("an automaton needs a root state".to_string());
+        }
+
+        let mut ac = AhoCorasick {
+            kind,
+            ascii_case_insensitive,
+            pattern_lens,
+            transitions: Vec::new(),
+            fail: Vec::new(),
+            depth: Vec::new(),
+            outputs: Vec::new(),
+            dictionary: Vec::new(),
+            root: Vec::new(),
+        };
+
+        let valid_state = |state: u32| (state as usize) < state_count;
+
+        for _ in 0..state_count {
+            let fail = reader.u32()?;
+            let depth = reader.u32()?;
+            let dictionary = reader.u32()?;
+            if !valid_state(fail) || !(valid_state(dictionary) || dictionary == NO_LINK) {
+                return Err("state link out of range".to_string());
+            }
+
+            let mut transitions = Vec::new();
+            for _ in 0..reader.u32()? {
+                let byte = reader.byte()?;
+                let next = reader.u32()?;
+                if !valid_state(next) || transitions.last().is_some_and(|(b, _)| *b >= byte) {
+                    return Err("invalid state transition".to_string());
+                }
+                transitions.push((byte, next));
+            }
+
+            let mut outputs = Vec::new();
+            for _ in 0..reader.u32()? {
+                let pattern = reader.u32()?;
+                if pattern as usize >= pattern_count
+                    || ac.pattern_lens[pattern as usize] > depth as usize
+                {
+                    return Err("pattern output out of range".to_string());
+                }
+                outputs.push(pattern);
+            }
+
+            ac.fail.push(fail);
+            ac.depth.push(depth);
+            ac.dictionary.push(dictionary);
+            ac.transitions.push(transitions);
+            ac.outputs.push(outputs);
+        }
+
+        if !reader.is_done() {
+            return Err("trailing bytes after the automaton".to_string());
+        }
+
+        if ac.fail[ROOT as usize] != ROOT
+            || ac.depth[ROOT as usize] != 0
+            || ac.dictionary[ROOT as usize] != NO_LINK
+        {
+            return Err("invalid root state".to_string());
+        }
+
+        // every link needs to lead closer to the root, or following them while matching may never end
+        for state in 0..state_count {
+            let depth = ac.depth[state];
+
+            if ac.transitions[state]
+                .iter()
+                .any(|(_, next)| ac.depth[*next as usize].checked_sub(1) != Some(depth))
+            {
+                return Err("state transition does not lead one level deeper".to_string());
+            }
+
+            if state == ROOT as usize {
+                continue;
+            }
+
+            let dictionary = ac.dictionary[state];
+            if ac.depth[ac.fail[state] as usize] >= depth
+                || (dictionary != NO_LINK && ac.depth[dictionary as usize] >= depth)
+            {
+                return Err("state link does not lead to a shallower state".to_string());
+            }
+        }
+
+        ac.root = (0..=255u8)
+            .map(|byte| ac.goto(ROOT, byte).unwrap_or(ROOT))
+            .collect();
+
+        Ok(ac)
+    }
+}
+
+/// An iterator over the matches in a stream, created by `AhoCorasick::stream_find_iter`
+#[derive(Debug)]
+pub struct StreamMatches<'a, R> {
+    automaton: &'a AhoCorasick,
+    reader: R,
+    // the bytes read but not yet searched, along with their offset in the stream
+    buffer: Vec<u8>,
+    offset: usize,
+    position: usize,
+    // the automaton state carried between chunks for overlapping matches
+    state: u32,
+    started: bool,
+    eof: bool,
+    // a read error was reported, the iterator is done
+    failed: bool,
+    pending: VecDeque<PatternMatch>,
+}
+
+impl<R> StreamMatches<'_, R>
+where
+    R: Read,
+{
+    // read the next chunk onto the end of the buffer
+    fn fill(&mut self) -> io::Result<()> {
+        let mut chunk = [0; CHUNK];
+
+        loop {
+            match self.reader.read(&mut chunk) {
+                Ok(0) => {
+                    self.eof = true;
+                    return Ok(());
+                }
+                Ok(read) => {
+                    self.buffer.extend_from_slice(&chunk[..read]);
+                    return Ok(());
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    // drop the first bytes of the buffer once they can no longer be part of a match
+    fn drain(&mut self, len: usize) {
+        self.buffer.drain(..len);
+        self.offset += len;
+        self.position -= len;
+    }
+
+    fn next_overlapping(&mut self) -> io::Result<()> {
+        let ac = self.automaton;
+
+        if !self.started {
+            self.started = true;
+            ac.for_each_output(ROOT, |pattern| {
+                self.pending.push_back(PatternMatch {
+                    pattern,
+                    start: 0,
+                    end: 0,
+                })
+            });
+            return Ok(());
+        }
+
+        if self.position == self.buffer.len() {
+            let len = self.buffer.len();
+            self.drain(len);
+            return self.fill();
+        }
+
+        let end = self.offset + self.position + 1;
+        self.state = ac.next_state(self.state, self.buffer[self.position]);
+        self.position += 1;
+
+        let pending = &mut self.pending;
+        ac.for_each_output(self.state, |pattern| {
+            pending.push_back(PatternMatch {
+                pattern,
+                start: end - ac.pattern_lens[pattern],
+                end,
+            })
+        });
+
+        Ok(())
+    }
+
+    fn next_leftmost(&mut self) -> io::Result<()> {
+        // an empty match at the end of the buffer, the search carries on once more bytes arrive
+        if self.position > self.buffer.len() {
+            return self.fill();
+        }
+
+        match self
+            .automaton
+            .leftmost_scan(&self.buffer, self.position, self.eof)
+        {
+            Scan::Found(found) => {
+                self.position = if found.end == found.start {
+                    found.end + 1
+                } else {
+                    found.end
+                };
+                self.pending.push_back(PatternMatch {
+                    pattern: found.pattern,
+                    start: found.start + self.offset,
+                    end: found.end + self.offset,
+                });
+            }
+            Scan::NotFound => self.position = self.buffer.len() + 1,
+            Scan::Incomplete(keep) => {
+                self.position = keep;
+                self.drain(keep);
+                self.fill()?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<R> Iterator for StreamMatches<'_, R>
+where
+    R: Read,
+{
+    type Item = io::Result<PatternMatch>;
+
+    fn next(&mut self) -> Option<io::Result<PatternMatch>> {
+        loop {
+            if let Some(found) = self.pending.pop_front() {
+                return Some(Ok(found));
+            }
+
+            let done = self.failed
+                || match self.automaton.kind {
+                    MatchKind::Overlapping => {
+                        self.started && self.eof && self.position == self.buffer.len()
+                    }
+                    _ => self.eof && self.position > self.buffer.len(),
+                };
+            if done {
+                return None;
+            }
+
+            let step = match self.automaton.kind {
+                MatchKind::Overlapping => self.next_overlapping(),
+                _ => self.next_leftmost(),
+            };
+
+            if let Err(e) = step {
+                self.failed = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    // a reader that hands out a few bytes at a time, so matches cross chunk boundaries
+    struct Trickle<'a> {
+        bytes: &'a [u8],
+        size: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = self.size.min(self.bytes.len()).min(buf.len());
+            buf[..len].copy_from_slice(&self.bytes[..len]);
+            self.bytes = &self.bytes[len..];
+            Ok(len)
+        }
+    }
+
+    fn triples(found: Vec<super::PatternMatch>) -> Vec<(usize, usize, usize)> {
+        found.iter().map(|m| (m.pattern, m.start, m.end)).collect()
+    }
+
+    #[test]
+    fn aho_corasick_overlapping() {
+        use super::*;
+
+        let ac = AhoCorasick::new(["he", "she", "his", "hers"], MatchKind::Overlapping);
+        assert_eq!(
+            triples(ac.find_all("ushers")),
+            vec![(1, 1, 4), (0, 2, 4), (3, 2, 6)]
+        );
+        assert_eq!(ac.find("ushers").map(|m| m.pattern), Some(1));
+        assert_eq!(ac.pattern_count(), 4);
+    }
+
+    #[test]
+    fn aho_corasick_leftmost() {
+        use super::*;
+
+        let patterns = ["Sam", "Samwise", "wise"];
+        let first = AhoCorasick::new(patterns, MatchKind::LeftmostFirst);
+        let longest = AhoCorasick::new(patterns, MatchKind::LeftmostLongest);
+
+        assert_eq!(
+            triples(first.find_all("Samwise")),
+            vec![(0, 0, 3), (2, 3, 7)]
+        );
+        assert_eq!(triples(longest.find_all("Samwise")), vec![(1, 0, 7)]);
+
+        let ac = AhoCorasick::new(["abcd", "bc", "b"], MatchKind::LeftmostFirst);
+        assert_eq!(triples(ac.find_all("abcbcd")), vec![(1, 1, 3), (1, 3, 5)]);
+
+        let ac = AhoCorasick::new(["abcd", "abce", "bcx"], MatchKind::LeftmostLongest);
+        assert_eq!(
+            triples(ac.find_all("abcx abcd")),
+            vec![(2, 1, 4), (0, 5, 9)]
+        );
+        assert_eq!(ac.find("nothing"), None);
+    }
+
+    #[test]
+    fn aho_corasick_case_insensitive() {
+        use super::*;
+
+        let ac =
+            AhoCorasick::new_ascii_case_insensitive(["Error", "WARN"], MatchKind::LeftmostFirst);
+        assert_eq!(
+            triples(ac.find_all("warn: ERROR, error")),
+            vec![(1, 0, 4), (0, 6, 11), (0, 13, 18)]
+        );
+
+        let exact = AhoCorasick::new(["Error"], MatchKind::LeftmostFirst);
+        assert_eq!(exact.find("ERROR"), None);
+    }
+
+    #[test]
+    fn aho_corasick_brute_force() {
+        use super::*;
+
+        let patterns = ["a", "ab", "bab", "bc", "bca", "c", "caa", "abcab", "aa"];
+        let text = b"abccabcaabbabcaabcabcbbacaabcbbabcab";
+
+        let overlapping = AhoCorasick::new(patterns, MatchKind::Overlapping);
+        let mut expected = Vec::new();
+        for end in 0..=text.len() {
+            for (pattern, p) in patterns.iter().enumerate() {
+                if text[..end].ends_with(p.as_bytes()) {
+                    expected.push((pattern, end - p.len(), end));
+                }
+            }
+        }
+
+        let mut found = triples(overlapping.find_all(text));
+        found.sort_by_key(|(pattern, _, end)| (*end, *pattern));
+        assert_eq!(found, expected);
+
+        for kind in [MatchKind::LeftmostFirst, MatchKind::LeftmostLongest].iter() {
+            let ac = AhoCorasick::new(patterns, *kind);
+            let mut expected = Vec::new();
+            let mut start = 0;
+
+            while start < text.len() {
+                let mut best: Option<(usize, usize, usize)> = None;
+                'outer: for s in start..text.len() {
+                    for (pattern, p) in patterns.iter().enumerate() {
+                        if text[s..].starts_with(p.as_bytes()) {
+                            let better = match best {
+                                None => true,
+                                Some((_, _, end)) => {
+                                    *kind == MatchKind::LeftmostLongest && s + p.len() > end
+                                }
+                            };
+                            if better {
+                                best = Some((pattern, s, s + p.len()));
+                            }
+                        }
+                    }
+                    if best.is_some() {
+                        break 'outer;
+                    }
+                }
+
+                match best {
+                    Some(found) => {
+                        expected.push(found);
+                        start = found.2;
+                    }
+                    None => break,
+                }
+            }
+
+            assert_eq!(triples(ac.find_all(text)), expected);
+        }
+    }
+
+    #[test]
+    fn aho_corasick_stream() {
+        use super::*;
+
+        let text = "the quick brown fox jumps over the lazy dog, the end".repeat(20);
+        let patterns = ["the", "quick brown", "fox", "lazy dog", "o", "end"];
+
+        for kind in [
+            MatchKind::Overlapping,
+            MatchKind::LeftmostFirst,
+            MatchKind::LeftmostLongest,
+        ]
+        .iter()
+        {
+            let ac = AhoCorasick::new(patterns, *kind);
+            let expected = triples(ac.find_all(&text));
+
+            for size in [1, 2, 3, 7, 64, 10_000].iter() {
+                let reader = Trickle {
+                    bytes: text.as_bytes(),
+                    size: *size,
+                };
+                let streamed: Vec<PatternMatch> =
+                    ac.stream_find_iter(reader).map(|m| m.unwrap()).collect();
+
+                assert_eq!(triples(streamed), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn aho_corasick_empty_pattern() {
+        use super::*;
+
+        let ac = AhoCorasick::new(["", "b"], MatchKind::LeftmostLongest);
+        assert_eq!(
+            triples(ac.find_all("ab")),
+            vec![(0, 0, 0), (1, 1, 2), (0, 2, 2)]
+        );
+
+        let streamed: Vec<PatternMatch> = ac
+            .stream_find_iter("ab".as_bytes())
+            .map(|m| m.unwrap())
+            .collect();
+        assert_eq!(triples(streamed), triples(ac.find_all("ab")));
+    }
+
+    #[test]
+    fn aho_corasick_serialize() {
+        use super::*;
+
+        let patterns = ["apple", "app", "pineapple", "pie", "Apple"];
+        let ac = AhoCorasick::new_ascii_case_insensitive(patterns, MatchKind::LeftmostLongest);
+        let bytes = ac.to_bytes();
+        let restored = AhoCorasick::from_bytes(&bytes).unwrap();
+
+        let text = "an APPLE pie and a pineapple";
+        assert_eq!(triples(restored.find_all(text)), triples(ac.find_all(text)));
+        assert_eq!(restored.match_kind(), MatchKind::LeftmostLongest);
+
+        assert!(AhoCorasick::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(AhoCorasick::from_bytes(b"nope").is_err());
+
+        let mut extra = bytes.clone();
+        extra.push(0);
+        assert!(AhoCorasick::from_bytes(&extra).is_err());
+    }
+
+    #[test]
+    fn aho_corasick_corrupted_links() {
+        use super::*;
+
+        // with a single pattern "a" the root is serialized at byte 23 and its child at byte 48, each starting with
+        // its fail, depth and dictionary links
+        let bytes = AhoCorasick::new(["a"], MatchKind::Overlapping).to_bytes();
+        let corrupt = |offset: usize, value: u32| {
+            let mut bytes = bytes.clone();
+            bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+            AhoCorasick::from_bytes(&bytes)
+        };
+
+        let links: Vec<u32> = [23, 27, 31, 48, 52, 56]
+            .iter()
+            .map(|at| ByteReader::new(&bytes[*at..]).u32().unwrap())
+            .collect();
+        assert_eq!(links, vec![ROOT, 0, NO_LINK, ROOT, 1, NO_LINK]);
+
+        // a fail link to itself
+        assert!(corrupt(48, 1).is_err());
+        // a depth that does not follow its parent's
+        assert!(corrupt(52, 7).is_err());
+        // a dictionary link to itself
+        assert!(corrupt(56, 1).is_err());
+        // a root that fails or links elsewhere
+        assert!(corrupt(23, 1).is_err());
+        assert!(corrupt(31, 1).is_err());
+        assert!(corrupt(27, 1).is_err());
+    }
+}
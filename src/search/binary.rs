@@ -4,7 +4,7 @@
 /// the index of that target will be returned, or None if the target was not found.
 ///
 /// Be aware, with binary search, this may not be the index of the first occurance. If
-/// you need the first occurance, use a linear search.
+/// you need the first occurance, use `linear::index_of` or `lower_bound`.
 ///
 /// # Examples
 /// ```
@@ -14,7 +14,7 @@
 /// assert_eq!(binary::index_of(23, &s), Some(7));
 /// assert_eq!(binary::index_of(123, &s), None);
 /// ```
-pub fn index_of<T>(target: T, array: &Vec<T>) -> Option<usize>
+pub fn index_of<T>(target: T, array: &[T]) -> Option<usize>
 where
     T: PartialOrd + Clone,
 {
@@ -32,7 +32,7 @@ where
         match array[mid] {
             ref value if *value == target => return Some(mid),
             ref value if *value < target => base = mid,
-            ref value if *value > target => base = base,
+            ref value if *value > target => {}
             _ => return None,
         }
 
@@ -55,7 +55,7 @@ where
 /// assert_eq!(binary::search(23, &s), Some(23));
 /// assert_eq!(binary::search(123, &s), None);
 /// ```
-pub fn search<T>(target: T, array: &Vec<T>) -> Option<T>
+pub fn search<T>(target: T, array: &[T]) -> Option<T>
 where
     T: PartialOrd + Clone,
 {
@@ -73,12 +73,88 @@ where
     let half = array.len() / 2;
     match array[half] {
         ref value if *value == target => Some(target),
-        ref value if *value > target => search(target, &array[0..half].to_vec()),
-        ref value if *value < target => search(target, &array[half..array.len()].to_vec()),
+        ref value if *value > target => search(target, &array[0..half]),
+        ref value if *value < target => search(target, &array[half..array.len()]),
         _ => None,
     }
 }
 
+/// Return the index of the first value in a partitioned array where the predicate is false, or the length of the
+/// array if it holds for every value. The predicate must hold for every value before that index and for none after
+/// it, as it does for `value < target` over a sorted array.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::binary;
+///
+/// let s = vec![1, 2, 3, 5, 7, 8, 9];
+/// assert_eq!(binary::partition_point(&s, |value| value * value < 30), 4);
+/// ```
+pub fn partition_point<T, F>(array: &[T], mut predicate: F) -> usize
+where
+    F: FnMut(&T) -> bool,
+{
+    let mut base = 0;
+    let mut size = array.len();
+
+    while size > 0 {
+        let half = size / 2;
+        let mid = base + half;
+
+        if predicate(&array[mid]) {
+            base = mid + 1;
+            size -= half + 1;
+        } else {
+            size = half;
+        }
+    }
+
+    base
+}
+
+/// Binary search. Binary searches need a sorted array
+///
+/// Return the index of the first value in the array that is not less than the target, this is the first occurance
+/// of the target if it is in the array and otherwise where it would be inserted. The length of the array is returned
+/// if every value is less than the target.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::binary;
+///
+/// let s = vec![1, 2, 3, 3, 3, 7, 8];
+/// assert_eq!(binary::lower_bound(3, &s), 2);
+/// assert_eq!(binary::lower_bound(4, &s), 5);
+/// assert_eq!(binary::lower_bound(10, &s), 7);
+/// ```
+pub fn lower_bound<T>(target: T, array: &[T]) -> usize
+where
+    T: PartialOrd,
+{
+    partition_point(array, |value| *value < target)
+}
+
+/// Binary search. Binary searches need a sorted array
+///
+/// Return the index of the first value in the array that is greater than the target, one past the last occurance of
+/// the target if it is in the array. The length of the array is returned if no value is greater than the target.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::binary;
+///
+/// let s = vec![1, 2, 3, 3, 3, 7, 8];
+/// assert_eq!(binary::upper_bound(3, &s), 5);
+/// assert_eq!(binary::upper_bound(0, &s), 0);
+/// assert_eq!(binary::upper_bound(8, &s), 7);
+/// ```
+pub fn upper_bound<T>(target: T, array: &[T]) -> usize
+where
+    T: PartialOrd,
+{
+    partition_point(array, |value| *value <= target)
+}
+
 #[cfg(test)]
 mod test {
     #[test]
@@ -129,4 +205,29 @@ mod test {
         assert_eq!(index_of("rust", &str_array), Some(3));
         assert_eq!(index_of("c++", &str_array), None);
     }
+
+    #[test]
+    fn binary_bounds() {
+        use super::*;
+
+        let array = vec![2, 3, 5, 6, 8, 9, 23, 54, 77, 78, 89, 89, 89, 90, 104];
+        for target in 0..110 {
+            let lower = array
+                .iter()
+                .position(|value| *value >= target)
+                .unwrap_or(array.len());
+            let upper = array
+                .iter()
+                .position(|value| *value > target)
+                .unwrap_or(array.len());
+
+            assert_eq!(lower_bound(target, &array), lower);
+            assert_eq!(upper_bound(target, &array), upper);
+        }
+
+        let empty: Vec<&str> = vec![];
+        assert_eq!(lower_bound("a", &empty), 0);
+        assert_eq!(upper_bound("a", &empty), 0);
+        assert_eq!(partition_point(&array, |value| *value < 89), 10);
+    }
 }
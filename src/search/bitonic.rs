@@ -0,0 +1,125 @@
+/// Find the peak of a bitonic array, an array that strictly increases and then strictly decreases. The index of the
+/// largest value is returned, or None if the array is empty.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::bitonic;
+///
+/// let array = vec![1, 4, 9, 17, 12, 8, 3];
+/// assert_eq!(bitonic::peak(&array), Some(3));
+/// ```
+pub fn peak<T>(array: &[T]) -> Option<usize>
+where
+    T: PartialOrd,
+{
+    if array.is_empty() {
+        return None;
+    }
+
+    let mut low = 0;
+    let mut high = array.len() - 1;
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+
+        if array[mid] < array[mid + 1] {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+
+    Some(low)
+}
+
+/// Search a bitonic array, an array that strictly increases and then strictly decreases. The peak is found first,
+/// then the increasing and the decreasing sides are each binary searched.
+///
+/// The target is the value you want to search for in array. If the target is found the index of that target will
+/// be returned, or None if the target was not found. A value can appear on both sides of the peak, the index on the
+/// increasing side is returned first.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::bitonic;
+///
+/// let array = vec![1, 4, 9, 17, 12, 8, 3];
+/// assert_eq!(bitonic::index_of(8, &array), Some(5));
+/// assert_eq!(bitonic::index_of(17, &array), Some(3));
+/// assert_eq!(bitonic::index_of(5, &array), None);
+/// ```
+pub fn index_of<T>(target: T, array: &[T]) -> Option<usize>
+where
+    T: PartialOrd,
+{
+    let top = peak(array)?;
+
+    // increasing side, 0..=top
+    let mut low = 0;
+    let mut high = top + 1;
+    while low < high {
+        let mid = low + (high - low) / 2;
+
+        if array[mid] < target {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+
+    if low <= top && array[low] == target {
+        return Some(low);
+    }
+
+    // decreasing side, top + 1..len
+    let mut low = top + 1;
+    let mut high = array.len();
+    while low < high {
+        let mid = low + (high - low) / 2;
+
+        if array[mid] > target {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+
+    if low < array.len() && array[low] == target {
+        return Some(low);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn bitonic_peak() {
+        use super::*;
+
+        assert_eq!(peak(&[1, 3, 8, 12, 4, 2]), Some(3));
+        assert_eq!(peak(&[1, 3, 8, 12]), Some(3));
+        assert_eq!(peak(&[12, 8, 3, 1]), Some(0));
+        assert_eq!(peak(&[5]), Some(0));
+        assert_eq!(peak::<u8>(&[]), None);
+    }
+
+    #[test]
+    fn bitonic_index() {
+        use super::*;
+
+        let array = vec![-3, 0, 2, 5, 11, 40, 39, 12, 5, 1, -10];
+        for (i, value) in array.iter().enumerate() {
+            let found = index_of(*value, &array).unwrap();
+            assert_eq!(array[found], *value);
+            if *value != 5 {
+                assert_eq!(found, i);
+            }
+        }
+
+        assert_eq!(index_of(5, &array), Some(3));
+        assert_eq!(index_of(3, &array), None);
+        assert_eq!(index_of(-11, &array), None);
+        assert_eq!(index_of(41, &array), None);
+    }
+}
@@ -0,0 +1,552 @@
+use std::hash::Hash;
+
+use super::super::bytes::ByteReader;
+use super::{optimal_size, slots};
+
+// the headers of serialized filters, followed by a format version
+const BLOOM_MAGIC: &[u8; 4] = b"RSBF";
+const COUNTING_MAGIC: &[u8; 4] = b"RSCB";
+const VERSION: u8 = 1;
+
+/// A Bloom filter, a bit array where every item sets k bits. An item is reported as present when all of its bits are
+/// set, so there are no false negatives and the false positive rate is set by the size of the filter.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::filter::BloomFilter;
+///
+/// let mut seen = BloomFilter::new(1000, 0.001).unwrap();
+/// assert!(seen.insert("alice"));
+/// assert!(!seen.insert("alice"));
+///
+/// assert!(seen.contains("alice"));
+/// assert!(!seen.contains("bob"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    len: usize,
+    hashes: u32,
+    seed: u64,
+}
+
+impl BloomFilter {
+    /// This will return a new BloomFilter sized to hold the expected number of items at the given false positive
+    /// rate. An error is returned if no items are expected or the rate is not between 0 and 1.
+    pub fn new(expected: usize, false_positive_rate: f64) -> Result<Self, String> {
+        BloomFilter::with_seed(expected, false_positive_rate, 0)
+    }
+
+    /// This will return a new BloomFilter like `new`, hashing with the given seed. Filters with different seeds set
+    /// different bits for the same item.
+    pub fn with_seed(expected: usize, false_positive_rate: f64, seed: u64) -> Result<Self, String> {
+        let (len, hashes) = optimal_size(expected, false_positive_rate)?;
+        BloomFilter::with_size(len, hashes, seed)
+    }
+
+    /// This will return a new BloomFilter with the given number of bits and hashes per item. An error is returned if
+    /// either is 0.
+    pub fn with_size(bits: usize, hashes: u32, seed: u64) -> Result<Self, String> {
+        if bits == 0 || hashes == 0 {
+            return Err("a Bloom filter needs at least one bit and one hash".to_string());
+        }
+
+        Ok(BloomFilter {
+            bits: vec![0; bits.div_ceil(64)],
+            len: bits,
+            hashes,
+            seed,
+        })
+    }
+
+    /// The number of bits in the filter
+    pub fn bit_len(&self) -> usize {
+        self.len
+    }
+
+    /// The number of bits each item sets
+    pub fn hash_count(&self) -> u32 {
+        self.hashes
+    }
+
+    /// The seed items are hashed with
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Add an item, returning true if it was not already reported as present.
+    pub fn insert<T>(&mut self, item: &T) -> bool
+    where
+        T: Hash + ?Sized,
+    {
+        let mut added = false;
+        for slot in slots(item, self.seed, self.hashes, self.len) {
+            let (word, bit) = (slot / 64, 1 << (slot % 64));
+            added |= self.bits[word] & bit == 0;
+            self.bits[word] |= bit;
+        }
+        added
+    }
+
+    /// Return true if the item may have been added, false if it certainly was not.
+    pub fn contains<T>(&self, item: &T) -> bool
+    where
+        T: Hash + ?Sized,
+    {
+        slots(item, self.seed, self.hashes, self.len)
+            .all(|slot| self.bits[slot / 64] & (1 << (slot % 64)) != 0)
+    }
+
+    /// Remove every item
+    pub fn clear(&mut self) {
+        self.bits.iter_mut().for_each(|word| *word = 0);
+    }
+
+    /// Return true if no item has been added
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|word| *word == 0)
+    }
+
+    /// Estimate how many distinct items have been added from how many bits are set
+    pub fn estimated_len(&self) -> f64 {
+        let ones = self
+            .bits
+            .iter()
+            .map(|word| word.count_ones() as f64)
+            .sum::<f64>();
+        let (m, k) = (self.len as f64, self.hashes as f64);
+
+        -m / k * (1.0 - ones / m).ln()
+    }
+
+    /// Estimate the chance that an item never added is reported as present, from how many bits are set
+    pub fn false_positive_rate(&self) -> f64 {
+        let ones = self
+            .bits
+            .iter()
+            .map(|word| word.count_ones() as f64)
+            .sum::<f64>();
+        (ones / self.len as f64).powi(self.hashes as i32)
+    }
+
+    fn check_compatible(&self, other: &BloomFilter) -> Result<(), String> {
+        if (self.len, self.hashes, self.seed) != (other.len, other.hashes, other.seed) {
+            return Err(
+                "Bloom filters need the same size, hash count and seed to be combined".to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Return a filter holding the items of both filters, exactly the filter that would have been built by adding
+    /// every item to one. An error is returned if the filters differ in size, hash count or seed.
+    pub fn union(&self, other: &BloomFilter) -> Result<BloomFilter, String> {
+        self.check_compatible(other)?;
+
+        let mut union = self.clone();
+        for (word, theirs) in union.bits.iter_mut().zip(other.bits.iter()) {
+            *word |= *theirs;
+        }
+        Ok(union)
+    }
+
+    /// Return a filter that reports every item present in both filters. It can have a higher false positive rate
+    /// than a filter built from only the common items. An error is returned if the filters differ in size, hash count
+    /// or seed.
+    pub fn intersection(&self, other: &BloomFilter) -> Result<BloomFilter, String> {
+        self.check_compatible(other)?;
+
+        let mut intersection = self.clone();
+        for (word, theirs) in intersection.bits.iter_mut().zip(other.bits.iter()) {
+            *word &= *theirs;
+        }
+        Ok(intersection)
+    }
+
+    /// Serialize the filter so it can be stored and later restored with `from_bytes`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(25 + 8 * self.bits.len());
+
+        bytes.extend_from_slice(BLOOM_MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&(self.len as u64).to_le_bytes());
+        bytes.extend_from_slice(&self.hashes.to_le_bytes());
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        for word in self.bits.iter() {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    /// Restore a filter serialized with `to_bytes`. An error is returned if the bytes are not a valid filter.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = ByteReader::new(bytes);
+
+        if reader.take(4)? != BLOOM_MAGIC {
+            return Err("not a serialized BloomFilter".to_string());
+        }
+        if reader.byte()? != VERSION {
+            return Err("unsupported BloomFilter serialization version".to_string());
+        }
+
+        let len = reader.u64()? as usize;
+        let hashes = reader.u32()?;
+        let seed = reader.u64()?;
+        // the bits are read before any memory is set aside for them, so a bad length can not allocate too much
+        let words = reader.take(len.div_ceil(64).saturating_mul(8))?;
+        let mut filter = BloomFilter::with_size(len, hashes, seed)?;
+
+        for (word, bytes) in filter.bits.iter_mut().zip(words.chunks_exact(8)) {
+            let mut value = [0; 8];
+            value.copy_from_slice(bytes);
+            *word = u64::from_le_bytes(value);
+        }
+        if len % 64 != 0 && filter.bits[filter.bits.len() - 1] >> (len % 64) != 0 {
+            return Err("bits set past the end of the filter".to_string());
+        }
+        if !reader.is_done() {
+            return Err("trailing bytes after the filter".to_string());
+        }
+
+        Ok(filter)
+    }
+}
+
+/// A counting Bloom filter, where every slot is a small counter instead of a bit so items can be removed again. A
+/// counter stops at 255 and is never decremented after that, so an item is never lost from the filter.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::filter::CountingBloomFilter;
+///
+/// let mut sessions = CountingBloomFilter::new(1000, 0.01).unwrap();
+/// sessions.insert("a1f3");
+/// sessions.insert("a1f3");
+/// sessions.insert("77c0");
+///
+/// assert_eq!(sessions.count("a1f3"), 2);
+/// assert!(sessions.remove("77c0"));
+/// assert!(!sessions.contains("77c0"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CountingBloomFilter {
+    counters: Vec<u8>,
+    hashes: u32,
+    seed: u64,
+}
+
+impl CountingBloomFilter {
+    /// This will return a new CountingBloomFilter sized to hold the expected number of items at the given false
+    /// positive rate. An error is returned if no items are expected or the rate is not between 0 and 1.
+    pub fn new(expected: usize, false_positive_rate: f64) -> Result<Self, String> {
+        CountingBloomFilter::with_seed(expected, false_positive_rate, 0)
+    }
+
+    /// This will return a new CountingBloomFilter like `new`, hashing with the given seed
+    pub fn with_seed(expected: usize, false_positive_rate: f64, seed: u64) -> Result<Self, String> {
+        let (len, hashes) = optimal_size(expected, false_positive_rate)?;
+        CountingBloomFilter::with_size(len, hashes, seed)
+    }
+
+    /// This will return a new CountingBloomFilter with the given number of counters and hashes per item. An error is
+    /// returned if either is 0.
+    pub fn with_size(counters: usize, hashes: u32, seed: u64) -> Result<Self, String> {
+        if counters == 0 || hashes == 0 {
+            return Err(
+                "a counting Bloom filter needs at least one counter and one hash".to_string(),
+            );
+        }
+
+        Ok(CountingBloomFilter {
+            counters: vec![0; counters],
+            hashes,
+            seed,
+        })
+    }
+
+    /// The number of counters in the filter
+    pub fn counter_len(&self) -> usize {
+        self.counters.len()
+    }
+
+    /// The number of counters each item increments
+    pub fn hash_count(&self) -> u32 {
+        self.hashes
+    }
+
+    /// The seed items are hashed with
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Add an item
+    pub fn insert<T>(&mut self, item: &T)
+    where
+        T: Hash + ?Sized,
+    {
+        for slot in slots(item, self.seed, self.hashes, self.counters.len()) {
+            self.counters[slot] = self.counters[slot].saturating_add(1);
+        }
+    }
+
+    /// Remove one copy of an item, returning false and leaving the filter unchanged if the item is certainly not in
+    /// the filter. Removing an item that was never added can remove other items.
+    pub fn remove<T>(&mut self, item: &T) -> bool
+    where
+        T: Hash + ?Sized,
+    {
+        if !self.contains(item) {
+            return false;
+        }
+
+        for slot in slots(item, self.seed, self.hashes, self.counters.len()) {
+            if self.counters[slot] != u8::MAX {
+                self.counters[slot] -= 1;
+            }
+        }
+        true
+    }
+
+    /// Return true if the item may have been added, false if it certainly was not.
+    pub fn contains<T>(&self, item: &T) -> bool
+    where
+        T: Hash + ?Sized,
+    {
+        self.count(item) > 0
+    }
+
+    /// Return an upper bound on how many times the item has been added, the smallest of its counters
+    pub fn count<T>(&self, item: &T) -> u8
+    where
+        T: Hash + ?Sized,
+    {
+        slots(item, self.seed, self.hashes, self.counters.len())
+            .map(|slot| self.counters[slot])
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Remove every item
+    pub fn clear(&mut self) {
+        self.counters.iter_mut().for_each(|counter| *counter = 0);
+    }
+
+    /// Return true if no item is in the filter
+    pub fn is_empty(&self) -> bool {
+        self.counters.iter().all(|counter| *counter == 0)
+    }
+
+    /// Return a plain Bloom filter with a bit set for every counter above 0, reporting the same items as present
+    pub fn to_bloom(&self) -> BloomFilter {
+        let mut bloom = BloomFilter {
+            bits: vec![0; self.counters.len().div_ceil(64)],
+            len: self.counters.len(),
+            hashes: self.hashes,
+            seed: self.seed,
+        };
+
+        for (slot, counter) in self.counters.iter().enumerate() {
+            if *counter > 0 {
+                bloom.bits[slot / 64] |= 1 << (slot % 64);
+            }
+        }
+        bloom
+    }
+
+    fn check_compatible(&self, other: &CountingBloomFilter) -> Result<(), String> {
+        if (self.counters.len(), self.hashes, self.seed)
+            != (other.counters.len(), other.hashes, other.seed)
+        {
+            return Err(
+                "counting Bloom filters need the same size, hash count and seed to be combined"
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Return a filter holding the items of both filters, with the counts added together. An error is returned if
+    /// the filters differ in size, hash count or seed.
+    pub fn union(&self, other: &CountingBloomFilter) -> Result<CountingBloomFilter, String> {
+        self.check_compatible(other)?;
+
+        let mut union = self.clone();
+        for (counter, theirs) in union.counters.iter_mut().zip(other.counters.iter()) {
+            *counter = counter.saturating_add(*theirs);
+        }
+        Ok(union)
+    }
+
+    /// Return a filter that reports every item present in both filters, keeping the smaller count of each counter.
+    /// An error is returned if the filters differ in size, hash count or seed.
+    pub fn intersection(&self, other: &CountingBloomFilter) -> Result<CountingBloomFilter, String> {
+        self.check_compatible(other)?;
+
+        let mut intersection = self.clone();
+        for (counter, theirs) in intersection.counters.iter_mut().zip(other.counters.iter()) {
+            *counter = (*counter).min(*theirs);
+        }
+        Ok(intersection)
+    }
+
+    /// Serialize the filter so it can be stored and later restored with `from_bytes`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(25 + self.counters.len());
+
+        bytes.extend_from_slice(COUNTING_MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&(self.counters.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&self.hashes.to_le_bytes());
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&self.counters);
+
+        bytes
+    }
+
+    /// Restore a filter serialized with `to_bytes`. An error is returned if the bytes are not a valid filter.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = ByteReader::new(bytes);
+
+        if reader.take(4)? != COUNTING_MAGIC {
+            return Err("not a serialized CountingBloomFilter".to_string());
+        }
+        if reader.byte()? != VERSION {
+            return Err("unsupported CountingBloomFilter serialization version".to_string());
+        }
+
+        let len = reader.u64()? as usize;
+        let hashes = reader.u32()?;
+        let seed = reader.u64()?;
+        // the counters are read before any memory is set aside for them, so a bad length can not allocate too much
+        let counters = reader.take(len)?;
+        if !reader.is_done() {
+            return Err("trailing bytes after the filter".to_string());
+        }
+
+        let mut filter = CountingBloomFilter::with_size(len, hashes, seed)?;
+        filter.counters.copy_from_slice(counters);
+        Ok(filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn bloom_filter_rates() {
+        use super::*;
+
+        let mut filter = BloomFilter::with_seed(5000, 0.01, 99).unwrap();
+        assert!(filter.is_empty());
+        for i in 0..5000 {
+            filter.insert(&i);
+        }
+
+        assert!((0..5000).all(|i| filter.contains(&i)));
+        let false_positives = (5000..105_000).filter(|i| filter.contains(i)).count();
+        assert!(
+            false_positives < 1500,
+            "{} false positives",
+            false_positives
+        );
+        assert!((filter.false_positive_rate() - 0.01).abs() < 0.005);
+        assert!((filter.estimated_len() - 5000.0).abs() < 250.0);
+
+        filter.clear();
+        assert!(filter.is_empty());
+        assert!(!filter.contains(&1));
+
+        assert!(BloomFilter::new(0, 0.1).is_err());
+        assert!(BloomFilter::with_size(10, 0, 0).is_err());
+    }
+
+    #[test]
+    fn bloom_filter_union_intersection() {
+        use super::*;
+
+        let mut left = BloomFilter::with_size(4096, 4, 1).unwrap();
+        let mut right = BloomFilter::with_size(4096, 4, 1).unwrap();
+        let mut both = BloomFilter::with_size(4096, 4, 1).unwrap();
+        for i in 0..200 {
+            left.insert(&i);
+            both.insert(&i);
+        }
+        for i in 100..300 {
+            right.insert(&i);
+            both.insert(&i);
+        }
+
+        assert_eq!(left.union(&right).unwrap(), both);
+
+        let common = left.intersection(&right).unwrap();
+        assert!((100..200).all(|i| common.contains(&i)));
+        assert!((0..300).filter(|i| common.contains(i)).count() < 150);
+
+        let other_seed = BloomFilter::with_size(4096, 4, 2).unwrap();
+        assert!(left.union(&other_seed).is_err());
+        assert!(left
+            .intersection(&BloomFilter::with_size(4095, 4, 1).unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn bloom_filter_bytes() {
+        use super::*;
+
+        let mut filter = BloomFilter::with_size(100, 3, 5).unwrap();
+        filter.insert("one");
+        filter.insert("two");
+
+        let bytes = filter.to_bytes();
+        assert_eq!(BloomFilter::from_bytes(&bytes), Ok(filter));
+
+        assert!(BloomFilter::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(BloomFilter::from_bytes(b"RSCB").is_err());
+
+        // a bit past the 100th
+        let mut extra = bytes.clone();
+        let last = extra.len() - 1;
+        extra[last] |= 0x80;
+        assert!(BloomFilter::from_bytes(&extra).is_err());
+    }
+
+    #[test]
+    fn counting_bloom_filter() {
+        use super::*;
+
+        let mut filter = CountingBloomFilter::with_seed(1000, 0.01, 3).unwrap();
+        for i in 0..1000 {
+            filter.insert(&i);
+        }
+        for i in 0..500 {
+            filter.insert(&i);
+        }
+
+        assert!((0..500).all(|i| filter.count(&i) >= 2));
+        assert!((0..1000).all(|i| filter.remove(&i)));
+        assert!((0..500).all(|i| filter.contains(&i)));
+        assert!((500..1000).filter(|i| filter.contains(i)).count() < 50);
+
+        let bloom = filter.to_bloom();
+        assert!((0..2000).all(|i| bloom.contains(&i) == filter.contains(&i)));
+
+        let mut other = CountingBloomFilter::with_seed(1000, 0.01, 3).unwrap();
+        other.insert(&7);
+        other.insert(&2000);
+        let union = filter.union(&other).unwrap();
+        assert_eq!(union.count(&7), filter.count(&7) + 1);
+        assert!(union.contains(&2000));
+        let common = filter.intersection(&other).unwrap();
+        assert!(common.contains(&7));
+        assert!(!common.contains(&2000));
+
+        let bytes = filter.to_bytes();
+        assert_eq!(CountingBloomFilter::from_bytes(&bytes), Ok(filter.clone()));
+        assert!(CountingBloomFilter::from_bytes(&bytes[1..]).is_err());
+        assert!(CountingBloomFilter::from_bytes(&[bytes.clone(), vec![0]].concat()).is_err());
+
+        filter.clear();
+        assert!(filter.is_empty());
+        assert!(!filter.remove(&1));
+    }
+}
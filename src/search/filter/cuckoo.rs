@@ -0,0 +1,370 @@
+use std::hash::Hash;
+
+use super::super::bytes::ByteReader;
+use super::hash;
+
+// the header of a serialized filter, followed by a format version
+const MAGIC: &[u8; 4] = b"RSCF";
+const VERSION: u8 = 1;
+
+// fingerprints per bucket
+const SLOTS: usize = 4;
+
+// marks an empty slot, no fingerprint is ever 0
+const EMPTY: u16 = 0;
+
+// how many fingerprints are moved looking for a free slot before an insert gives up
+const MAX_KICKS: usize = 500;
+
+/// A cuckoo filter, holding a 16 bit fingerprint of every item in one of two buckets. Unlike a Bloom filter items can
+/// be removed, and at the same memory it has a lower false positive rate, about 1 in 8000. The same item can be added
+/// at most twice the bucket size of 4, so 8 times, as every copy needs a slot in one of its two buckets. When both of
+/// an item's buckets are the same one it can only be added 4 times.
+///
+/// There is no union or intersection, the buckets of two filters can hold their fingerprints in any order so they
+/// can not be combined slot by slot.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::filter::CuckooFilter;
+///
+/// let mut blocked = CuckooFilter::new(1000).unwrap();
+/// blocked.insert("10.0.0.7").unwrap();
+/// blocked.insert("10.0.0.9").unwrap();
+///
+/// assert!(blocked.contains("10.0.0.7"));
+/// assert!(blocked.remove("10.0.0.7"));
+/// assert!(!blocked.contains("10.0.0.7"));
+/// assert_eq!(blocked.len(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CuckooFilter {
+    // a power of two of buckets, so the alternate bucket can be found with xor
+    buckets: Vec<[u16; SLOTS]>,
+    len: usize,
+    seed: u64,
+    // the xorshift state that picks which fingerprint to move
+    random: u64,
+}
+
+impl CuckooFilter {
+    /// This will return a new CuckooFilter with room for at least the given number of items. An error is returned if
+    /// the capacity is 0.
+    pub fn new(capacity: usize) -> Result<Self, String> {
+        CuckooFilter::with_seed(capacity, 0)
+    }
+
+    /// This will return a new CuckooFilter like `new`, hashing with the given seed. The seed also picks which
+    /// fingerprints are moved when buckets fill up.
+    pub fn with_seed(capacity: usize, seed: u64) -> Result<Self, String> {
+        if capacity == 0 {
+            return Err("a cuckoo filter needs room for at least one item".to_string());
+        }
+
+        // inserts start failing at around 95% full
+        let mut buckets = capacity.div_ceil(SLOTS).next_power_of_two();
+        if capacity as f64 > 0.95 * (buckets * SLOTS) as f64 {
+            buckets *= 2;
+        }
+
+        let random = seed ^ 0x2545_f491_4f6c_dd1d;
+        Ok(CuckooFilter {
+            buckets: vec![[EMPTY; SLOTS]; buckets],
+            len: 0,
+            seed,
+            random: if random == 0 { 1 } else { random },
+        })
+    }
+
+    /// The number of items in the filter
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return true if the filter holds no items
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of fingerprint slots in the filter
+    pub fn capacity(&self) -> usize {
+        self.buckets.len() * SLOTS
+    }
+
+    /// The fraction of slots that hold a fingerprint
+    pub fn load_factor(&self) -> f64 {
+        self.len as f64 / self.capacity() as f64
+    }
+
+    /// The seed items are hashed with
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // the fingerprint of an item and its first bucket
+    fn locate<T>(&self, item: &T) -> (u16, usize)
+    where
+        T: Hash + ?Sized,
+    {
+        let hash = hash(item, self.seed);
+        let fingerprint = match (hash >> 48) as u16 {
+            EMPTY => 1,
+            fingerprint => fingerprint,
+        };
+
+        (fingerprint, hash as usize & (self.buckets.len() - 1))
+    }
+
+    // the other bucket a fingerprint can live in, going from either bucket gives the other
+    fn alternate(&self, bucket: usize, fingerprint: u16) -> usize {
+        bucket ^ (hash(&fingerprint, self.seed) as usize & (self.buckets.len() - 1))
+    }
+
+    fn place(&mut self, bucket: usize, fingerprint: u16) -> bool {
+        match self.buckets[bucket].iter().position(|slot| *slot == EMPTY) {
+            Some(slot) => {
+                self.buckets[bucket][slot] = fingerprint;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn next_random(&mut self) -> u64 {
+        self.random ^= self.random << 13;
+        self.random ^= self.random >> 7;
+        self.random ^= self.random << 17;
+        self.random
+    }
+
+    /// Add an item. When both of its buckets are full, fingerprints already in the filter are moved to their other
+    /// bucket to make room. An error is returned if no room could be made, the filter is then left unchanged.
+    pub fn insert<T>(&mut self, item: &T) -> Result<(), String>
+    where
+        T: Hash + ?Sized,
+    {
+        let (mut fingerprint, first) = self.locate(item);
+        let second = self.alternate(first, fingerprint);
+
+        if self.place(first, fingerprint) || self.place(second, fingerprint) {
+            self.len += 1;
+            return Ok(());
+        }
+
+        let mut bucket = if self.next_random() & 1 == 0 {
+            first
+        } else {
+            second
+        };
+        let mut moved = Vec::with_capacity(MAX_KICKS);
+
+        for _ in 0..MAX_KICKS {
+            let slot = (self.next_random() % SLOTS as u64) as usize;
+            std::mem::swap(&mut fingerprint, &mut self.buckets[bucket][slot]);
+            moved.push((bucket, slot));
+
+            bucket = self.alternate(bucket, fingerprint);
+            if self.place(bucket, fingerprint) {
+                self.len += 1;
+                return Ok(());
+            }
+        }
+
+        // put every moved fingerprint back where it was
+        for (bucket, slot) in moved.into_iter().rev() {
+            std::mem::swap(&mut fingerprint, &mut self.buckets[bucket][slot]);
+        }
+        Err("the cuckoo filter is full".to_string())
+    }
+
+    /// Return true if the item may have been added, false if it certainly was not.
+    pub fn contains<T>(&self, item: &T) -> bool
+    where
+        T: Hash + ?Sized,
+    {
+        let (fingerprint, first) = self.locate(item);
+        let second = self.alternate(first, fingerprint);
+
+        self.buckets[first].contains(&fingerprint) || self.buckets[second].contains(&fingerprint)
+    }
+
+    /// Remove one copy of an item, returning false if the item is certainly not in the filter. Removing an item that
+    /// was never added can remove another item with the same fingerprint.
+    pub fn remove<T>(&mut self, item: &T) -> bool
+    where
+        T: Hash + ?Sized,
+    {
+        let (fingerprint, first) = self.locate(item);
+        let second = self.alternate(first, fingerprint);
+
+        for bucket in [first, second].iter() {
+            if let Some(slot) = self.buckets[*bucket]
+                .iter()
+                .position(|slot| *slot == fingerprint)
+            {
+                self.buckets[*bucket][slot] = EMPTY;
+                self.len -= 1;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Remove every item
+    pub fn clear(&mut self) {
+        self.buckets
+            .iter_mut()
+            .for_each(|bucket| *bucket = [EMPTY; SLOTS]);
+        self.len = 0;
+    }
+
+    /// Serialize the filter so it can be stored and later restored with `from_bytes`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(29 + 2 * self.capacity());
+
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&(self.buckets.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&self.random.to_le_bytes());
+        for fingerprint in self.buckets.iter().flatten() {
+            bytes.extend_from_slice(&fingerprint.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    /// Restore a filter serialized with `to_bytes`. An error is returned if the bytes are not a valid filter.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = ByteReader::new(bytes);
+
+        if reader.take(4)? != MAGIC {
+            return Err("not a serialized CuckooFilter".to_string());
+        }
+        if reader.byte()? != VERSION {
+            return Err("unsupported CuckooFilter serialization version".to_string());
+        }
+
+        let bucket_count = reader.u64()? as usize;
+        if !bucket_count.is_power_of_two() {
+            return Err("a cuckoo filter needs a power of two of buckets".to_string());
+        }
+
+        let seed = reader.u64()?;
+        let random = reader.u64()?;
+        if random == 0 {
+            return Err("invalid cuckoo filter random state".to_string());
+        }
+
+        // the buckets are read before any memory is set aside for them, so a bad count can not allocate too much
+        let fingerprints = reader.take(bucket_count.saturating_mul(2 * SLOTS))?;
+        let mut buckets = vec![[EMPTY; SLOTS]; bucket_count];
+        let mut len = 0;
+        for (slot, bytes) in buckets
+            .iter_mut()
+            .flatten()
+            .zip(fingerprints.chunks_exact(2))
+        {
+            *slot = u16::from_le_bytes([bytes[0], bytes[1]]);
+            len += (*slot != EMPTY) as usize;
+        }
+        if !reader.is_done() {
+            return Err("trailing bytes after the filter".to_string());
+        }
+
+        Ok(CuckooFilter {
+            buckets,
+            len,
+            seed,
+            random,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn cuckoo_filter_insert_remove() {
+        use super::*;
+
+        let mut filter = CuckooFilter::with_seed(10_000, 11).unwrap();
+        assert!(filter.capacity() >= 10_000);
+
+        for i in 0..10_000 {
+            filter.insert(&i).unwrap();
+        }
+        assert_eq!(filter.len(), 10_000);
+        assert!((0..10_000).all(|i| filter.contains(&i)));
+
+        let false_positives = (10_000..1_010_000).filter(|i| filter.contains(i)).count();
+        assert!(false_positives < 500, "{} false positives", false_positives);
+
+        for i in (0..10_000).step_by(2) {
+            assert!(filter.remove(&i));
+        }
+        assert!((1..10_000).step_by(2).all(|i| filter.contains(&i)));
+        assert!(
+            (0..10_000)
+                .step_by(2)
+                .filter(|i| filter.contains(i))
+                .count()
+                < 10
+        );
+        assert_eq!(filter.len(), 5000);
+
+        filter.clear();
+        assert!(filter.is_empty());
+        assert!(!filter.remove(&1));
+        assert!(CuckooFilter::new(0).is_err());
+    }
+
+    #[test]
+    fn cuckoo_filter_full() {
+        use super::*;
+
+        let mut filter = CuckooFilter::with_seed(64, 5).unwrap();
+        let mut added = 0;
+        loop {
+            let before = filter.clone();
+            if filter.insert(&added).is_err() {
+                // a failed insert leaves the filter as it was
+                assert_eq!(filter.buckets, before.buckets);
+                break;
+            }
+            added += 1;
+        }
+
+        assert_eq!(filter.len(), added as usize);
+        assert!(filter.load_factor() > 0.8);
+        assert!((0..added).all(|i| filter.contains(&i)));
+
+        // the same item fills both of its buckets
+        let mut filter = CuckooFilter::new(100).unwrap();
+        for _ in 0..2 * SLOTS {
+            filter.insert("again").unwrap();
+        }
+        assert!(filter.insert("again").is_err());
+    }
+
+    #[test]
+    fn cuckoo_filter_bytes() {
+        use super::*;
+
+        let mut filter = CuckooFilter::with_seed(100, 9).unwrap();
+        for word in ["red", "green", "blue"].iter() {
+            filter.insert(*word).unwrap();
+        }
+
+        let bytes = filter.to_bytes();
+        let restored = CuckooFilter::from_bytes(&bytes).unwrap();
+        assert_eq!(restored, filter);
+        assert!(restored.contains("green"));
+
+        assert!(CuckooFilter::from_bytes(&bytes[..bytes.len() - 2]).is_err());
+        assert!(CuckooFilter::from_bytes(b"RSBF").is_err());
+
+        let mut odd = bytes.clone();
+        odd[5] = 3;
+        assert!(CuckooFilter::from_bytes(&odd).is_err());
+    }
+}
@@ -0,0 +1,102 @@
+//! Approximate membership filters. A filter answers "is this item in the set?" with either "definitely not" or
+//! "probably", using a small fraction of the memory the set itself would take, so it can be checked before a much
+//! slower lookup.
+//!
+//! Items are hashed with the standard library's `DefaultHasher` mixed with a seed, so a filter gives the same answers
+//! on every run. The standard library does not promise that hasher stays the same between Rust releases, so bytes
+//! from `to_bytes` should be read back with a program built by the same compiler.
+//!
+//! # Examples
+//! ```
+//! use rs_algo::search::filter::BloomFilter;
+//! use rs_algo::search::binary;
+//!
+//! let cold = vec![3, 8, 15, 16, 23, 42];
+//! let mut filter = BloomFilter::new(cold.len(), 0.01).unwrap();
+//! for value in cold.iter() {
+//!     filter.insert(value);
+//! }
+//!
+//! // only search when the filter says the value may be there
+//! let lookup = |value: i32| {
+//!     if filter.contains(&value) {
+//!         binary::search(value, &cold)
+//!     } else {
+//!         None
+//!     }
+//! };
+//!
+//! assert_eq!(lookup(23), Some(23));
+//! assert_eq!(lookup(7), None);
+//! ```
+pub use self::bloom::{BloomFilter, CountingBloomFilter};
+pub use self::cuckoo::CuckooFilter;
+
+mod bloom;
+mod cuckoo;
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+// hash an item with a seed
+fn hash<T>(item: &T, seed: u64) -> u64
+where
+    T: Hash + ?Sized,
+{
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    item.hash(&mut hasher);
+    hasher.finish()
+}
+
+// the k slots an item sets in a Bloom filter of len slots, found by double hashing
+fn slots<T>(item: &T, seed: u64, k: u32, len: usize) -> impl Iterator<Item = usize>
+where
+    T: Hash + ?Sized,
+{
+    let first = hash(item, seed);
+    // an odd step never cycles back early when len is a power of two
+    let step = hash(item, seed ^ 0x9e37_79b9_7f4a_7c15) | 1;
+
+    (0..k as u64).map(move |i| (first.wrapping_add(i.wrapping_mul(step)) % len as u64) as usize)
+}
+
+// the size of a Bloom filter for the expected number of items and false positive rate, as slots and hash count
+fn optimal_size(expected: usize, false_positive_rate: f64) -> Result<(usize, u32), String> {
+    if expected == 0 {
+        return Err("a filter needs to expect at least one item".to_string());
+    }
+    if !(false_positive_rate > 0.0 && false_positive_rate < 1.0) {
+        return Err("the false positive rate must be between 0 and 1".to_string());
+    }
+
+    let ln2 = std::f64::consts::LN_2;
+    let slots = (-(expected as f64) * false_positive_rate.ln() / (ln2 * ln2)).ceil();
+    let hashes = (slots / expected as f64 * ln2).round().max(1.0);
+
+    Ok((slots as usize, hashes as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn filter_sizing() {
+        use super::*;
+
+        // the textbook numbers: a million items at 1% need about 9.6 bits per item and 7 hashes
+        let (len, hashes) = optimal_size(1_000_000, 0.01).unwrap();
+        assert_eq!(len, 9_585_059);
+        assert_eq!(hashes, 7);
+
+        assert!(optimal_size(0, 0.01).is_err());
+        assert!(optimal_size(10, 0.0).is_err());
+        assert!(optimal_size(10, 1.0).is_err());
+        assert!(optimal_size(10, f64::NAN).is_err());
+
+        let picked: Vec<usize> = slots("item", 7, 5, 64).collect();
+        assert_eq!(picked.len(), 5);
+        assert!(picked.iter().all(|slot| *slot < 64));
+        assert_eq!(picked, slots("item", 7, 5, 64).collect::<Vec<usize>>());
+        assert_ne!(picked, slots("item", 8, 5, 64).collect::<Vec<usize>>());
+    }
+}
@@ -0,0 +1,481 @@
+use std::borrow::Borrow;
+use std::cmp::min;
+
+use super::RadixTrie;
+use crate::compare::distance::Metric;
+
+#[derive(Debug, Clone)]
+struct BkNode<T> {
+    item: T,
+    // the distance from this item to each child, with the child's index
+    children: Vec<(usize, usize)>,
+}
+
+/// A Burkhard-Keller tree, for finding every item within some distance of a query under any `Metric`. The triangle
+/// inequality lets whole subtrees be skipped, so far fewer distances are worked out than with a linear scan.
+///
+/// # Examples
+/// ```
+/// use rs_algo::compare::distance::Levenshtein;
+/// use rs_algo::search::BkTree;
+///
+/// let mut words = BkTree::new(Levenshtein);
+/// for word in ["book", "books", "cake", "boo", "cape", "cart"].iter() {
+///     words.insert(word.to_string());
+/// }
+///
+/// let found: Vec<(usize, &String)> = words.find("bok", 1);
+/// assert_eq!(found, vec![(1, &"book".to_string()), (1, &"boo".to_string())]);
+/// assert_eq!(words.closest("carts"), Some((1, &"cart".to_string())));
+/// ```
+#[derive(Debug, Clone)]
+pub struct BkTree<T, M> {
+    // the root is at index 0
+    nodes: Vec<BkNode<T>>,
+    metric: M,
+}
+
+impl<T, M> Default for BkTree<T, M>
+where
+    M: Default,
+{
+    fn default() -> Self {
+        BkTree::new(M::default())
+    }
+}
+
+impl<T, M> BkTree<T, M> {
+    /// This will return a new empty BkTree that measures with the given metric
+    pub fn new(metric: M) -> Self {
+        BkTree {
+            nodes: Vec::new(),
+            metric,
+        }
+    }
+
+    /// The number of items in the tree
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Return true if the tree holds no items
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Insert an item, returning false if an item at distance 0 from it is already in the tree.
+    pub fn insert(&mut self, item: T) -> bool
+    where
+        M: Metric<T>,
+    {
+        if self.nodes.is_empty() {
+            self.nodes.push(BkNode {
+                item,
+                children: Vec::new(),
+            });
+            return true;
+        }
+
+        let mut node = 0;
+        loop {
+            let distance = self.metric.distance(&item, &self.nodes[node].item);
+            if distance == 0 {
+                return false;
+            }
+
+            let children = &self.nodes[node].children;
+            match children.binary_search_by_key(&distance, |(d, _)| *d) {
+                Ok(i) => node = children[i].1,
+                Err(i) => {
+                    let child = self.nodes.len();
+                    self.nodes[node].children.insert(i, (distance, child));
+                    self.nodes.push(BkNode {
+                        item,
+                        children: Vec::new(),
+                    });
+                    return true;
+                }
+            }
+        }
+    }
+
+    /// Return every item within max_distance of the query along with its distance, closest first. Items at the same
+    /// distance come in the order they were inserted.
+    pub fn find<Q>(&self, query: &Q, max_distance: usize) -> Vec<(usize, &T)>
+    where
+        Q: ?Sized,
+        T: Borrow<Q>,
+        M: Metric<Q>,
+    {
+        let mut found = Vec::new();
+        let mut stack = if self.nodes.is_empty() {
+            vec![]
+        } else {
+            vec![0]
+        };
+
+        while let Some(node) = stack.pop() {
+            let distance = self.metric.distance(query, self.nodes[node].item.borrow());
+            if distance <= max_distance {
+                found.push((distance, node));
+            }
+
+            // only children whose distance to this node is within max_distance of the query's can hold a match
+            let low = distance.saturating_sub(max_distance);
+            let high = distance.saturating_add(max_distance);
+            for (d, child) in self.nodes[node].children.iter() {
+                if low <= *d && *d <= high {
+                    stack.push(*child);
+                }
+            }
+        }
+
+        found.sort_unstable();
+        found
+            .into_iter()
+            .map(|(distance, node)| (distance, &self.nodes[node].item))
+            .collect()
+    }
+
+    /// Return the item closest to the query along with its distance, the first one inserted when several are as
+    /// close. None is returned if the tree is empty.
+    pub fn closest<Q>(&self, query: &Q) -> Option<(usize, &T)>
+    where
+        Q: ?Sized,
+        T: Borrow<Q>,
+        M: Metric<Q>,
+    {
+        let mut best: Option<(usize, usize)> = None;
+        let mut stack = if self.nodes.is_empty() {
+            vec![]
+        } else {
+            vec![0]
+        };
+
+        while let Some(node) = stack.pop() {
+            let distance = self.metric.distance(query, self.nodes[node].item.borrow());
+            if best.map_or(true, |found| (distance, node) < found) {
+                best = Some((distance, node));
+            }
+
+            // the search radius shrinks to the best distance found so far
+            let radius = best.map_or(usize::MAX, |(d, _)| d);
+            let low = distance.saturating_sub(radius);
+            let high = distance.saturating_add(radius);
+            for (d, child) in self.nodes[node].children.iter() {
+                if low <= *d && *d <= high {
+                    stack.push(*child);
+                }
+            }
+        }
+
+        best.map(|(distance, node)| (distance, &self.nodes[node].item))
+    }
+}
+
+/// The state of a `LevenshteinAutomaton` after reading part of a word, one row of the edit distance grid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutomatonState {
+    // the distance from the word read so far to each prefix of the query, capped at max_distance + 1
+    row: Vec<usize>,
+}
+
+/// A Levenshtein automaton, accepting every word within max_distance of a query. It is read one char at a time so it
+/// can be run alongside a trie or a sorted word list, dropping every word that shares a prefix which can no longer
+/// match. Distances agree with `compare::distance::levenshtein_str`.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::{LevenshteinAutomaton, RadixTrie};
+///
+/// let mut dictionary = RadixTrie::new();
+/// for (i, word) in ["hello", "help", "hell", "yellow", "world"].iter().enumerate() {
+///     dictionary.insert(word, i);
+/// }
+///
+/// let automaton = LevenshteinAutomaton::new("helo", 1);
+/// let found: Vec<(String, usize)> = automaton
+///     .search_trie(&dictionary)
+///     .into_iter()
+///     .map(|(word, distance, _)| (word, distance))
+///     .collect();
+///
+/// assert_eq!(found, vec![("hell".to_string(), 1), ("hello".to_string(), 1), ("help".to_string(), 1)]);
+/// assert_eq!(automaton.matches("yellow"), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LevenshteinAutomaton {
+    query: Vec<char>,
+    max_distance: usize,
+}
+
+impl LevenshteinAutomaton {
+    /// This will return a new LevenshteinAutomaton for words within max_distance of the query
+    pub fn new(query: &str, max_distance: usize) -> Self {
+        LevenshteinAutomaton {
+            query: query.chars().collect(),
+            max_distance,
+        }
+    }
+
+    /// The largest distance the automaton accepts
+    pub fn max_distance(&self) -> usize {
+        self.max_distance
+    }
+
+    /// The state before any char has been read
+    pub fn start(&self) -> AutomatonState {
+        AutomatonState {
+            row: (0..=self.query.len())
+                .map(|i| min(i, self.max_distance + 1))
+                .collect(),
+        }
+    }
+
+    /// The state after reading one more char
+    pub fn step(&self, state: &AutomatonState, c: char) -> AutomatonState {
+        let limit = self.max_distance + 1;
+        let mut row = Vec::with_capacity(state.row.len());
+        row.push(min(state.row[0] + 1, limit));
+
+        for (j, q) in self.query.iter().enumerate() {
+            let substitute = state.row[j] + (*q != c) as usize;
+            let distance = min(substitute, min(state.row[j + 1], row[j]) + 1);
+            row.push(min(distance, limit));
+        }
+
+        AutomatonState { row }
+    }
+
+    /// Return true if the word read so far is within max_distance of the query
+    pub fn is_match(&self, state: &AutomatonState) -> bool {
+        self.distance(state).is_some()
+    }
+
+    /// Return true if some word starting with what has been read so far can still match
+    pub fn can_match(&self, state: &AutomatonState) -> bool {
+        state.row.iter().any(|d| *d <= self.max_distance)
+    }
+
+    /// Return the distance from the word read so far to the query, or None if it is more than max_distance.
+    pub fn distance(&self, state: &AutomatonState) -> Option<usize> {
+        let distance = state.row[self.query.len()];
+        if distance <= self.max_distance {
+            Some(distance)
+        } else {
+            None
+        }
+    }
+
+    /// Return the distance from the word to the query, or None if it is more than max_distance. Reading stops as
+    /// soon as the word can no longer match.
+    pub fn matches(&self, word: &str) -> Option<usize> {
+        let mut state = self.start();
+        for c in word.chars() {
+            if !self.can_match(&state) {
+                return None;
+            }
+            state = self.step(&state, c);
+        }
+
+        self.distance(&state)
+    }
+
+    /// Return every key in the trie within max_distance of the query in sorted order, along with its distance and
+    /// value. Subtrees are skipped as soon as their prefix can no longer match, and keys that are not valid UTF-8
+    /// are never matched.
+    pub fn search_trie<'a, V>(&self, trie: &'a RadixTrie<V>) -> Vec<(String, usize, &'a V)> {
+        let mut found = Vec::new();
+
+        // labels can split a char, so the bytes of an unfinished char are carried down to the children
+        let start = (self.start(), Vec::new());
+        trie.walk(&start, &mut |(state, pending), label, key, value| {
+            let mut state = state.clone();
+            let mut pending = pending.clone();
+
+            for byte in label.iter() {
+                pending.push(*byte);
+                match std::str::from_utf8(&pending) {
+                    Ok(c) => {
+                        state = self.step(&state, c.chars().next().unwrap());
+                        pending.clear();
+                    }
+                    Err(e) if e.error_len().is_some() => return None,
+                    Err(_) => {}
+                }
+            }
+
+            if !self.can_match(&state) {
+                return None;
+            }
+            if let (Some(value), Some(distance), true) =
+                (value, self.distance(&state), pending.is_empty())
+            {
+                // the key is valid UTF-8 as every char in it was decoded
+                let word = String::from_utf8(key.to_vec()).unwrap();
+                found.push((word, distance, value));
+            }
+
+            Some((state, pending))
+        });
+
+        found
+    }
+
+    /// Return every word within max_distance of the query, in the order given, along with its distance. Words that
+    /// share a prefix with the one before them reuse its states, and words that share a prefix which can no longer
+    /// match are skipped without reading them, so sorted input is the fastest. Any order gives the same matches.
+    ///
+    /// # Examples
+    /// ```
+    /// use rs_algo::search::LevenshteinAutomaton;
+    ///
+    /// let words = ["cat", "catalog", "cater", "dog", "dot"];
+    /// let automaton = LevenshteinAutomaton::new("cate", 1);
+    ///
+    /// assert_eq!(automaton.search_sorted(&words), vec![("cat", 1), ("cater", 1)]);
+    /// ```
+    pub fn search_sorted<'a, S>(&self, words: &'a [S]) -> Vec<(&'a str, usize)>
+    where
+        S: AsRef<str>,
+    {
+        let mut found = Vec::new();
+        // states[i] is the state after the first i chars of the previous word
+        let mut states = vec![self.start()];
+        let mut previous: Vec<char> = Vec::new();
+        // the length of a prefix of the previous word that can no longer match
+        let mut dead: Option<usize> = None;
+
+        for word in words.iter() {
+            let word = word.as_ref();
+            let chars: Vec<char> = word.chars().collect();
+            let common = previous
+                .iter()
+                .zip(chars.iter())
+                .take_while(|(a, b)| a == b)
+                .count();
+
+            if dead.is_some_and(|len| common >= len) {
+                continue;
+            }
+
+            dead = None;
+            states.truncate(common + 1);
+            for c in chars[common..].iter() {
+                let next = self.step(&states[states.len() - 1], *c);
+                if !self.can_match(&next) {
+                    dead = Some(states.len());
+                    break;
+                }
+                states.push(next);
+            }
+            previous = chars;
+
+            if dead.is_none() {
+                if let Some(distance) = self.distance(&states[states.len() - 1]) {
+                    found.push((word, distance));
+                }
+            }
+        }
+
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compare::distance::levenshtein_str;
+
+    const WORDS: [&str; 16] = [
+        "", "a", "ab", "abc", "abd", "bad", "bade", "bat", "cab", "café", "cafe", "caffe", "face",
+        "fade", "ça", "çava",
+    ];
+
+    #[test]
+    fn bk_tree_find() {
+        use super::*;
+        use crate::compare::distance::Levenshtein;
+
+        let mut tree = BkTree::new(Levenshtein);
+        for word in WORDS.iter() {
+            assert!(tree.insert(word.to_string()));
+        }
+        assert!(!tree.insert("bad".to_string()));
+        assert_eq!(tree.len(), WORDS.len());
+
+        for query in ["", "ab", "cafe", "fad", "xyz", "çabc"].iter() {
+            for k in 0..4 {
+                let found: Vec<(usize, &str)> = tree
+                    .find(*query, k)
+                    .into_iter()
+                    .map(|(distance, word)| (distance, word.as_str()))
+                    .collect();
+
+                let mut expected: Vec<(usize, &str)> = WORDS
+                    .iter()
+                    .map(|word| (levenshtein_str(query, word), *word))
+                    .filter(|(distance, _)| *distance <= k)
+                    .collect();
+                expected.sort_by_key(|(distance, _)| *distance);
+
+                assert_eq!(found, expected);
+            }
+
+            let closest = tree.closest(*query).map(|(distance, _)| distance);
+            let expected = WORDS.iter().map(|word| levenshtein_str(query, word)).min();
+            assert_eq!(closest, expected);
+        }
+
+        let empty: BkTree<String, Levenshtein> = BkTree::default();
+        assert!(empty.find("a", 3).is_empty());
+        assert_eq!(empty.closest("a"), None);
+    }
+
+    #[test]
+    fn levenshtein_automaton_search() {
+        use super::*;
+
+        let mut trie = RadixTrie::new();
+        for (i, word) in WORDS.iter().enumerate() {
+            trie.insert(word, i);
+        }
+        // a key that is not UTF-8 is never matched
+        trie.insert([b'c', b'a', 0xff], 100);
+
+        let mut sorted = WORDS.to_vec();
+        sorted.sort_unstable();
+
+        for query in ["", "ab", "cafe", "fad", "xyz", "ça"].iter() {
+            for k in 0..4 {
+                let automaton = LevenshteinAutomaton::new(query, k);
+
+                let expected: Vec<(&str, usize)> = sorted
+                    .iter()
+                    .map(|word| (*word, levenshtein_str(query, word)))
+                    .filter(|(_, distance)| *distance <= k)
+                    .collect();
+
+                let from_trie: Vec<(&str, usize)> = automaton
+                    .search_trie(&trie)
+                    .into_iter()
+                    .map(|(word, distance, value)| {
+                        assert_eq!(WORDS[*value], word);
+                        (WORDS[*value], distance)
+                    })
+                    .collect();
+                assert_eq!(from_trie, expected);
+                assert_eq!(automaton.search_sorted(&sorted), expected);
+
+                let mut unsorted: Vec<(&str, usize)> = automaton.search_sorted(&WORDS);
+                unsorted.sort_unstable();
+                assert_eq!(unsorted, expected);
+
+                for word in WORDS.iter() {
+                    let distance = levenshtein_str(query, word);
+                    let expected = if distance <= k { Some(distance) } else { None };
+                    assert_eq!(automaton.matches(word), expected);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,366 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// How a KdTree measures the distance between two points
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpatialMetric {
+    /// The straight line distance, the square root of the sum of squared differences
+    Euclidean,
+    /// The taxicab distance, the sum of absolute differences
+    Manhattan,
+}
+
+impl SpatialMetric {
+    fn distance<const K: usize>(self, a: &[f64; K], b: &[f64; K]) -> f64 {
+        let differences = a.iter().zip(b.iter()).map(|(x, y)| x - y);
+
+        match self {
+            SpatialMetric::Euclidean => differences.map(|d| d * d).sum::<f64>().sqrt(),
+            SpatialMetric::Manhattan => differences.map(f64::abs).sum(),
+        }
+    }
+}
+
+/// A k-d tree over points in K dimensions. It is built once from a set of points, splitting each subtree at the
+/// median of its widest axis, so the tree is balanced and holds no pointers. Every query returns the indices of the
+/// points in the array it was built from.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::{KdTree, SpatialMetric};
+///
+/// let cities = [[51.5, -0.1], [48.9, 2.4], [52.5, 13.4], [40.4, -3.7], [41.9, 12.5]];
+/// let tree = KdTree::new(&cities, SpatialMetric::Euclidean).unwrap();
+///
+/// assert_eq!(tree.nearest(&[50.0, 3.0]).map(|(i, _)| i), Some(1));
+///
+/// let near: Vec<usize> = tree.k_nearest(&[50.0, 3.0], 2).into_iter().map(|(i, _)| i).collect();
+/// assert_eq!(near, vec![1, 0]);
+///
+/// assert_eq!(tree.within_box(&[45.0, -5.0], &[55.0, 5.0]), vec![0, 1]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct KdTree<const K: usize> {
+    metric: SpatialMetric,
+    points: Vec<[f64; K]>,
+    // the subtree over order[low..high] has its root at the middle, split along axes[middle]
+    order: Vec<usize>,
+    axes: Vec<usize>,
+}
+
+impl<const K: usize> KdTree<K> {
+    /// This will return a new KdTree over the given points. An error is returned if K is 0 or any coordinate is NaN
+    /// or infinite.
+    pub fn new(points: &[[f64; K]], metric: SpatialMetric) -> Result<Self, String> {
+        if K == 0 {
+            return Err("a k-d tree needs at least one dimension".to_string());
+        }
+        if points.iter().flatten().any(|x| !x.is_finite()) {
+            return Err("every coordinate of a k-d tree point must be finite".to_string());
+        }
+
+        let mut tree = KdTree {
+            metric,
+            points: points.to_vec(),
+            order: (0..points.len()).collect(),
+            axes: vec![0; points.len()],
+        };
+        tree.build(0, points.len());
+
+        Ok(tree)
+    }
+
+    fn build(&mut self, low: usize, high: usize) {
+        if low >= high {
+            return;
+        }
+
+        // split along the axis the points in this subtree are most spread out on
+        let axis = (0..K)
+            .map(|axis| {
+                let values = self.order[low..high].iter().map(|i| self.points[*i][axis]);
+                let min = values.clone().fold(f64::INFINITY, f64::min);
+                let max = values.fold(f64::NEG_INFINITY, f64::max);
+                (max - min, axis)
+            })
+            .max_by(|a, b| a.0.total_cmp(&b.0).then(b.1.cmp(&a.1)))
+            .map_or(0, |(_, axis)| axis);
+
+        let middle = low + (high - low) / 2;
+        let points = &self.points;
+        self.order[low..high].select_nth_unstable_by(middle - low, |a, b| {
+            points[*a][axis].total_cmp(&points[*b][axis])
+        });
+        self.axes[middle] = axis;
+
+        self.build(low, middle);
+        self.build(middle + 1, high);
+    }
+
+    /// The number of points in the tree
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Return true if the tree holds no points
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// The metric distances are measured with
+    pub fn metric(&self) -> SpatialMetric {
+        self.metric
+    }
+
+    /// Return the point at the given index of the array the tree was built from
+    pub fn point(&self, index: usize) -> Option<&[f64; K]> {
+        self.points.get(index)
+    }
+
+    /// Return the index of the point closest to the query along with its distance, the lowest index when several are
+    /// as close. None is returned if the tree is empty.
+    pub fn nearest(&self, query: &[f64; K]) -> Option<(usize, f64)> {
+        self.k_nearest(query, 1).pop()
+    }
+
+    /// Return the indices of the k points closest to the query along with their distances, closest first. Points at
+    /// the same distance come in order of their index.
+    pub fn k_nearest(&self, query: &[f64; K], k: usize) -> Vec<(usize, f64)> {
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            self.k_nearest_in(query, k, 0, self.len(), &mut heap);
+        }
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|found| (found.index, found.distance))
+            .collect()
+    }
+
+    fn k_nearest_in(
+        &self,
+        query: &[f64; K],
+        k: usize,
+        low: usize,
+        high: usize,
+        heap: &mut BinaryHeap<Neighbour>,
+    ) {
+        if low >= high {
+            return;
+        }
+
+        let middle = low + (high - low) / 2;
+        let index = self.order[middle];
+        let candidate = Neighbour {
+            distance: self.metric.distance(query, &self.points[index]),
+            index,
+        };
+
+        if heap.len() < k {
+            heap.push(candidate);
+        } else if heap.peek().is_some_and(|worst| candidate < *worst) {
+            heap.pop();
+            heap.push(candidate);
+        }
+
+        let difference = query[self.axes[middle]] - self.points[index][self.axes[middle]];
+        let (near, far) = if difference < 0.0 {
+            ((low, middle), (middle + 1, high))
+        } else {
+            ((middle + 1, high), (low, middle))
+        };
+
+        self.k_nearest_in(query, k, near.0, near.1, heap);
+
+        // every point past the split is at least as far away as the split itself
+        if heap.len() < k
+            || heap
+                .peek()
+                .is_some_and(|worst| difference.abs() <= worst.distance)
+        {
+            self.k_nearest_in(query, k, far.0, far.1, heap);
+        }
+    }
+
+    /// Return the indices of every point within the radius of the query along with their distances, closest first.
+    /// Points at the same distance come in order of their index.
+    pub fn within_radius(&self, query: &[f64; K], radius: f64) -> Vec<(usize, f64)> {
+        let mut found = Vec::new();
+        let mut stack = vec![(0, self.len())];
+
+        while let Some((low, high)) = stack.pop() {
+            if low >= high {
+                continue;
+            }
+
+            let middle = low + (high - low) / 2;
+            let index = self.order[middle];
+            let distance = self.metric.distance(query, &self.points[index]);
+            if distance <= radius {
+                found.push(Neighbour { distance, index });
+            }
+
+            let difference = query[self.axes[middle]] - self.points[index][self.axes[middle]];
+            if difference <= radius {
+                stack.push((low, middle));
+            }
+            if -difference <= radius {
+                stack.push((middle + 1, high));
+            }
+        }
+
+        found.sort_unstable();
+        found
+            .into_iter()
+            .map(|found| (found.index, found.distance))
+            .collect()
+    }
+
+    /// Return the indices of every point inside the box from min to max, edges included, in order of their index.
+    pub fn within_box(&self, min: &[f64; K], max: &[f64; K]) -> Vec<usize> {
+        let mut found = Vec::new();
+        let mut stack = vec![(0, self.len())];
+
+        while let Some((low, high)) = stack.pop() {
+            if low >= high {
+                continue;
+            }
+
+            let middle = low + (high - low) / 2;
+            let index = self.order[middle];
+            let point = &self.points[index];
+            if (0..K).all(|axis| min[axis] <= point[axis] && point[axis] <= max[axis]) {
+                found.push(index);
+            }
+
+            let axis = self.axes[middle];
+            if min[axis] <= point[axis] {
+                stack.push((low, middle));
+            }
+            if point[axis] <= max[axis] {
+                stack.push((middle + 1, high));
+            }
+        }
+
+        found.sort_unstable();
+        found
+    }
+}
+
+// a point found by a query, ordered by distance and then by index
+#[derive(Debug, Clone, Copy)]
+struct Neighbour {
+    distance: f64,
+    index: usize,
+}
+
+impl PartialEq for Neighbour {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Neighbour {}
+
+impl PartialOrd for Neighbour {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Neighbour {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then(self.index.cmp(&other.index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::random;
+
+    fn points<const K: usize>(count: usize, seed: u64) -> Vec<[f64; K]> {
+        let mut state = seed;
+
+        // a coarse grid so there are plenty of duplicates and ties
+        (0..count)
+            .map(|_| [(); K].map(|_| random(&mut state, 21) as f64 - 10.0))
+            .collect()
+    }
+
+    fn check<const K: usize>(metric: super::SpatialMetric, seed: u64) {
+        use super::*;
+
+        let data = points::<K>(300, seed);
+        let queries = points::<K>(40, seed + 1);
+        let tree = KdTree::new(&data, metric).unwrap();
+
+        for query in queries.iter() {
+            let mut all: Vec<(usize, f64)> = data
+                .iter()
+                .enumerate()
+                .map(|(i, point)| (i, metric.distance(query, point)))
+                .collect();
+            all.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+
+            assert_eq!(tree.nearest(query), all.first().copied());
+            for k in [0, 1, 5, 17, 300, 400].iter() {
+                let expected: Vec<(usize, f64)> = all.iter().take(*k).copied().collect();
+                assert_eq!(tree.k_nearest(query, *k), expected);
+            }
+
+            for radius in [0.0, 1.0, 4.5, 9.0].iter() {
+                let expected: Vec<(usize, f64)> = all
+                    .iter()
+                    .filter(|(_, distance)| distance <= radius)
+                    .copied()
+                    .collect();
+                assert_eq!(tree.within_radius(query, *radius), expected);
+            }
+
+            let min = query.map(|x| x - 3.0);
+            let max = query.map(|x| x + 2.0);
+            let expected: Vec<usize> = (0..data.len())
+                .filter(|i| {
+                    (0..K).all(|axis| min[axis] <= data[*i][axis] && data[*i][axis] <= max[axis])
+                })
+                .collect();
+            assert_eq!(tree.within_box(&min, &max), expected);
+        }
+    }
+
+    #[test]
+    fn kd_tree_matches_naive() {
+        use super::*;
+
+        check::<1>(SpatialMetric::Euclidean, 7);
+        check::<2>(SpatialMetric::Euclidean, 11);
+        check::<2>(SpatialMetric::Manhattan, 13);
+        check::<3>(SpatialMetric::Euclidean, 17);
+        check::<3>(SpatialMetric::Manhattan, 19);
+    }
+
+    #[test]
+    fn kd_tree_edge_cases() {
+        use super::*;
+
+        let empty: KdTree<2> = KdTree::new(&[], SpatialMetric::Euclidean).unwrap();
+        assert_eq!(empty.nearest(&[0.0, 0.0]), None);
+        assert!(empty.within_radius(&[0.0, 0.0], 10.0).is_empty());
+        assert!(empty.within_box(&[0.0, 0.0], &[1.0, 1.0]).is_empty());
+
+        assert!(KdTree::new(&[[0.0, f64::NAN]], SpatialMetric::Euclidean).is_err());
+        assert!(KdTree::new(&[[f64::INFINITY]], SpatialMetric::Manhattan).is_err());
+        assert!(KdTree::<0>::new(&[[]], SpatialMetric::Euclidean).is_err());
+
+        let tree = KdTree::new(&[[0.0, 0.0], [3.0, 4.0]], SpatialMetric::Manhattan).unwrap();
+        assert_eq!(tree.nearest(&[3.0, 3.0]), Some((1, 1.0)));
+        assert_eq!(
+            tree.within_radius(&[0.0, 0.0], 7.0),
+            vec![(0, 0.0), (1, 7.0)]
+        );
+        assert_eq!(tree.point(1), Some(&[3.0, 4.0]));
+        assert!(tree.within_box(&[1.0, 1.0], &[0.0, 0.0]).is_empty());
+    }
+}
@@ -0,0 +1,266 @@
+//! Linear search, checking every value in turn. The array does not need to be sorted, and for short arrays a linear
+//! search is faster than sorting first and then running a binary search.
+//!
+//! The equality and min/max scans look at fixed size chunks of the array without branching inside a chunk, which lets
+//! the compiler turn the loop into vector instructions for bytes, integers and other plain values.
+
+// values looked at per chunk, enough to fill a few vector registers of bytes
+const CHUNK: usize = 32;
+
+/// Linear search. Return the index of the first value equal to the target, or None if there is none.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::linear;
+///
+/// let s = vec![4, 8, 15, 16, 23, 42, 15];
+/// assert_eq!(linear::index_of(15, &s), Some(2));
+/// assert_eq!(linear::index_of(7, &s), None);
+/// ```
+pub fn index_of<T>(target: T, array: &[T]) -> Option<usize>
+where
+    T: PartialEq,
+{
+    let mut chunks = array.chunks_exact(CHUNK);
+    let mut offset = 0;
+
+    for chunk in &mut chunks {
+        if chunk
+            .iter()
+            .fold(false, |found, value| found | (*value == target))
+        {
+            return chunk
+                .iter()
+                .position(|value| *value == target)
+                .map(|i| offset + i);
+        }
+        offset += CHUNK;
+    }
+
+    chunks
+        .remainder()
+        .iter()
+        .position(|value| *value == target)
+        .map(|i| offset + i)
+}
+
+/// Linear search. Return the index of the last value equal to the target, or None if there is none.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::linear;
+///
+/// let s = b"a.b.c";
+/// assert_eq!(linear::last_index_of(b'.', s), Some(3));
+/// assert_eq!(linear::last_index_of(b'/', s), None);
+/// ```
+pub fn last_index_of<T>(target: T, array: &[T]) -> Option<usize>
+where
+    T: PartialEq,
+{
+    let mut chunks = array.rchunks_exact(CHUNK);
+    let mut end = array.len();
+
+    for chunk in &mut chunks {
+        end -= CHUNK;
+        if chunk
+            .iter()
+            .fold(false, |found, value| found | (*value == target))
+        {
+            return chunk
+                .iter()
+                .rposition(|value| *value == target)
+                .map(|i| end + i);
+        }
+    }
+
+    chunks
+        .remainder()
+        .iter()
+        .rposition(|value| *value == target)
+}
+
+/// Linear search. Return the indices of every value equal to the target, in order.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::linear;
+///
+/// let s = vec!["a", "b", "a", "c", "a"];
+/// assert_eq!(linear::indices_of("a", &s), vec![0, 2, 4]);
+/// assert!(linear::indices_of("d", &s).is_empty());
+/// ```
+pub fn indices_of<T>(target: T, array: &[T]) -> Vec<usize>
+where
+    T: PartialEq,
+{
+    let mut indices = Vec::new();
+    let mut offset = 0;
+
+    for chunk in array.chunks(CHUNK) {
+        // most chunks of most arrays hold no match, those are skipped after one branch free pass
+        if chunk
+            .iter()
+            .fold(false, |found, value| found | (*value == target))
+        {
+            let matches = chunk
+                .iter()
+                .enumerate()
+                .filter(|(_, value)| **value == target);
+            indices.extend(matches.map(|(i, _)| offset + i));
+        }
+        offset += chunk.len();
+    }
+
+    indices
+}
+
+/// Linear search. Return the index of the first value the predicate returns true for, or None if there is none.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::linear;
+///
+/// let s = vec![3, 9, 12, 7, 20];
+/// assert_eq!(linear::position_by(&s, |value| value % 4 == 0), Some(2));
+/// assert_eq!(linear::position_by(&s, |value| *value > 100), None);
+/// ```
+pub fn position_by<T, F>(array: &[T], predicate: F) -> Option<usize>
+where
+    F: FnMut(&T) -> bool,
+{
+    array.iter().position(predicate)
+}
+
+/// Return the smallest value in the array and the index of its first occurance, or None if there is none.
+/// Values that do not compare with themselves, like a float NaN, are skipped.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::linear;
+///
+/// let s = vec![5.5, f64::NAN, 2.25, 8.0, 2.25];
+/// assert_eq!(linear::min(&s), Some((2, 2.25)));
+/// ```
+pub fn min<T>(array: &[T]) -> Option<(usize, T)>
+where
+    T: PartialOrd + Copy,
+{
+    extreme(array, |a, b| a < b)
+}
+
+/// Return the largest value in the array and the index of its first occurance, or None if there is none.
+/// Values that do not compare with themselves, like a float NaN, are skipped.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::linear;
+///
+/// let s = vec![5, 12, -3, 12, 7];
+/// assert_eq!(linear::max(&s), Some((1, 12)));
+/// ```
+pub fn max<T>(array: &[T]) -> Option<(usize, T)>
+where
+    T: PartialOrd + Copy,
+{
+    extreme(array, |a, b| a > b)
+}
+
+// the first value no other value is better than, found with one chunked pass for the value and one for its index
+fn extreme<T, F>(array: &[T], better: F) -> Option<(usize, T)>
+where
+    T: PartialOrd + Copy,
+    F: Fn(T, T) -> bool,
+{
+    let start = array
+        .iter()
+        .position(|value| value.partial_cmp(value).is_some())?;
+    let array = &array[start..];
+
+    // every lane keeps the best value of its own column, a NaN is never better so it never gets in
+    let mut lanes = [array[0]; CHUNK];
+    let mut chunks = array.chunks_exact(CHUNK);
+    for chunk in &mut chunks {
+        for (lane, value) in lanes.iter_mut().zip(chunk.iter()) {
+            if better(*value, *lane) {
+                *lane = *value;
+            }
+        }
+    }
+
+    let best = chunks
+        .remainder()
+        .iter()
+        .chain(lanes.iter())
+        .fold(
+            array[0],
+            |best, value| if better(*value, best) { *value } else { best },
+        );
+
+    index_of(best, array).map(|i| (start + i, best))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::random;
+
+    #[test]
+    fn linear_occurrences() {
+        use super::*;
+
+        let mut state = 23;
+        for len in [0, 1, 5, 31, 32, 33, 64, 100, 257].iter() {
+            let array: Vec<u8> = (0..*len).map(|_| random(&mut state, 20) as u8).collect();
+
+            for target in 0..21 {
+                let expected: Vec<usize> =
+                    (0..array.len()).filter(|i| array[*i] == target).collect();
+
+                assert_eq!(index_of(target, &array), expected.first().copied());
+                assert_eq!(last_index_of(target, &array), expected.last().copied());
+                assert_eq!(indices_of(target, &array), expected);
+                assert_eq!(
+                    position_by(&array, |value| *value == target),
+                    expected.first().copied()
+                );
+            }
+        }
+
+        let words = vec!["rust", "go", "c", "go"];
+        assert_eq!(index_of("go", &words), Some(1));
+        assert_eq!(last_index_of("go", &words), Some(3));
+        assert_eq!(index_of("zig", &words), None);
+    }
+
+    #[test]
+    fn linear_min_max() {
+        use super::*;
+
+        let mut state = 41;
+        for len in [1, 7, 32, 33, 100, 1000].iter() {
+            let array: Vec<i64> = (0..*len)
+                .map(|_| random(&mut state, 500) as i64 - 250)
+                .collect();
+
+            let low = *array.iter().min().unwrap();
+            let high = *array.iter().max().unwrap();
+            assert_eq!(
+                min(&array),
+                Some((array.iter().position(|x| *x == low).unwrap(), low))
+            );
+            assert_eq!(
+                max(&array),
+                Some((array.iter().position(|x| *x == high).unwrap(), high))
+            );
+        }
+
+        let empty: [u32; 0] = [];
+        assert_eq!(min(&empty), None);
+        assert_eq!(max(&empty), None);
+
+        let floats = [f64::NAN, 3.0, f64::NAN, -1.5, 7.0, f64::NAN];
+        assert_eq!(min(&floats), Some((3, -1.5)));
+        assert_eq!(max(&floats), Some((4, 7.0)));
+        assert_eq!(min(&[f64::NAN, f64::NAN]), None);
+    }
+}
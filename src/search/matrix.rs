@@ -0,0 +1,88 @@
+/// Staircase search on a 2D matrix where every row and every column is sorted in ascending order. The search starts
+/// in the top right corner and moves either one column left or one row down on each step, taking at most
+/// rows + columns steps. Every row needs to be the same length.
+///
+/// The target is the value you want to search for in the matrix. If the target is found its (row, column)
+/// coordinates will be returned, or None if the target was not found.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::matrix;
+///
+/// let grid = vec![
+///     vec![1, 4, 7, 11],
+///     vec![2, 5, 8, 12],
+///     vec![3, 6, 9, 16],
+///     vec![10, 13, 14, 17],
+/// ];
+///
+/// assert_eq!(matrix::index_of(9, &grid), Some((2, 2)));
+/// assert_eq!(matrix::index_of(15, &grid), None);
+/// ```
+pub fn index_of<T, R>(target: T, matrix: &[R]) -> Option<(usize, usize)>
+where
+    T: PartialOrd,
+    R: AsRef<[T]>,
+{
+    let columns = matrix.first()?.as_ref().len();
+    if columns == 0 {
+        return None;
+    }
+
+    let mut row = 0;
+    let mut column = columns - 1;
+
+    while row < matrix.len() {
+        match matrix[row].as_ref()[column] {
+            ref value if *value == target => return Some((row, column)),
+            ref value if *value > target => {
+                if column == 0 {
+                    return None;
+                }
+                column -= 1;
+            }
+            _ => row += 1,
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn matrix_search() {
+        use super::*;
+
+        let grid = [[1, 2, 8, 9], [2, 4, 9, 12], [4, 7, 10, 13], [6, 8, 11, 15]];
+        for (r, row) in grid.iter().enumerate() {
+            for (c, value) in row.iter().enumerate() {
+                let (found_r, found_c) = index_of(*value, &grid).unwrap();
+                assert_eq!(grid[found_r][found_c], *value);
+                if *value != 2 && *value != 4 && *value != 8 && *value != 9 {
+                    assert_eq!((found_r, found_c), (r, c));
+                }
+            }
+        }
+
+        assert_eq!(index_of(0, &grid), None);
+        assert_eq!(index_of(5, &grid), None);
+        assert_eq!(index_of(16, &grid), None);
+    }
+
+    #[test]
+    fn matrix_shapes() {
+        use super::*;
+
+        let wide = vec![vec!["a", "c", "e", "g"]];
+        assert_eq!(index_of("e", &wide), Some((0, 2)));
+
+        let tall = vec![vec![1], vec![3], vec![5]];
+        assert_eq!(index_of(5, &tall), Some((2, 0)));
+        assert_eq!(index_of(4, &tall), None);
+
+        let empty: Vec<Vec<i32>> = vec![];
+        assert_eq!(index_of(1, &empty), None);
+        assert_eq!(index_of(1, &[Vec::<i32>::new()]), None);
+    }
+}
@@ -1 +1,26 @@
+pub use self::aho_corasick::{AhoCorasick, MatchKind, PatternMatch, StreamMatches};
+pub use self::fuzzy::{AutomatonState, BkTree, LevenshteinAutomaton};
+pub use self::kd_tree::{KdTree, SpatialMetric};
+pub use self::order_statistic::{OrderStatisticIter, OrderStatisticTree};
+pub use self::sorted_vec::{SortedMap, SortedVec};
+pub use self::static_index::{Layout, StaticIndex};
+pub use self::trie::{PrefixIter, RadixTrie};
+
 pub mod binary;
+pub mod bitonic;
+pub mod filter;
+pub mod linear;
+pub mod matrix;
+pub mod range;
+pub mod rotated;
+pub mod text;
+pub mod unimodal;
+
+mod aho_corasick;
+mod bytes;
+mod fuzzy;
+mod kd_tree;
+mod order_statistic;
+mod sorted_vec;
+mod static_index;
+mod trie;
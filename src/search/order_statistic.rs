@@ -0,0 +1,557 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::iter::FromIterator;
+use std::ops::{Bound, RangeBounds};
+
+#[derive(Debug, Clone)]
+struct Node<T> {
+    value: T,
+    // copies of the value held by this node
+    count: usize,
+    // copies held by the whole subtree
+    size: usize,
+    priority: u64,
+    left: Option<Box<Node<T>>>,
+    right: Option<Box<Node<T>>>,
+}
+
+fn size<T>(node: &Option<Box<Node<T>>>) -> usize {
+    node.as_ref().map_or(0, |node| node.size)
+}
+
+fn priority<T>(node: &Option<Box<Node<T>>>) -> u64 {
+    node.as_ref().map_or(0, |node| node.priority)
+}
+
+impl<T> Node<T> {
+    fn update(&mut self) {
+        self.size = self.count + size(&self.left) + size(&self.right);
+    }
+
+    // lift the left child above this node
+    fn rotate_right(node: &mut Box<Node<T>>) {
+        let mut left = node.left.take().unwrap();
+        node.left = left.right.take();
+        node.update();
+        std::mem::swap(node, &mut left);
+        node.right = Some(left);
+        node.update();
+    }
+
+    // lift the right child above this node
+    fn rotate_left(node: &mut Box<Node<T>>) {
+        let mut right = node.right.take().unwrap();
+        node.right = right.left.take();
+        node.update();
+        std::mem::swap(node, &mut right);
+        node.left = Some(right);
+        node.update();
+    }
+}
+
+/// A multiset that answers rank ("how many values are less than x") and select ("the k-th smallest value") in
+/// O(log n) while values are added and removed. It is a treap, a binary search tree kept balanced by giving every node
+/// a random priority, with each node knowing how many values are in its subtree. Equal values share a node, so
+/// duplicates cost no extra depth.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::OrderStatisticTree;
+///
+/// let mut scores: OrderStatisticTree<u32> = vec![70, 85, 85, 92, 60].into_iter().collect();
+/// scores.insert(78);
+/// scores.remove(&60);
+///
+/// // one score is below 78
+/// assert_eq!(scores.rank(&78), 1);
+/// assert_eq!(scores.select(2), Some(&85));
+/// assert_eq!(scores.count(&85), 2);
+/// assert_eq!(scores.count_range(75..90), 3);
+/// assert_eq!(scores.iter().copied().collect::<Vec<u32>>(), vec![70, 78, 85, 85, 92]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct OrderStatisticTree<T> {
+    root: Option<Box<Node<T>>>,
+    // the xorshift state node priorities are drawn from
+    random: u64,
+}
+
+impl<T> Default for OrderStatisticTree<T> {
+    fn default() -> Self {
+        OrderStatisticTree::with_seed(0)
+    }
+}
+
+impl<T> OrderStatisticTree<T> {
+    /// This will return a new empty OrderStatisticTree
+    pub fn new() -> Self {
+        OrderStatisticTree::default()
+    }
+
+    /// This will return a new empty OrderStatisticTree that draws node priorities from the given seed. Trees built
+    /// with the same seed and the same operations have the same shape.
+    pub fn with_seed(seed: u64) -> Self {
+        let random = seed ^ 0x9e37_79b9_7f4a_7c15;
+        OrderStatisticTree {
+            root: None,
+            random: if random == 0 { 1 } else { random },
+        }
+    }
+
+    /// The number of values in the tree, counting every copy
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    /// Return true if the tree holds no values
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Remove every value
+    pub fn clear(&mut self) {
+        self.root = None;
+    }
+
+    /// Iterate over the values in sorted order, equal values repeated once per copy
+    pub fn iter(&self) -> OrderStatisticIter<'_, T> {
+        let mut iter = OrderStatisticIter {
+            stack: Vec::new(),
+            remaining: self.len(),
+        };
+        iter.push_left(&self.root);
+        iter
+    }
+
+    /// Return the k-th smallest value, counting from 0 and counting every copy, or None if the tree holds k values or
+    /// fewer.
+    pub fn select(&self, k: usize) -> Option<&T> {
+        let mut k = k;
+        let mut node = self.root.as_ref();
+
+        while let Some(current) = node {
+            let left = size(&current.left);
+            if k < left {
+                node = current.left.as_ref();
+            } else if k < left + current.count {
+                return Some(&current.value);
+            } else {
+                k -= left + current.count;
+                node = current.right.as_ref();
+            }
+        }
+        None
+    }
+
+    /// The smallest value, or None if the tree is empty
+    pub fn first(&self) -> Option<&T> {
+        let mut node = self.root.as_ref()?;
+        while let Some(left) = node.left.as_ref() {
+            node = left;
+        }
+        Some(&node.value)
+    }
+
+    /// The largest value, or None if the tree is empty
+    pub fn last(&self) -> Option<&T> {
+        let mut node = self.root.as_ref()?;
+        while let Some(right) = node.right.as_ref() {
+            node = right;
+        }
+        Some(&node.value)
+    }
+
+    fn next_random(&mut self) -> u64 {
+        self.random ^= self.random << 13;
+        self.random ^= self.random >> 7;
+        self.random ^= self.random << 17;
+        self.random
+    }
+}
+
+impl<T> OrderStatisticTree<T>
+where
+    T: Ord,
+{
+    /// Add a copy of the value, returning how many copies the tree now holds.
+    pub fn insert(&mut self, value: T) -> usize {
+        let priority = self.next_random();
+        insert(&mut self.root, value, priority)
+    }
+
+    /// Remove one copy of the value, returning false if the value was not in the tree.
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        remove(&mut self.root, value, false) > 0
+    }
+
+    /// Remove every copy of the value, returning how many were removed.
+    pub fn remove_all<Q>(&mut self, value: &Q) -> usize
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        remove(&mut self.root, value, true)
+    }
+
+    /// Return the number of copies of the value in the tree
+    pub fn count<Q>(&self, value: &Q) -> usize
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        let mut node = self.root.as_ref();
+
+        while let Some(current) = node {
+            node = match value.cmp(current.value.borrow()) {
+                Ordering::Less => current.left.as_ref(),
+                Ordering::Greater => current.right.as_ref(),
+                Ordering::Equal => return current.count,
+            };
+        }
+        0
+    }
+
+    /// Return true if the value is in the tree
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        self.count(value) > 0
+    }
+
+    /// Return the number of values in the tree that are less than the given one, counting every copy
+    pub fn rank<Q>(&self, value: &Q) -> usize
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        self.count_below(value, false)
+    }
+
+    /// Return the number of values in the tree within the range, counting every copy
+    pub fn count_range<Q, R>(&self, range: R) -> usize
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+        R: RangeBounds<Q>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(start) => self.count_below(start, false),
+            Bound::Excluded(start) => self.count_below(start, true),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => self.count_below(end, true),
+            Bound::Excluded(end) => self.count_below(end, false),
+            Bound::Unbounded => self.len(),
+        };
+
+        end.saturating_sub(start)
+    }
+
+    // the number of values less than the given one, or less than or equal to it when inclusive
+    fn count_below<Q>(&self, value: &Q, inclusive: bool) -> usize
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        let mut below = 0;
+        let mut node = self.root.as_ref();
+
+        while let Some(current) = node {
+            match value.cmp(current.value.borrow()) {
+                Ordering::Less => node = current.left.as_ref(),
+                Ordering::Greater => {
+                    below += size(&current.left) + current.count;
+                    node = current.right.as_ref();
+                }
+                Ordering::Equal => {
+                    below += size(&current.left);
+                    if inclusive {
+                        below += current.count;
+                    }
+                    break;
+                }
+            }
+        }
+        below
+    }
+}
+
+fn insert<T>(node: &mut Option<Box<Node<T>>>, value: T, priority: u64) -> usize
+where
+    T: Ord,
+{
+    let current = match node {
+        Some(current) => current,
+        None => {
+            *node = Some(Box::new(Node {
+                value,
+                count: 1,
+                size: 1,
+                priority,
+                left: None,
+                right: None,
+            }));
+            return 1;
+        }
+    };
+
+    let count = match value.cmp(&current.value) {
+        Ordering::Equal => {
+            current.count += 1;
+            current.count
+        }
+        Ordering::Less => {
+            let count = insert(&mut current.left, value, priority);
+            if self::priority(&current.left) > current.priority {
+                Node::rotate_right(current);
+            }
+            count
+        }
+        Ordering::Greater => {
+            let count = insert(&mut current.right, value, priority);
+            if self::priority(&current.right) > current.priority {
+                Node::rotate_left(current);
+            }
+            count
+        }
+    };
+
+    // a rotation updated the sizes of the nodes it moved, this covers the plain descent
+    current.update();
+    count
+}
+
+// remove one copy of the value, or every copy when all is set, returning how many were removed
+fn remove<T, Q>(node: &mut Option<Box<Node<T>>>, value: &Q, all: bool) -> usize
+where
+    Q: Ord + ?Sized,
+    T: Borrow<Q>,
+{
+    let current = match node {
+        Some(current) => current,
+        None => return 0,
+    };
+
+    let removed = match value.cmp(current.value.borrow()) {
+        Ordering::Less => remove(&mut current.left, value, all),
+        Ordering::Greater => remove(&mut current.right, value, all),
+        Ordering::Equal if current.count > 1 && !all => {
+            current.count -= 1;
+            1
+        }
+        Ordering::Equal => {
+            let removed = current.count;
+            unlink(node);
+            return removed;
+        }
+    };
+
+    current.update();
+    removed
+}
+
+// take a node out of the tree, rotating it down until it has at most one child
+fn unlink<T>(node: &mut Option<Box<Node<T>>>) {
+    let current = node.as_mut().unwrap();
+
+    match (current.left.is_some(), current.right.is_some()) {
+        (false, _) => *node = current.right.take(),
+        (_, false) => *node = current.left.take(),
+        _ => {
+            // the child with the higher priority takes the node's place
+            if priority(&current.left) > priority(&current.right) {
+                Node::rotate_right(current);
+                unlink(&mut current.right);
+            } else {
+                Node::rotate_left(current);
+                unlink(&mut current.left);
+            }
+            current.update();
+        }
+    }
+}
+
+impl<T> FromIterator<T> for OrderStatisticTree<T>
+where
+    T: Ord,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(values: I) -> Self {
+        let mut tree = OrderStatisticTree::new();
+        tree.extend(values);
+        tree
+    }
+}
+
+impl<T> Extend<T> for OrderStatisticTree<T>
+where
+    T: Ord,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        for value in values {
+            self.insert(value);
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a OrderStatisticTree<T> {
+    type Item = &'a T;
+    type IntoIter = OrderStatisticIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An in-order iterator over the values of an OrderStatisticTree, returned by `OrderStatisticTree::iter`
+#[derive(Debug, Clone)]
+pub struct OrderStatisticIter<'a, T> {
+    // the nodes still to visit, with how many copies of each are left to return
+    stack: Vec<(&'a Node<T>, usize)>,
+    remaining: usize,
+}
+
+impl<'a, T> OrderStatisticIter<'a, T> {
+    fn push_left(&mut self, node: &'a Option<Box<Node<T>>>) {
+        let mut node = node.as_ref();
+        while let Some(current) = node {
+            self.stack.push((current, current.count));
+            node = current.left.as_ref();
+        }
+    }
+}
+
+impl<'a, T> Iterator for OrderStatisticIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (node, copies) = self.stack.last_mut()?;
+        let node: &'a Node<T> = node;
+
+        *copies -= 1;
+        if *copies == 0 {
+            self.stack.pop();
+            self.push_left(&node.right);
+        }
+
+        self.remaining -= 1;
+        Some(&node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for OrderStatisticIter<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::random;
+
+    // the depth of the deepest node, to check the tree stays balanced
+    fn depth<T>(node: &Option<Box<super::Node<T>>>) -> usize {
+        node.as_ref()
+            .map_or(0, |node| 1 + depth(&node.left).max(depth(&node.right)))
+    }
+
+    #[test]
+    fn order_statistic_matches_sorted_vec() {
+        use super::*;
+
+        let mut state = 17;
+        let mut tree = OrderStatisticTree::with_seed(3);
+        let mut expected: Vec<u64> = Vec::new();
+
+        for round in 0..3000 {
+            let value = random(&mut state, 200);
+            match round % 5 {
+                0..=2 => {
+                    let at = expected.partition_point(|x| *x <= value);
+                    expected.insert(at, value);
+                    assert_eq!(
+                        tree.insert(value),
+                        expected.iter().filter(|x| **x == value).count()
+                    );
+                }
+                3 => {
+                    let found = expected.iter().position(|x| *x == value);
+                    assert_eq!(tree.remove(&value), found.is_some());
+                    if let Some(at) = found {
+                        expected.remove(at);
+                    }
+                }
+                _ => {
+                    let before = expected.len();
+                    expected.retain(|x| *x != value);
+                    assert_eq!(tree.remove_all(&value), before - expected.len());
+                }
+            }
+
+            assert_eq!(tree.len(), expected.len());
+            assert_eq!(
+                tree.rank(&value),
+                expected.iter().filter(|x| **x < value).count()
+            );
+            assert_eq!(
+                tree.count(&value),
+                expected.iter().filter(|x| **x == value).count()
+            );
+
+            let k = random(&mut state, expected.len() as u64 + 2) as usize;
+            assert_eq!(tree.select(k), expected.get(k));
+
+            let low = random(&mut state, 200);
+            let high = random(&mut state, 200);
+            let inside = |x: &&u64| low < **x && **x <= high;
+            assert_eq!(
+                tree.count_range((Bound::Excluded(low), Bound::Included(high))),
+                expected.iter().filter(inside).count()
+            );
+        }
+
+        assert_eq!(tree.iter().len(), expected.len());
+        assert!(tree.iter().eq(expected.iter()));
+        assert_eq!(tree.first(), expected.first());
+        assert_eq!(tree.last(), expected.last());
+        assert!(tree.contains(&expected[0]));
+    }
+
+    #[test]
+    fn order_statistic_stays_balanced() {
+        use super::*;
+
+        // sorted input is the worst case for an unbalanced tree
+        let tree: OrderStatisticTree<u32> = (0..100_000).collect();
+        assert_eq!(tree.len(), 100_000);
+        assert!(depth(&tree.root) < 60, "depth {}", depth(&tree.root));
+
+        assert_eq!(tree.select(12_345), Some(&12_345));
+        assert_eq!(tree.rank(&12_345), 12_345);
+        assert_eq!(tree.count_range(100..), 99_900);
+        assert_eq!(tree.count_range(..=99), 100);
+        assert_eq!(
+            tree.count_range((Bound::Included(50), Bound::Excluded(10))),
+            0
+        );
+
+        let mut tree = tree;
+        for i in (0..100_000).step_by(2) {
+            assert!(tree.remove(&i));
+        }
+        assert_eq!(tree.len(), 50_000);
+        assert!(depth(&tree.root) < 60);
+        assert_eq!(tree.select(0), Some(&1));
+
+        tree.clear();
+        assert!(tree.is_empty());
+        assert_eq!(tree.select(0), None);
+        assert_eq!(tree.first(), None);
+    }
+}
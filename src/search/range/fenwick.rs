@@ -0,0 +1,380 @@
+use std::ops::RangeBounds;
+
+use super::{bounds, Number, Signed};
+
+/// A Fenwick tree (binary indexed tree), updating a single value and summing a range both in O(log n). It works over
+/// unsigned types too, as long as no value is taken below zero.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::range::FenwickTree;
+///
+/// let mut counts = FenwickTree::new(&[0u32, 4, 1, 0, 7]);
+/// counts.add(3, 2);
+/// counts.sub(4, 5);
+///
+/// assert_eq!(counts.prefix_sum(3), 5);
+/// assert_eq!(counts.range_sum(2..), 5);
+/// assert_eq!(counts.get(4), 2);
+///
+/// // the first index where the running total reaches 6
+/// assert_eq!(counts.lower_bound(6), Some(3));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FenwickTree<T> {
+    // tree[i] holds the sum of the lowbit(i) values ending at index i - 1, tree[0] is unused
+    tree: Vec<T>,
+}
+
+impl<T> FenwickTree<T>
+where
+    T: Number,
+{
+    /// This will return a new FenwickTree over the given values, built in O(n)
+    pub fn new(values: &[T]) -> Self {
+        let mut tree = vec![T::ZERO; values.len() + 1];
+        tree[1..].copy_from_slice(values);
+
+        for i in 1..tree.len() {
+            let parent = i + lowbit(i);
+            if parent < tree.len() {
+                tree[parent] = tree[parent] + tree[i];
+            }
+        }
+
+        FenwickTree { tree }
+    }
+
+    /// This will return a new FenwickTree of len zeros
+    pub fn with_len(len: usize) -> Self {
+        FenwickTree {
+            tree: vec![T::ZERO; len + 1],
+        }
+    }
+
+    /// The number of values in the tree
+    pub fn len(&self) -> usize {
+        self.tree.len() - 1
+    }
+
+    /// Return true if the tree holds no values
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Add delta to the value at the index
+    ///
+    /// # Panics
+    /// If the index is out of bounds
+    pub fn add(&mut self, index: usize, delta: T) {
+        self.check(index);
+
+        let mut i = index + 1;
+        while i < self.tree.len() {
+            self.tree[i] = self.tree[i] + delta;
+            i += lowbit(i);
+        }
+    }
+
+    /// Subtract delta from the value at the index
+    ///
+    /// # Panics
+    /// If the index is out of bounds
+    pub fn sub(&mut self, index: usize, delta: T) {
+        self.check(index);
+
+        let mut i = index + 1;
+        while i < self.tree.len() {
+            self.tree[i] = self.tree[i] - delta;
+            i += lowbit(i);
+        }
+    }
+
+    /// Replace the value at the index
+    ///
+    /// # Panics
+    /// If the index is out of bounds
+    pub fn set(&mut self, index: usize, value: T) {
+        let old = self.get(index);
+        if value >= old {
+            self.add(index, value - old);
+        } else {
+            self.sub(index, old - value);
+        }
+    }
+
+    /// Return the value at the index
+    ///
+    /// # Panics
+    /// If the index is out of bounds
+    pub fn get(&self, index: usize) -> T {
+        self.check(index);
+        self.range_sum(index..=index)
+    }
+
+    /// Return the sum of the first end values
+    ///
+    /// # Panics
+    /// If end is larger than the length
+    pub fn prefix_sum(&self, end: usize) -> T {
+        bounds(..end, self.len());
+
+        let mut sum = T::ZERO;
+        let mut i = end;
+        while i > 0 {
+            sum = sum + self.tree[i];
+            i -= lowbit(i);
+        }
+        sum
+    }
+
+    /// Return the sum of the values in the range
+    ///
+    /// # Panics
+    /// If the range is out of bounds
+    pub fn range_sum<R>(&self, range: R) -> T
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = bounds(range, self.len());
+        self.prefix_sum(end) - self.prefix_sum(start)
+    }
+
+    /// Return the first index where the sum of the values up to and including it reaches the target, or None if the
+    /// sum of every value is below the target. Every value must be non-negative for the answer to be right.
+    pub fn lower_bound(&self, target: T) -> Option<usize> {
+        let mut position = 0;
+        let mut remaining = target;
+        let mut step = if self.is_empty() {
+            0
+        } else {
+            1 << (usize::BITS - 1 - self.len().leading_zeros())
+        };
+
+        while step > 0 {
+            let next = position + step;
+            if next < self.tree.len() && self.tree[next] < remaining {
+                position = next;
+                remaining = remaining - self.tree[next];
+            }
+            step >>= 1;
+        }
+
+        if position < self.len() {
+            Some(position)
+        } else {
+            None
+        }
+    }
+
+    fn check(&self, index: usize) {
+        assert!(
+            index < self.len(),
+            "index {} is out of range for length {}",
+            index,
+            self.len()
+        );
+    }
+}
+
+/// A Fenwick tree that adds to every value in a range and sums a range, both in O(log n). It is made of two inner
+/// Fenwick trees that hold negative terms, so it needs a signed type.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::range::RangeFenwickTree;
+///
+/// let mut heights = RangeFenwickTree::new(&[1, 1, 1, 1, 1]);
+/// heights.add_range(1..4, 10);
+/// heights.add_range(3.., -2);
+///
+/// assert_eq!(heights.range_sum(..), 31);
+/// assert_eq!(heights.get(3), 9);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RangeFenwickTree<T> {
+    // the sum of the first p values is slope.prefix_sum(p) * p - offset.prefix_sum(p)
+    slope: FenwickTree<T>,
+    offset: FenwickTree<T>,
+}
+
+impl<T> RangeFenwickTree<T>
+where
+    T: Signed,
+{
+    /// This will return a new RangeFenwickTree over the given values
+    pub fn new(values: &[T]) -> Self {
+        // the values start out as point differences, so a prefix sum of the slope rebuilds each one
+        let mut slope = vec![T::ZERO; values.len()];
+        let mut offset = vec![T::ZERO; values.len()];
+        let mut previous = T::ZERO;
+
+        for (i, value) in values.iter().enumerate() {
+            let difference = *value - previous;
+            slope[i] = difference;
+            offset[i] = difference * T::from_usize(i);
+            previous = *value;
+        }
+
+        RangeFenwickTree {
+            slope: FenwickTree::new(&slope),
+            offset: FenwickTree::new(&offset),
+        }
+    }
+
+    /// This will return a new RangeFenwickTree of len zeros
+    pub fn with_len(len: usize) -> Self {
+        RangeFenwickTree {
+            slope: FenwickTree::with_len(len),
+            offset: FenwickTree::with_len(len),
+        }
+    }
+
+    /// The number of values in the tree
+    pub fn len(&self) -> usize {
+        self.slope.len()
+    }
+
+    /// Return true if the tree holds no values
+    pub fn is_empty(&self) -> bool {
+        self.slope.is_empty()
+    }
+
+    /// Add delta to every value in the range
+    ///
+    /// # Panics
+    /// If the range is out of bounds
+    pub fn add_range<R>(&mut self, range: R, delta: T)
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = bounds(range, self.len());
+        if start == end {
+            return;
+        }
+
+        self.slope.add(start, delta);
+        self.offset.add(start, delta * T::from_usize(start));
+        if end < self.len() {
+            self.slope.sub(end, delta);
+            self.offset.sub(end, delta * T::from_usize(end));
+        }
+    }
+
+    /// Return the value at the index
+    ///
+    /// # Panics
+    /// If the index is out of bounds
+    pub fn get(&self, index: usize) -> T {
+        self.slope.check(index);
+        self.slope.prefix_sum(index + 1)
+    }
+
+    /// Return the sum of the first end values
+    ///
+    /// # Panics
+    /// If end is larger than the length
+    pub fn prefix_sum(&self, end: usize) -> T {
+        self.slope.prefix_sum(end) * T::from_usize(end) - self.offset.prefix_sum(end)
+    }
+
+    /// Return the sum of the values in the range
+    ///
+    /// # Panics
+    /// If the range is out of bounds
+    pub fn range_sum<R>(&self, range: R) -> T
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = bounds(range, self.len());
+        self.prefix_sum(end) - self.prefix_sum(start)
+    }
+}
+
+fn lowbit(i: usize) -> usize {
+    i & i.wrapping_neg()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::Random;
+
+    #[test]
+    fn fenwick_matches_naive() {
+        use super::*;
+
+        let mut random = Random::new(42);
+        for len in [0, 1, 2, 7, 16, 33].iter() {
+            let mut values: Vec<u64> = (0..*len).map(|_| random.below(10) as u64).collect();
+            let mut tree = FenwickTree::new(&values);
+            let mut ranged =
+                RangeFenwickTree::new(&values.iter().map(|v| *v as i64).collect::<Vec<i64>>());
+
+            for _ in 0..200 {
+                if *len > 0 {
+                    let i = random.below(*len);
+                    let delta = random.below(5) as u64;
+                    match random.below(3) {
+                        0 => {
+                            values[i] += delta;
+                            tree.add(i, delta);
+                            ranged.add_range(i..=i, delta as i64);
+                        }
+                        1 => {
+                            let delta = delta.min(values[i]);
+                            values[i] -= delta;
+                            tree.sub(i, delta);
+                            ranged.add_range(i..=i, -(delta as i64));
+                        }
+                        _ => {
+                            let (start, end) = random.range(*len);
+                            for value in values[start..end].iter_mut() {
+                                *value += delta;
+                            }
+                            for j in start..end {
+                                tree.add(j, delta);
+                            }
+                            ranged.add_range(start..end, delta as i64);
+                        }
+                    }
+                }
+
+                let (start, end) = random.range(*len);
+                let expected: u64 = values[start..end].iter().sum();
+                assert_eq!(tree.range_sum(start..end), expected);
+                assert_eq!(ranged.range_sum(start..end), expected as i64);
+
+                for (i, value) in values.iter().enumerate() {
+                    assert_eq!(tree.get(i), *value);
+                    assert_eq!(ranged.get(i), *value as i64);
+                }
+
+                let target = random.below(values.iter().sum::<u64>() as usize + 2) as u64;
+                let mut total = 0;
+                let expected = values.iter().position(|value| {
+                    total += value;
+                    total >= target
+                });
+                assert_eq!(tree.lower_bound(target), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn fenwick_set_and_bounds() {
+        use super::*;
+
+        let mut tree = FenwickTree::with_len(4);
+        tree.set(2, 9u8);
+        tree.set(2, 3);
+        tree.set(0, 1);
+        assert_eq!(tree.prefix_sum(4), 4);
+        assert_eq!(tree.lower_bound(0), Some(0));
+        assert_eq!(tree.lower_bound(5), None);
+
+        let ranged = RangeFenwickTree::<f64>::with_len(3);
+        assert_eq!(ranged.range_sum(..), 0.0);
+        assert!(std::panic::catch_unwind(|| tree.get(4)).is_err());
+        assert!(std::panic::catch_unwind(|| ranged.range_sum(..4)).is_err());
+    }
+}
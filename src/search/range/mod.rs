@@ -0,0 +1,108 @@
+//! Structures that answer queries over a range of an array, such as the sum or the minimum of every value between
+//! two indices, and keep answering them quickly as the array is updated.
+//!
+//! Ranges are given the same way they are when slicing, `2..5`, `..4` or `..`, and like slicing a range that is out
+//! of bounds or runs backwards panics.
+//!
+//! # Examples
+//! ```
+//! use rs_algo::search::range::{FenwickTree, Min, SegmentTree, SparseTable};
+//!
+//! let values = [5, 2, 8, 1, 9, 3];
+//!
+//! let mut sums = FenwickTree::new(&values);
+//! sums.add(1, 10);
+//! assert_eq!(sums.range_sum(1..4), 21);
+//!
+//! let mut mins: SegmentTree<Min<i32>> = SegmentTree::new(&values);
+//! mins.set(3, 7);
+//! assert_eq!(mins.query(2..5), 7);
+//!
+//! let table: SparseTable<Min<i32>> = SparseTable::new(&values);
+//! assert_eq!(table.query(..), 1);
+//! ```
+pub use self::fenwick::{FenwickTree, RangeFenwickTree};
+pub use self::monoid::{
+    AddMax, AddMin, AddSum, AssignMax, AssignMin, AssignSum, Idempotent, MapMonoid, Max, Min,
+    Monoid, Number, Signed, Sum,
+};
+pub use self::segment_tree::{LazySegmentTree, SegmentTree};
+pub use self::sparse_table::SparseTable;
+
+mod fenwick;
+mod monoid;
+mod segment_tree;
+mod sparse_table;
+
+use std::ops::{Bound, RangeBounds};
+
+// turn a range into start and end indices, panicking the way slicing does when it is out of bounds
+fn bounds<R>(range: R, len: usize) -> (usize, usize)
+where
+    R: RangeBounds<usize>,
+{
+    let start = match range.start_bound() {
+        Bound::Included(start) => *start,
+        Bound::Excluded(start) => start + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => end + 1,
+        Bound::Excluded(end) => *end,
+        Bound::Unbounded => len,
+    };
+
+    assert!(
+        start <= end,
+        "range starts at {} but ends at {}",
+        start,
+        end
+    );
+    assert!(
+        end <= len,
+        "range end {} is out of range for length {}",
+        end,
+        len
+    );
+
+    (start, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::random;
+
+    pub(super) struct Random(u64);
+
+    impl Random {
+        pub(super) fn new(seed: u64) -> Self {
+            Random(seed)
+        }
+
+        pub(super) fn below(&mut self, n: usize) -> usize {
+            random(&mut self.0, n as u64) as usize
+        }
+
+        pub(super) fn range(&mut self, len: usize) -> (usize, usize) {
+            let a = self.below(len + 1);
+            let b = self.below(len + 1);
+            (a.min(b), a.max(b))
+        }
+    }
+
+    #[test]
+    fn range_bounds() {
+        use super::*;
+
+        assert_eq!(bounds(.., 5), (0, 5));
+        assert_eq!(bounds(1..3, 5), (1, 3));
+        assert_eq!(bounds(1..=3, 5), (1, 4));
+        assert_eq!(bounds(..=4, 5), (0, 5));
+        assert_eq!(bounds(5.., 5), (5, 5));
+
+        assert!(std::panic::catch_unwind(|| bounds(2..6, 5)).is_err());
+        #[allow(clippy::reversed_empty_ranges)]
+        let backwards = std::panic::catch_unwind(|| bounds(3..2, 5));
+        assert!(backwards.is_err());
+    }
+}
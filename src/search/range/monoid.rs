@@ -0,0 +1,291 @@
+use std::marker::PhantomData;
+use std::ops::{Add, Mul, Sub};
+
+/// A set of values with an associative way to combine two of them and an identity value that changes nothing when
+/// combined. A `SegmentTree` can answer queries for any monoid.
+pub trait Monoid {
+    /// The values being combined
+    type Value: Clone;
+
+    /// The value that leaves any other value unchanged when combined with it
+    fn identity() -> Self::Value;
+
+    /// Combine two values, a then b. This must be associative but does not need to be commutative.
+    fn combine(a: &Self::Value, b: &Self::Value) -> Self::Value;
+}
+
+/// A monoid where combining a value with itself gives back the same value, such as min or max. A `SparseTable` can
+/// answer queries for any idempotent monoid, as overlapping parts of a range are then counted only once.
+pub trait Idempotent: Monoid {}
+
+/// Maps that update every value in a range of a `LazySegmentTree` at once, such as adding to or assigning every
+/// value. Applying a map to the combined value of a range has to give the same result as applying it to each value
+/// and then combining them.
+pub trait MapMonoid {
+    /// The monoid the values of the tree belong to
+    type Monoid: Monoid;
+
+    /// The maps applied to ranges
+    type Map: Clone;
+
+    /// The map that leaves every value unchanged
+    fn identity_map() -> Self::Map;
+
+    /// Apply a map to the combined value of len values
+    fn apply(
+        map: &Self::Map,
+        value: &<Self::Monoid as Monoid>::Value,
+        len: usize,
+    ) -> <Self::Monoid as Monoid>::Value;
+
+    /// Return the map that does inner and then outer
+    fn compose(outer: &Self::Map, inner: &Self::Map) -> Self::Map;
+}
+
+/// The numeric types the built in monoids work over, every primitive integer and float.
+pub trait Number:
+    Copy + PartialOrd + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    /// Zero, the identity of sums
+    const ZERO: Self;
+    /// The smallest value, the identity of max. Negative infinity for floats.
+    const MIN: Self;
+    /// The largest value, the identity of min. Infinity for floats.
+    const MAX: Self;
+
+    /// Convert a count to this type
+    fn from_usize(n: usize) -> Self;
+}
+
+/// The numeric types that can hold negative values, the signed integers and floats
+pub trait Signed: Number {}
+
+macro_rules! integer {
+    ($($t:ty),*) => {
+        $(
+            impl Number for $t {
+                const ZERO: Self = 0;
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+
+                fn from_usize(n: usize) -> Self {
+                    n as $t
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! float {
+    ($($t:ty),*) => {
+        $(
+            impl Number for $t {
+                const ZERO: Self = 0.0;
+                const MIN: Self = <$t>::NEG_INFINITY;
+                const MAX: Self = <$t>::INFINITY;
+
+                fn from_usize(n: usize) -> Self {
+                    n as $t
+                }
+            }
+
+            impl Signed for $t {}
+        )*
+    };
+}
+
+integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+float!(f32, f64);
+
+impl Signed for i8 {}
+impl Signed for i16 {}
+impl Signed for i32 {}
+impl Signed for i64 {}
+impl Signed for i128 {}
+impl Signed for isize {}
+
+/// Range sums
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sum<T>(PhantomData<T>);
+
+impl<T: Number> Monoid for Sum<T> {
+    type Value = T;
+
+    fn identity() -> T {
+        T::ZERO
+    }
+
+    fn combine(a: &T, b: &T) -> T {
+        *a + *b
+    }
+}
+
+/// Range minimums
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Min<T>(PhantomData<T>);
+
+impl<T: Number> Monoid for Min<T> {
+    type Value = T;
+
+    fn identity() -> T {
+        T::MAX
+    }
+
+    fn combine(a: &T, b: &T) -> T {
+        if *b < *a {
+            *b
+        } else {
+            *a
+        }
+    }
+}
+
+impl<T: Number> Idempotent for Min<T> {}
+
+/// Range maximums
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Max<T>(PhantomData<T>);
+
+impl<T: Number> Monoid for Max<T> {
+    type Value = T;
+
+    fn identity() -> T {
+        T::MIN
+    }
+
+    fn combine(a: &T, b: &T) -> T {
+        if *b > *a {
+            *b
+        } else {
+            *a
+        }
+    }
+}
+
+impl<T: Number> Idempotent for Max<T> {}
+
+/// Add a value to every value in a range, querying range sums
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AddSum<T>(PhantomData<T>);
+
+impl<T: Number> MapMonoid for AddSum<T> {
+    type Monoid = Sum<T>;
+    type Map = T;
+
+    fn identity_map() -> T {
+        T::ZERO
+    }
+
+    fn apply(map: &T, value: &T, len: usize) -> T {
+        *value + *map * T::from_usize(len)
+    }
+
+    fn compose(outer: &T, inner: &T) -> T {
+        *outer + *inner
+    }
+}
+
+/// Add a value to every value in a range, querying range minimums
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AddMin<T>(PhantomData<T>);
+
+impl<T: Number> MapMonoid for AddMin<T> {
+    type Monoid = Min<T>;
+    type Map = T;
+
+    fn identity_map() -> T {
+        T::ZERO
+    }
+
+    fn apply(map: &T, value: &T, _: usize) -> T {
+        *value + *map
+    }
+
+    fn compose(outer: &T, inner: &T) -> T {
+        *outer + *inner
+    }
+}
+
+/// Add a value to every value in a range, querying range maximums
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AddMax<T>(PhantomData<T>);
+
+impl<T: Number> MapMonoid for AddMax<T> {
+    type Monoid = Max<T>;
+    type Map = T;
+
+    fn identity_map() -> T {
+        T::ZERO
+    }
+
+    fn apply(map: &T, value: &T, _: usize) -> T {
+        *value + *map
+    }
+
+    fn compose(outer: &T, inner: &T) -> T {
+        *outer + *inner
+    }
+}
+
+/// Set every value in a range to one value, querying range sums. The map `None` leaves values unchanged.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AssignSum<T>(PhantomData<T>);
+
+impl<T: Number> MapMonoid for AssignSum<T> {
+    type Monoid = Sum<T>;
+    type Map = Option<T>;
+
+    fn identity_map() -> Option<T> {
+        None
+    }
+
+    fn apply(map: &Option<T>, value: &T, len: usize) -> T {
+        map.map_or(*value, |assigned| assigned * T::from_usize(len))
+    }
+
+    fn compose(outer: &Option<T>, inner: &Option<T>) -> Option<T> {
+        outer.or(*inner)
+    }
+}
+
+/// Set every value in a range to one value, querying range minimums. The map `None` leaves values unchanged.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AssignMin<T>(PhantomData<T>);
+
+impl<T: Number> MapMonoid for AssignMin<T> {
+    type Monoid = Min<T>;
+    type Map = Option<T>;
+
+    fn identity_map() -> Option<T> {
+        None
+    }
+
+    fn apply(map: &Option<T>, value: &T, _: usize) -> T {
+        map.unwrap_or(*value)
+    }
+
+    fn compose(outer: &Option<T>, inner: &Option<T>) -> Option<T> {
+        outer.or(*inner)
+    }
+}
+
+/// Set every value in a range to one value, querying range maximums. The map `None` leaves values unchanged.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AssignMax<T>(PhantomData<T>);
+
+impl<T: Number> MapMonoid for AssignMax<T> {
+    type Monoid = Max<T>;
+    type Map = Option<T>;
+
+    fn identity_map() -> Option<T> {
+        None
+    }
+
+    fn apply(map: &Option<T>, value: &T, _: usize) -> T {
+        map.unwrap_or(*value)
+    }
+
+    fn compose(outer: &Option<T>, inner: &Option<T>) -> Option<T> {
+        outer.or(*inner)
+    }
+}
@@ -0,0 +1,546 @@
+use std::fmt;
+use std::ops::RangeBounds;
+
+use super::{bounds, MapMonoid, Monoid};
+
+/// A segment tree over any `Monoid`, replacing a value and combining every value in a range both in O(log n). The
+/// built in monoids are `Sum`, `Min` and `Max`, any other associative operation can be added by implementing
+/// `Monoid`.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::range::{Monoid, SegmentTree};
+///
+/// // a monoid that is not commutative: the composition of linear functions ax + b
+/// struct Linear;
+///
+/// impl Monoid for Linear {
+///     type Value = (i64, i64);
+///
+///     fn identity() -> (i64, i64) {
+///         (1, 0)
+///     }
+///
+///     // apply a and then b
+///     fn combine(a: &(i64, i64), b: &(i64, i64)) -> (i64, i64) {
+///         (a.0 * b.0, a.1 * b.0 + b.1)
+///     }
+/// }
+///
+/// let mut functions: SegmentTree<Linear> = SegmentTree::new(&[(2, 1), (3, 0), (1, 5)]);
+/// assert_eq!(functions.query(..), (6, 8));
+///
+/// functions.set(1, (1, 1));
+/// assert_eq!(functions.query(0..2), (2, 2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SegmentTree<M>
+where
+    M: Monoid,
+{
+    len: usize,
+    // the number of leaves, a power of two, leaf i is at tree[size + i] and node k has children 2k and 2k + 1
+    size: usize,
+    tree: Vec<M::Value>,
+}
+
+impl<M> SegmentTree<M>
+where
+    M: Monoid,
+{
+    /// This will return a new SegmentTree over the given values, built in O(n)
+    pub fn new(values: &[M::Value]) -> Self {
+        let size = values.len().next_power_of_two();
+        let mut tree = vec![M::identity(); 2 * size];
+        tree[size..size + values.len()].clone_from_slice(values);
+
+        for k in (1..size).rev() {
+            tree[k] = M::combine(&tree[2 * k], &tree[2 * k + 1]);
+        }
+
+        SegmentTree {
+            len: values.len(),
+            size,
+            tree,
+        }
+    }
+
+    /// The number of values in the tree
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return true if the tree holds no values
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the value at the index
+    ///
+    /// # Panics
+    /// If the index is out of bounds
+    pub fn get(&self, index: usize) -> &M::Value {
+        bounds(index..=index, self.len);
+        &self.tree[self.size + index]
+    }
+
+    /// Replace the value at the index
+    ///
+    /// # Panics
+    /// If the index is out of bounds
+    pub fn set(&mut self, index: usize, value: M::Value) {
+        bounds(index..=index, self.len);
+
+        let mut k = self.size + index;
+        self.tree[k] = value;
+        while k > 1 {
+            k /= 2;
+            self.tree[k] = M::combine(&self.tree[2 * k], &self.tree[2 * k + 1]);
+        }
+    }
+
+    /// Return every value in the range combined in order, or the identity for an empty range.
+    ///
+    /// # Panics
+    /// If the range is out of bounds
+    pub fn query<R>(&self, range: R) -> M::Value
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = bounds(range, self.len);
+        let mut left = M::identity();
+        let mut right = M::identity();
+        let mut low = start + self.size;
+        let mut high = end + self.size;
+
+        while low < high {
+            if low & 1 == 1 {
+                left = M::combine(&left, &self.tree[low]);
+                low += 1;
+            }
+            if high & 1 == 1 {
+                high -= 1;
+                right = M::combine(&self.tree[high], &right);
+            }
+            low /= 2;
+            high /= 2;
+        }
+
+        M::combine(&left, &right)
+    }
+
+    /// Return the largest end where the predicate holds for the values from start to end combined. The predicate must
+    /// hold for the identity and, once it fails for some end, fail for every larger end. This finds, for example, how
+    /// far a running sum of non-negative values stays under a limit in O(log n).
+    ///
+    /// # Examples
+    /// ```
+    /// use rs_algo::search::range::{SegmentTree, Sum};
+    ///
+    /// let tree: SegmentTree<Sum<u32>> = SegmentTree::new(&[3, 1, 4, 1, 5, 9]);
+    ///
+    /// assert_eq!(tree.max_right(1, |sum| *sum <= 6), 4);
+    /// assert_eq!(tree.max_right(0, |sum| *sum < 100), 6);
+    /// ```
+    ///
+    /// # Panics
+    /// If start is larger than the length
+    pub fn max_right<F>(&self, start: usize, predicate: F) -> usize
+    where
+        F: Fn(&M::Value) -> bool,
+    {
+        bounds(start.., self.len);
+        if start == self.len {
+            return self.len;
+        }
+
+        let mut k = start + self.size;
+        let mut sum = M::identity();
+        loop {
+            // climb while k is a right child, its parent would reach back before start
+            while k & 1 == 0 {
+                k /= 2;
+            }
+
+            let next = M::combine(&sum, &self.tree[k]);
+            if !predicate(&next) {
+                // the answer is inside node k, walk down to the first leaf that fails
+                while k < self.size {
+                    k *= 2;
+                    let next = M::combine(&sum, &self.tree[k]);
+                    if predicate(&next) {
+                        sum = next;
+                        k += 1;
+                    }
+                }
+                return (k - self.size).min(self.len);
+            }
+
+            sum = next;
+            k += 1;
+            if k.is_power_of_two() {
+                // node k was the last one on its level
+                return self.len;
+            }
+        }
+    }
+}
+
+/// A segment tree with lazy propagation, applying a map to every value in a range and combining every value in a
+/// range both in O(log n). The maps are given by a `MapMonoid`, the built in ones add to or assign every value in the
+/// range while querying sums, minimums or maximums.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::range::{AddMin, AssignSum, LazySegmentTree};
+///
+/// let mut lowest: LazySegmentTree<AddMin<i64>> = LazySegmentTree::new(&[5, 3, 8, 6, 2]);
+/// lowest.apply(1..4, 10);
+/// assert_eq!(lowest.query(..4), 5);
+/// assert_eq!(lowest.query(..), 2);
+///
+/// let mut totals: LazySegmentTree<AssignSum<i64>> = LazySegmentTree::new(&[1, 2, 3, 4]);
+/// totals.apply(1..3, Some(10));
+/// assert_eq!(totals.query(..), 25);
+/// assert_eq!(totals.get(2), 10);
+/// ```
+pub struct LazySegmentTree<A>
+where
+    A: MapMonoid,
+{
+    len: usize,
+    size: usize,
+    // the depth of the leaves
+    log: u32,
+    tree: Vec<<A::Monoid as Monoid>::Value>,
+    // maps waiting to be pushed down to the children of each node
+    lazy: Vec<A::Map>,
+}
+
+// derive can not see through the nested associated types, so these are written out
+impl<A> Clone for LazySegmentTree<A>
+where
+    A: MapMonoid,
+{
+    fn clone(&self) -> Self {
+        LazySegmentTree {
+            len: self.len,
+            size: self.size,
+            log: self.log,
+            tree: self.tree.clone(),
+            lazy: self.lazy.clone(),
+        }
+    }
+}
+
+impl<A> fmt::Debug for LazySegmentTree<A>
+where
+    A: MapMonoid,
+    <A::Monoid as Monoid>::Value: fmt::Debug,
+    A::Map: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LazySegmentTree")
+            .field("len", &self.len)
+            .field("tree", &self.tree)
+            .field("lazy", &self.lazy)
+            .finish()
+    }
+}
+
+impl<A> LazySegmentTree<A>
+where
+    A: MapMonoid,
+{
+    /// This will return a new LazySegmentTree over the given values, built in O(n)
+    pub fn new(values: &[<A::Monoid as Monoid>::Value]) -> Self {
+        let inner = SegmentTree::<A::Monoid>::new(values);
+
+        LazySegmentTree {
+            len: inner.len,
+            size: inner.size,
+            log: inner.size.trailing_zeros(),
+            lazy: vec![A::identity_map(); inner.size],
+            tree: inner.tree,
+        }
+    }
+
+    /// The number of values in the tree
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return true if the tree holds no values
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // the number of leaves below node k
+    fn width(&self, k: usize) -> usize {
+        self.size >> (usize::BITS - 1 - k.leading_zeros())
+    }
+
+    fn update(&mut self, k: usize) {
+        self.tree[k] = A::Monoid::combine(&self.tree[2 * k], &self.tree[2 * k + 1]);
+    }
+
+    fn apply_node(&mut self, k: usize, map: &A::Map) {
+        self.tree[k] = A::apply(map, &self.tree[k], self.width(k));
+        if k < self.size {
+            self.lazy[k] = A::compose(map, &self.lazy[k]);
+        }
+    }
+
+    fn push(&mut self, k: usize) {
+        let map = std::mem::replace(&mut self.lazy[k], A::identity_map());
+        self.apply_node(2 * k, &map);
+        self.apply_node(2 * k + 1, &map);
+    }
+
+    // push every pending map on the paths to the edges of the range down, so the nodes inside it are up to date
+    fn push_edges(&mut self, low: usize, high: usize) {
+        for i in (1..=self.log).rev() {
+            if (low >> i) << i != low {
+                self.push(low >> i);
+            }
+            if (high >> i) << i != high {
+                self.push((high - 1) >> i);
+            }
+        }
+    }
+
+    /// Return the value at the index
+    ///
+    /// # Panics
+    /// If the index is out of bounds
+    pub fn get(&mut self, index: usize) -> <A::Monoid as Monoid>::Value {
+        bounds(index..=index, self.len);
+
+        let k = self.size + index;
+        for i in (1..=self.log).rev() {
+            self.push(k >> i);
+        }
+        self.tree[k].clone()
+    }
+
+    /// Replace the value at the index
+    ///
+    /// # Panics
+    /// If the index is out of bounds
+    pub fn set(&mut self, index: usize, value: <A::Monoid as Monoid>::Value) {
+        bounds(index..=index, self.len);
+
+        let k = self.size + index;
+        for i in (1..=self.log).rev() {
+            self.push(k >> i);
+        }
+        self.tree[k] = value;
+        for i in 1..=self.log {
+            self.update(k >> i);
+        }
+    }
+
+    /// Return every value in the range combined in order, or the identity for an empty range. Pending maps on the
+    /// edges of the range are pushed down, so this needs mutable access.
+    ///
+    /// # Panics
+    /// If the range is out of bounds
+    pub fn query<R>(&mut self, range: R) -> <A::Monoid as Monoid>::Value
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = bounds(range, self.len);
+        if start == end {
+            return A::Monoid::identity();
+        }
+
+        let mut low = start + self.size;
+        let mut high = end + self.size;
+        self.push_edges(low, high);
+
+        let mut left = A::Monoid::identity();
+        let mut right = A::Monoid::identity();
+        while low < high {
+            if low & 1 == 1 {
+                left = A::Monoid::combine(&left, &self.tree[low]);
+                low += 1;
+            }
+            if high & 1 == 1 {
+                high -= 1;
+                right = A::Monoid::combine(&self.tree[high], &right);
+            }
+            low /= 2;
+            high /= 2;
+        }
+
+        A::Monoid::combine(&left, &right)
+    }
+
+    /// Apply the map to every value in the range
+    ///
+    /// # Panics
+    /// If the range is out of bounds
+    pub fn apply<R>(&mut self, range: R, map: A::Map)
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = bounds(range, self.len);
+        if start == end {
+            return;
+        }
+
+        let (low, high) = (start + self.size, end + self.size);
+        self.push_edges(low, high);
+
+        let (mut l, mut r) = (low, high);
+        while l < r {
+            if l & 1 == 1 {
+                self.apply_node(l, &map);
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                self.apply_node(r, &map);
+            }
+            l /= 2;
+            r /= 2;
+        }
+
+        for i in 1..=self.log {
+            if (low >> i) << i != low {
+                self.update(low >> i);
+            }
+            if (high >> i) << i != high {
+                self.update((high - 1) >> i);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::Random;
+
+    #[test]
+    fn segment_tree_matches_naive() {
+        use super::super::{Max, Min, Sum};
+        use super::*;
+
+        let mut random = Random::new(7);
+        for len in [0, 1, 2, 5, 8, 13, 32].iter() {
+            let mut values: Vec<i64> = (0..*len).map(|_| random.below(100) as i64 - 50).collect();
+            let mut sums: SegmentTree<Sum<i64>> = SegmentTree::new(&values);
+            let mut mins: SegmentTree<Min<i64>> = SegmentTree::new(&values);
+            let mut maxes: SegmentTree<Max<i64>> = SegmentTree::new(&values);
+
+            for _ in 0..200 {
+                if *len > 0 {
+                    let i = random.below(*len);
+                    values[i] = random.below(100) as i64 - 50;
+                    sums.set(i, values[i]);
+                    mins.set(i, values[i]);
+                    maxes.set(i, values[i]);
+                }
+
+                let (start, end) = random.range(*len);
+                let slice = &values[start..end];
+                assert_eq!(sums.query(start..end), slice.iter().sum::<i64>());
+                assert_eq!(
+                    mins.query(start..end),
+                    *slice.iter().min().unwrap_or(&i64::MAX)
+                );
+                assert_eq!(
+                    maxes.query(start..end),
+                    *slice.iter().max().unwrap_or(&i64::MIN)
+                );
+
+                let limit = random.below(150) as i64;
+                let expected = (start..=*len)
+                    .rev()
+                    .find(|end| {
+                        values[start..*end]
+                            .iter()
+                            .max()
+                            .map_or(true, |max| *max <= limit)
+                    })
+                    .unwrap();
+                assert_eq!(maxes.max_right(start, |max| *max <= limit), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn lazy_segment_tree_matches_naive() {
+        use super::super::{AddMax, AddMin, AddSum, AssignMax, AssignMin, AssignSum};
+        use super::*;
+
+        let mut random = Random::new(99);
+        for len in [0, 1, 3, 8, 11, 32].iter() {
+            let mut values: Vec<i64> = (0..*len).map(|_| random.below(100) as i64 - 50).collect();
+            let mut add_sum: LazySegmentTree<AddSum<i64>> = LazySegmentTree::new(&values);
+            let mut add_min: LazySegmentTree<AddMin<i64>> = LazySegmentTree::new(&values);
+            let mut add_max: LazySegmentTree<AddMax<i64>> = LazySegmentTree::new(&values);
+            let mut assign_sum: LazySegmentTree<AssignSum<i64>> = LazySegmentTree::new(&values);
+            let mut assign_min: LazySegmentTree<AssignMin<i64>> = LazySegmentTree::new(&values);
+            let mut assign_max: LazySegmentTree<AssignMax<i64>> = LazySegmentTree::new(&values);
+
+            // the add and assign trees see different updates, so each keeps its own copy of the values
+            let mut assigned = values.clone();
+
+            for _ in 0..300 {
+                let (start, end) = random.range(*len);
+                let value = random.below(20) as i64 - 10;
+                match random.below(3) {
+                    0 => {
+                        for v in values[start..end].iter_mut() {
+                            *v += value;
+                        }
+                        add_sum.apply(start..end, value);
+                        add_min.apply(start..end, value);
+                        add_max.apply(start..end, value);
+
+                        for v in assigned[start..end].iter_mut() {
+                            *v = value;
+                        }
+                        assign_sum.apply(start..end, Some(value));
+                        assign_min.apply(start..end, Some(value));
+                        assign_max.apply(start..end, Some(value));
+                    }
+                    1 if *len > 0 => {
+                        let i = random.below(*len);
+                        values[i] = value;
+                        add_sum.set(i, value);
+                        add_min.set(i, value);
+                        add_max.set(i, value);
+                        assert_eq!(assign_sum.get(i), assigned[i]);
+                        assert_eq!(add_max.get(i), value);
+                    }
+                    _ => {}
+                }
+
+                let (start, end) = random.range(*len);
+                let slice = &values[start..end];
+                assert_eq!(add_sum.query(start..end), slice.iter().sum::<i64>());
+                assert_eq!(
+                    add_min.query(start..end),
+                    *slice.iter().min().unwrap_or(&i64::MAX)
+                );
+                assert_eq!(
+                    add_max.query(start..end),
+                    *slice.iter().max().unwrap_or(&i64::MIN)
+                );
+
+                let slice = &assigned[start..end];
+                assert_eq!(assign_sum.query(start..end), slice.iter().sum::<i64>());
+                assert_eq!(
+                    assign_min.query(start..end),
+                    *slice.iter().min().unwrap_or(&i64::MAX)
+                );
+                assert_eq!(
+                    assign_max.query(start..end),
+                    *slice.iter().max().unwrap_or(&i64::MIN)
+                );
+            }
+        }
+    }
+}
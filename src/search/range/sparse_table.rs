@@ -0,0 +1,107 @@
+use std::ops::RangeBounds;
+
+use super::{bounds, Idempotent};
+
+/// A sparse table over an `Idempotent` monoid such as `Min` or `Max`, answering range queries in O(1) after an
+/// O(n log n) build. The values can not be changed once the table is built, use a `SegmentTree` for that.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::range::{Max, SparseTable};
+///
+/// let temperatures = [12.5, 14.0, 9.5, 17.25, 11.0];
+/// let table: SparseTable<Max<f64>> = SparseTable::new(&temperatures);
+///
+/// assert_eq!(table.query(..3), 14.0);
+/// assert_eq!(table.query(2..=3), 17.25);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SparseTable<M>
+where
+    M: Idempotent,
+{
+    // levels[j][i] holds the 2^j values starting at i combined
+    levels: Vec<Vec<M::Value>>,
+}
+
+impl<M> SparseTable<M>
+where
+    M: Idempotent,
+{
+    /// This will return a new SparseTable over the given values
+    pub fn new(values: &[M::Value]) -> Self {
+        let mut levels = vec![values.to_vec()];
+
+        let mut width = 1;
+        while 2 * width <= values.len() {
+            let previous = &levels[levels.len() - 1];
+            let level = (0..=values.len() - 2 * width)
+                .map(|i| M::combine(&previous[i], &previous[i + width]))
+                .collect();
+
+            levels.push(level);
+            width *= 2;
+        }
+
+        SparseTable { levels }
+    }
+
+    /// The number of values in the table
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Return true if the table holds no values
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return every value in the range combined, or the identity for an empty range. Two blocks that together cover
+    /// the range are combined, they may overlap as the monoid is idempotent.
+    ///
+    /// # Panics
+    /// If the range is out of bounds
+    pub fn query<R>(&self, range: R) -> M::Value
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = bounds(range, self.len());
+        if start == end {
+            return M::identity();
+        }
+
+        let level = (usize::BITS - 1 - (end - start).leading_zeros()) as usize;
+        let values = &self.levels[level];
+        M::combine(&values[start], &values[end - (1 << level)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::Random;
+
+    #[test]
+    fn sparse_table_matches_naive() {
+        use super::super::{Max, Min};
+        use super::*;
+
+        let mut random = Random::new(3);
+        for len in [0, 1, 2, 3, 4, 9, 16, 31].iter() {
+            let values: Vec<u16> = (0..*len).map(|_| random.below(1000) as u16).collect();
+            let mins: SparseTable<Min<u16>> = SparseTable::new(&values);
+            let maxes: SparseTable<Max<u16>> = SparseTable::new(&values);
+            assert_eq!(mins.len(), *len);
+
+            for start in 0..=*len {
+                for end in start..=*len {
+                    let slice = &values[start..end];
+                    assert_eq!(
+                        mins.query(start..end),
+                        *slice.iter().min().unwrap_or(&u16::MAX)
+                    );
+                    assert_eq!(maxes.query(start..end), *slice.iter().max().unwrap_or(&0));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,139 @@
+/// Find the rotation point of a sorted array that has been rotated, this is the index of the smallest value.
+/// An array that was never rotated has a rotation point of 0, as does an empty array.
+///
+/// Repeated values can force this to check every value, but a rotated array without repeats is searched in
+/// O(log n).
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::rotated;
+///
+/// let ring = vec![23, 24, 56, 67, 1, 2, 3, 5, 7];
+/// assert_eq!(rotated::rotation_point(&ring), 4);
+/// assert_eq!(rotated::rotation_point(&[1, 2, 3]), 0);
+/// ```
+pub fn rotation_point<T>(array: &[T]) -> usize
+where
+    T: PartialOrd,
+{
+    if array.is_empty() {
+        return 0;
+    }
+
+    let mut low = 0;
+    let mut high = array.len() - 1;
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+
+        if array[mid] > array[high] {
+            low = mid + 1;
+        } else if array[mid] < array[high] {
+            high = mid;
+        } else {
+            // with repeats we can't tell which side the rotation is on, shrink the range by one
+            if array[high - 1] > array[high] {
+                return high;
+            }
+            high -= 1;
+        }
+    }
+
+    low
+}
+
+/// Binary search on a sorted array that has been rotated. The rotation point is found first, the array is then
+/// searched as if it was never rotated.
+///
+/// The target is the value you want to search for in array. If the target is found the index of that target will
+/// be returned, or None if the target was not found. With repeated values this will be the index of the target that
+/// comes first in sorted order.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::rotated;
+///
+/// let ring = vec![23, 24, 56, 67, 1, 2, 3, 5, 7];
+/// assert_eq!(rotated::index_of(56, &ring), Some(2));
+/// assert_eq!(rotated::index_of(3, &ring), Some(6));
+/// assert_eq!(rotated::index_of(4, &ring), None);
+/// ```
+pub fn index_of<T>(target: T, array: &[T]) -> Option<usize>
+where
+    T: PartialOrd,
+{
+    let len = array.len();
+    let offset = rotation_point(array);
+
+    // binary search over the un-rotated order, i maps to array[(offset + i) % len]
+    let mut low = 0;
+    let mut high = len;
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+
+        if array[(offset + mid) % len] < target {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+
+    if low < len && array[(offset + low) % len] == target {
+        return Some((offset + low) % len);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn rotated_rotation_point() {
+        use super::*;
+
+        let sorted = vec![2, 3, 5, 6, 8, 9, 23, 54, 77, 78, 89, 90, 104];
+        for shift in 0..sorted.len() {
+            let mut ring = sorted.clone();
+            ring.rotate_left(shift);
+
+            assert_eq!(rotation_point(&ring), (sorted.len() - shift) % sorted.len());
+        }
+
+        assert_eq!(rotation_point::<i32>(&[]), 0);
+        assert_eq!(rotation_point(&[7]), 0);
+    }
+
+    #[test]
+    fn rotated_repeats() {
+        use super::*;
+
+        assert_eq!(rotation_point(&[2, 2, 2, 0, 1, 2]), 3);
+        assert_eq!(rotation_point(&[1, 0, 1, 1, 1]), 1);
+        assert_eq!(rotation_point(&[1, 1, 1, 0, 1]), 3);
+        assert_eq!(rotation_point(&[4, 4, 4, 4]), 0);
+
+        assert_eq!(index_of(0, &[1, 1, 1, 0, 1]), Some(3));
+        assert_eq!(index_of(1, &[1, 1, 1, 0, 1]), Some(4));
+        assert_eq!(index_of(2, &[1, 1, 1, 0, 1]), None);
+    }
+
+    #[test]
+    fn rotated_index() {
+        use super::*;
+
+        let sorted = vec!["algorithm", "cake", "denver", "rust", "zebra"];
+        for shift in 0..sorted.len() {
+            let mut ring = sorted.clone();
+            ring.rotate_right(shift);
+
+            for word in sorted.iter() {
+                let index = index_of(*word, &ring).unwrap();
+                assert_eq!(ring[index], *word);
+            }
+            assert_eq!(index_of("c++", &ring), None);
+        }
+
+        assert_eq!(index_of(1, &[]), None);
+    }
+}
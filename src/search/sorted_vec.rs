@@ -0,0 +1,665 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::iter::{FromIterator, Peekable};
+use std::ops::{Bound, RangeBounds};
+
+use super::binary;
+use crate::sort::merge;
+
+/// A set kept as a sorted array. Lookups are binary searches over one contiguous block of memory, so for sets that
+/// are read far more often than they are changed it is smaller and faster to search than a `BTreeSet`. A single
+/// insert or remove moves every value after it, batches of values are merged in with `insert_batch` instead.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::SortedVec;
+///
+/// let mut primes: SortedVec<u32> = vec![7, 2, 13, 5, 3, 11].into();
+/// primes.insert(17);
+/// primes.insert_batch(vec![23, 19, 2]);
+///
+/// assert_eq!(primes.as_slice(), &[2, 3, 5, 7, 11, 13, 17, 19, 23]);
+/// assert_eq!(primes.range(6..14), &[7, 11, 13]);
+/// assert_eq!(primes.rank(&12), 5);
+/// assert_eq!(primes.select(5), Some(&13));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SortedVec<T> {
+    values: Vec<T>,
+}
+
+impl<T> Default for SortedVec<T> {
+    fn default() -> Self {
+        SortedVec { values: Vec::new() }
+    }
+}
+
+impl<T> SortedVec<T>
+where
+    T: Ord,
+{
+    /// This will return a new empty SortedVec
+    pub fn new() -> Self {
+        SortedVec::default()
+    }
+
+    /// This will return a new empty SortedVec with room for capacity values
+    pub fn with_capacity(capacity: usize) -> Self {
+        SortedVec {
+            values: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// This will return a new SortedVec holding the values, sorted with `merge::sort_by_mut`. Of equal values only
+    /// the first is kept.
+    pub fn from_vec(values: Vec<T>) -> Self {
+        let mut values = values;
+        merge::sort_by_mut(&mut values, T::cmp);
+        values.dedup_by(|a, b| a == b);
+
+        SortedVec { values }
+    }
+
+    /// The number of values in the set
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Return true if the set holds no values
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The values in sorted order
+    pub fn as_slice(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Return the sorted values
+    pub fn into_vec(self) -> Vec<T> {
+        self.values
+    }
+
+    /// Iterate over the values in sorted order
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.values.iter()
+    }
+
+    /// Remove every value
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    // Ok with the index of the value, or Err with where it would be inserted
+    fn position<Q>(&self, value: &Q) -> Result<usize, usize>
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        let index = binary::partition_point(&self.values, |x| x.borrow() < value);
+        match self.values.get(index) {
+            Some(x) if x.borrow() == value => Ok(index),
+            _ => Err(index),
+        }
+    }
+
+    /// Add a value, returning false and leaving the set unchanged if an equal value is already in it.
+    pub fn insert(&mut self, value: T) -> bool {
+        match self.position(&value) {
+            Ok(_) => false,
+            Err(index) => {
+                self.values.insert(index, value);
+                true
+            }
+        }
+    }
+
+    /// Add every value from the iterator. The new values are sorted on their own and merged with the set in one pass,
+    /// so a large batch costs O(n + k log k) instead of a move of the whole set per value. Values already in the set
+    /// are kept over equal new ones.
+    pub fn insert_batch<I>(&mut self, values: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let batch = SortedVec::from_vec(values.into_iter().collect());
+        if batch.is_empty() {
+            return;
+        }
+
+        let old = std::mem::take(&mut self.values);
+        self.values = merge_sorted(old, batch.values, |a, b| a.cmp(b), |old, _| old);
+    }
+
+    /// Remove the value equal to the given one, returning it. None is returned if there is no such value.
+    pub fn remove<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        self.position(value)
+            .ok()
+            .map(|index| self.values.remove(index))
+    }
+
+    /// Return true if a value equal to the given one is in the set
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        self.position(value).is_ok()
+    }
+
+    /// Return the value in the set equal to the given one, or None if there is no such value.
+    pub fn get<Q>(&self, value: &Q) -> Option<&T>
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        self.position(value).ok().map(|index| &self.values[index])
+    }
+
+    /// Return the number of values in the set that are less than the given one
+    pub fn rank<Q>(&self, value: &Q) -> usize
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+    {
+        binary::partition_point(&self.values, |x| x.borrow() < value)
+    }
+
+    /// Return the k-th smallest value, counting from 0, or None if the set holds k values or fewer.
+    pub fn select(&self, k: usize) -> Option<&T> {
+        self.values.get(k)
+    }
+
+    /// The smallest value, or None if the set is empty
+    pub fn first(&self) -> Option<&T> {
+        self.values.first()
+    }
+
+    /// The largest value, or None if the set is empty
+    pub fn last(&self) -> Option<&T> {
+        self.values.last()
+    }
+
+    /// Return the values within the range, in sorted order
+    pub fn range<Q, R>(&self, range: R) -> &[T]
+    where
+        Q: Ord + ?Sized,
+        T: Borrow<Q>,
+        R: RangeBounds<Q>,
+    {
+        let (start, end) = range_indices(&self.values, range, |x| x.borrow());
+        &self.values[start..end]
+    }
+}
+
+impl<T> From<Vec<T>> for SortedVec<T>
+where
+    T: Ord,
+{
+    fn from(values: Vec<T>) -> Self {
+        SortedVec::from_vec(values)
+    }
+}
+
+impl<T> FromIterator<T> for SortedVec<T>
+where
+    T: Ord,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(values: I) -> Self {
+        SortedVec::from_vec(values.into_iter().collect())
+    }
+}
+
+impl<T> Extend<T> for SortedVec<T>
+where
+    T: Ord,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        self.insert_batch(values);
+    }
+}
+
+impl<T> IntoIterator for SortedVec<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SortedVec<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.iter()
+    }
+}
+
+/// A map kept as an array of key value pairs sorted by key, the map version of `SortedVec`.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::SortedMap;
+///
+/// let mut stock: SortedMap<&str, u32> = vec![("pear", 4), ("apple", 10), ("fig", 0)].into_iter().collect();
+/// stock.insert("kiwi", 7);
+/// *stock.get_mut("fig").unwrap() += 12;
+///
+/// assert_eq!(stock.get("fig"), Some(&12));
+/// assert_eq!(stock.select(1), Some((&"fig", &12)));
+///
+/// let keys: Vec<&str> = stock.range("b".."l").iter().map(|(key, _)| *key).collect();
+/// assert_eq!(keys, vec!["fig", "kiwi"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SortedMap<K, V> {
+    entries: Vec<(K, V)>,
+}
+
+impl<K, V> Default for SortedMap<K, V> {
+    fn default() -> Self {
+        SortedMap {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<K, V> SortedMap<K, V>
+where
+    K: Ord,
+{
+    /// This will return a new empty SortedMap
+    pub fn new() -> Self {
+        SortedMap::default()
+    }
+
+    /// This will return a new empty SortedMap with room for capacity entries
+    pub fn with_capacity(capacity: usize) -> Self {
+        SortedMap {
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// This will return a new SortedMap holding the entries, sorted by key with `merge::sort_by_mut`. Of entries with
+    /// equal keys only the last is kept, as if they were inserted in order.
+    pub fn from_vec(entries: Vec<(K, V)>) -> Self {
+        let mut entries = entries;
+        merge::sort_by_mut(&mut entries, |a, b| a.0.cmp(&b.0));
+
+        // the sort is stable, so the last of a run of equal keys is the one inserted last
+        let mut deduped: Vec<(K, V)> = Vec::with_capacity(entries.len());
+        for entry in entries {
+            match deduped.last_mut() {
+                Some(last) if last.0 == entry.0 => *last = entry,
+                _ => deduped.push(entry),
+            }
+        }
+
+        SortedMap { entries: deduped }
+    }
+
+    /// The number of entries in the map
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return true if the map holds no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The entries in key order
+    pub fn as_slice(&self) -> &[(K, V)] {
+        &self.entries
+    }
+
+    /// Return the entries in key order
+    pub fn into_vec(self) -> Vec<(K, V)> {
+        self.entries
+    }
+
+    /// Iterate over the entries in key order
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries.iter().map(|(key, value)| (key, value))
+    }
+
+    /// Iterate over the keys in order
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.entries.iter().map(|(key, _)| key)
+    }
+
+    /// Iterate over the values in key order
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.iter().map(|(_, value)| value)
+    }
+
+    /// Remove every entry
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    // Ok with the index of the key, or Err with where it would be inserted
+    fn position<Q>(&self, key: &Q) -> Result<usize, usize>
+    where
+        Q: Ord + ?Sized,
+        K: Borrow<Q>,
+    {
+        let index = binary::partition_point(&self.entries, |(k, _)| k.borrow() < key);
+        match self.entries.get(index) {
+            Some((k, _)) if k.borrow() == key => Ok(index),
+            _ => Err(index),
+        }
+    }
+
+    /// Insert a key and value. If the key was already in the map its old value is returned.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.position(&key) {
+            Ok(index) => Some(std::mem::replace(&mut self.entries[index].1, value)),
+            Err(index) => {
+                self.entries.insert(index, (key, value));
+                None
+            }
+        }
+    }
+
+    /// Insert every entry from the iterator, merging them with the map in one pass like `SortedVec::insert_batch`.
+    /// New values replace the values of keys already in the map, and of equal new keys the last one wins.
+    pub fn insert_batch<I>(&mut self, entries: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let batch = SortedMap::from_vec(entries.into_iter().collect());
+        if batch.is_empty() {
+            return;
+        }
+
+        let old = std::mem::take(&mut self.entries);
+        self.entries = merge_sorted(old, batch.entries, |a, b| a.0.cmp(&b.0), |_, new| new);
+    }
+
+    /// Remove a key, returning its value. None is returned if the key was not in the map.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        Q: Ord + ?Sized,
+        K: Borrow<Q>,
+    {
+        self.position(key)
+            .ok()
+            .map(|index| self.entries.remove(index).1)
+    }
+
+    /// Return true if the key is in the map
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        Q: Ord + ?Sized,
+        K: Borrow<Q>,
+    {
+        self.position(key).is_ok()
+    }
+
+    /// Return the value of the key, or None if the key is not in the map.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        Q: Ord + ?Sized,
+        K: Borrow<Q>,
+    {
+        self.position(key).ok().map(|index| &self.entries[index].1)
+    }
+
+    /// Return the value of the key to change it, or None if the key is not in the map.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        Q: Ord + ?Sized,
+        K: Borrow<Q>,
+    {
+        match self.position(key) {
+            Ok(index) => Some(&mut self.entries[index].1),
+            Err(_) => None,
+        }
+    }
+
+    /// Return the number of keys in the map that are less than the given one
+    pub fn rank<Q>(&self, key: &Q) -> usize
+    where
+        Q: Ord + ?Sized,
+        K: Borrow<Q>,
+    {
+        binary::partition_point(&self.entries, |(k, _)| k.borrow() < key)
+    }
+
+    /// Return the entry with the k-th smallest key, counting from 0, or None if the map holds k entries or fewer.
+    pub fn select(&self, k: usize) -> Option<(&K, &V)> {
+        self.entries.get(k).map(|(key, value)| (key, value))
+    }
+
+    /// The entry with the smallest key, or None if the map is empty
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.select(0)
+    }
+
+    /// The entry with the largest key, or None if the map is empty
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.entries.last().map(|(key, value)| (key, value))
+    }
+
+    /// Return the entries with keys within the range, in key order
+    pub fn range<Q, R>(&self, range: R) -> &[(K, V)]
+    where
+        Q: Ord + ?Sized,
+        K: Borrow<Q>,
+        R: RangeBounds<Q>,
+    {
+        let (start, end) = range_indices(&self.entries, range, |(key, _)| key.borrow());
+        &self.entries[start..end]
+    }
+}
+
+impl<K, V> From<Vec<(K, V)>> for SortedMap<K, V>
+where
+    K: Ord,
+{
+    fn from(entries: Vec<(K, V)>) -> Self {
+        SortedMap::from_vec(entries)
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for SortedMap<K, V>
+where
+    K: Ord,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(entries: I) -> Self {
+        SortedMap::from_vec(entries.into_iter().collect())
+    }
+}
+
+impl<K, V> Extend<(K, V)> for SortedMap<K, V>
+where
+    K: Ord,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, entries: I) {
+        self.insert_batch(entries);
+    }
+}
+
+impl<K, V> IntoIterator for SortedMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = std::vec::IntoIter<(K, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+// the start and end indices of the sorted values whose keys fall within the range
+fn range_indices<T, Q, R, F>(values: &[T], range: R, key: F) -> (usize, usize)
+where
+    Q: Ord + ?Sized,
+    R: RangeBounds<Q>,
+    F: Fn(&T) -> &Q,
+{
+    let start = match range.start_bound() {
+        Bound::Included(start) => binary::partition_point(values, |x| key(x) < start),
+        Bound::Excluded(start) => binary::partition_point(values, |x| key(x) <= start),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => binary::partition_point(values, |x| key(x) <= end),
+        Bound::Excluded(end) => binary::partition_point(values, |x| key(x) < end),
+        Bound::Unbounded => values.len(),
+    };
+
+    // a range that ends before it starts is empty
+    (start, end.max(start))
+}
+
+// merge two sorted runs without duplicates into one, resolving values found in both with pick
+fn merge_sorted<T, C, P>(old: Vec<T>, new: Vec<T>, compare: C, pick: P) -> Vec<T>
+where
+    C: Fn(&T, &T) -> Ordering,
+    P: Fn(T, T) -> T,
+{
+    let mut merged = Vec::with_capacity(old.len() + new.len());
+    let mut old: Peekable<_> = old.into_iter().peekable();
+    let mut new: Peekable<_> = new.into_iter().peekable();
+
+    while let (Some(a), Some(b)) = (old.peek(), new.peek()) {
+        match compare(a, b) {
+            Ordering::Less => merged.extend(old.next()),
+            Ordering::Greater => merged.extend(new.next()),
+            Ordering::Equal => {
+                let (a, b) = (old.next().unwrap(), new.next().unwrap());
+                merged.push(pick(a, b));
+            }
+        }
+    }
+
+    merged.extend(old);
+    merged.extend(new);
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::random;
+    use std::collections::{BTreeMap, BTreeSet};
+
+    #[test]
+    fn sorted_vec_matches_btree_set() {
+        use super::*;
+
+        let mut state = 5;
+        let mut set = SortedVec::new();
+        let mut expected = BTreeSet::new();
+
+        for round in 0..400 {
+            let value = random(&mut state, 100);
+            match round % 4 {
+                0 | 1 => assert_eq!(set.insert(value), expected.insert(value)),
+                2 => assert_eq!(set.remove(&value), expected.take(&value)),
+                _ => {
+                    let batch: Vec<u64> = (0..5).map(|_| random(&mut state, 100)).collect();
+                    expected.extend(batch.iter().copied());
+                    set.extend(batch);
+                }
+            }
+
+            assert_eq!(
+                set.iter().copied().collect::<Vec<u64>>(),
+                expected.iter().copied().collect::<Vec<u64>>()
+            );
+            assert_eq!(set.contains(&value), expected.contains(&value));
+            assert_eq!(set.rank(&value), expected.range(..value).count());
+
+            let low = random(&mut state, 100);
+            let high = random(&mut state, 100);
+            let in_range: Vec<u64> = expected.range(low..=high.max(low)).copied().collect();
+            assert_eq!(set.range(low..=high.max(low)), in_range.as_slice());
+            assert_eq!(
+                set.range(high..low).len(),
+                if high < low {
+                    expected.range(high..low).count()
+                } else {
+                    0
+                }
+            );
+        }
+
+        assert_eq!(set.select(0), expected.iter().next());
+        assert_eq!(set.first(), expected.iter().next());
+        assert_eq!(set.last(), expected.iter().next_back());
+        assert_eq!(set.select(set.len()), None);
+    }
+
+    #[test]
+    fn sorted_vec_borrowed_lookups() {
+        use super::*;
+
+        let words: SortedVec<String> = ["pear", "fig", "apple", "fig"]
+            .iter()
+            .map(|word| word.to_string())
+            .collect();
+
+        assert_eq!(words.len(), 3);
+        assert!(words.contains("fig"));
+        assert_eq!(words.get("apple"), Some(&"apple".to_string()));
+        assert_eq!(
+            words.range::<str, _>((Bound::Excluded("b"), Bound::Unbounded)),
+            &["fig".to_string(), "pear".to_string()]
+        );
+        assert_eq!(words.into_vec(), vec!["apple", "fig", "pear"]);
+    }
+
+    #[test]
+    fn sorted_map_matches_btree_map() {
+        use super::*;
+
+        let mut state = 9;
+        let mut map = SortedMap::new();
+        let mut expected = BTreeMap::new();
+
+        for round in 0..400 {
+            let key = random(&mut state, 60);
+            let value = random(&mut state, 1000);
+            match round % 4 {
+                0 | 1 => assert_eq!(map.insert(key, value), expected.insert(key, value)),
+                2 => assert_eq!(map.remove(&key), expected.remove(&key)),
+                _ => {
+                    let batch: Vec<(u64, u64)> = (0..6)
+                        .map(|_| (random(&mut state, 60), random(&mut state, 1000)))
+                        .collect();
+                    expected.extend(batch.iter().copied());
+                    map.insert_batch(batch);
+                }
+            }
+
+            let entries: Vec<(u64, u64)> = map.iter().map(|(k, v)| (*k, *v)).collect();
+            assert_eq!(
+                entries,
+                expected
+                    .iter()
+                    .map(|(k, v)| (*k, *v))
+                    .collect::<Vec<(u64, u64)>>()
+            );
+            assert_eq!(map.get(&key), expected.get(&key));
+            assert_eq!(map.rank(&key), expected.range(..key).count());
+            assert_eq!(map.range(..key).len(), expected.range(..key).count());
+        }
+
+        let first = map.keys().next().copied().unwrap();
+        *map.get_mut(&first).unwrap() = 7;
+        assert_eq!(map.first(), Some((&first, &7)));
+        assert_eq!(
+            map.last().map(|(k, _)| *k),
+            expected.keys().next_back().copied()
+        );
+        assert_eq!(map.values().count(), expected.len());
+
+        let built: SortedMap<u8, char> = vec![(2, 'a'), (1, 'b'), (2, 'c')].into();
+        assert_eq!(built.into_vec(), vec![(1, 'b'), (2, 'c')]);
+    }
+}
@@ -0,0 +1,279 @@
+use std::cmp::Ordering;
+
+/// The memory layout a StaticIndex rebuilds the sorted array into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// A binary tree stored in breadth first order, the children of node k are found at 2k and 2k + 1
+    Eytzinger,
+    /// A static B-tree (S-tree) of nodes holding 16 keys each, so one node is compared per cache line or two
+    BTree,
+}
+
+// keys per node for the B-tree layout
+const BLOCK: usize = 16;
+
+// marks a padding slot in the B-tree layout
+const PADDING: usize = usize::MAX;
+
+#[inline(always)]
+fn prefetch<T>(keys: &[T], index: usize) {
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        use std::arch::x86_64::{_mm_prefetch, _MM_HINT_T0};
+
+        // prefetching never faults, the address does not need to be within the slice
+        _mm_prefetch::<_MM_HINT_T0>(keys.as_ptr().wrapping_add(index) as *const i8);
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    let _ = (keys, index);
+}
+
+/// A read only search index over a sorted array. The values are copied into a cache friendly layout once, lookups
+/// then touch far fewer cache lines than a binary search over the sorted array would. Every result is mapped back to
+/// the index of the value in the original sorted array.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::{Layout, StaticIndex};
+///
+/// let sorted = vec![1, 3, 3, 3, 8, 12, 40];
+/// let index = StaticIndex::new(&sorted, Layout::Eytzinger).unwrap();
+///
+/// assert_eq!(index.lower_bound(3), Some(1));
+/// assert_eq!(index.lower_bound(9), Some(5));
+/// assert_eq!(index.lower_bound(41), None);
+/// assert_eq!(index.index_of(8), Some(4));
+/// assert_eq!(index.index_of(9), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct StaticIndex<T> {
+    layout: Layout,
+    len: usize,
+    keys: Vec<T>,
+    positions: Vec<usize>,
+}
+
+impl<T> StaticIndex<T>
+where
+    T: PartialOrd + Copy,
+{
+    /// This will return a new StaticIndex over the given sorted array. An error is returned if the array is not
+    /// sorted in ascending order.
+    pub fn new(sorted: &[T], layout: Layout) -> Result<Self, String> {
+        let unsorted = sorted.windows(2).any(|pair| {
+            matches!(
+                pair[0].partial_cmp(&pair[1]),
+                None | Some(Ordering::Greater)
+            )
+        });
+
+        if unsorted {
+            return Err("a static index needs an array sorted in ascending order".to_string());
+        }
+
+        let mut index = StaticIndex {
+            layout,
+            len: sorted.len(),
+            keys: Vec::new(),
+            positions: Vec::new(),
+        };
+
+        if sorted.is_empty() {
+            return Ok(index);
+        }
+
+        match layout {
+            Layout::Eytzinger => {
+                // slot 0 is never read, the tree starts at slot 1
+                index.keys = vec![sorted[0]; sorted.len() + 1];
+                index.positions = vec![0; sorted.len() + 1];
+
+                let mut next = 0;
+                index.fill_eytzinger(sorted, 1, &mut next);
+            }
+            Layout::BTree => {
+                let blocks = sorted.len().div_ceil(BLOCK);
+
+                // padding takes the largest value, so it only ever sorts after the real values
+                index.keys = vec![sorted[sorted.len() - 1]; blocks * BLOCK];
+                index.positions = vec![PADDING; blocks * BLOCK];
+
+                let mut next = 0;
+                index.fill_btree(sorted, 0, blocks, &mut next);
+            }
+        }
+
+        Ok(index)
+    }
+
+    fn fill_eytzinger(&mut self, sorted: &[T], k: usize, next: &mut usize) {
+        if k <= self.len {
+            self.fill_eytzinger(sorted, 2 * k, next);
+
+            self.keys[k] = sorted[*next];
+            self.positions[k] = *next;
+            *next += 1;
+
+            self.fill_eytzinger(sorted, 2 * k + 1, next);
+        }
+    }
+
+    fn fill_btree(&mut self, sorted: &[T], block: usize, blocks: usize, next: &mut usize) {
+        if block >= blocks {
+            return;
+        }
+
+        for i in 0..BLOCK {
+            self.fill_btree(sorted, block * (BLOCK + 1) + i + 1, blocks, next);
+
+            if *next < sorted.len() {
+                self.keys[block * BLOCK + i] = sorted[*next];
+                self.positions[block * BLOCK + i] = *next;
+            }
+            *next += 1;
+        }
+
+        self.fill_btree(sorted, block * (BLOCK + 1) + BLOCK + 1, blocks, next);
+    }
+
+    // the slot holding the first value not less than the target
+    fn lower_bound_slot(&self, target: T) -> Option<usize> {
+        match self.layout {
+            Layout::Eytzinger => {
+                let mut k = 1;
+
+                while k <= self.len {
+                    // 16 slots ahead is four levels down the tree
+                    prefetch(&self.keys, 16 * k);
+                    k = 2 * k + (self.keys[k] < target) as usize;
+                }
+
+                // undo the right turns taken after the last left turn, that left turn was the answer
+                k >>= k.trailing_ones() + 1;
+
+                if k == 0 {
+                    None
+                } else {
+                    Some(k)
+                }
+            }
+            Layout::BTree => {
+                let blocks = self.keys.len() / BLOCK;
+                let mut block = 0;
+                let mut found = None;
+
+                while block < blocks {
+                    let start = block * BLOCK;
+                    let i = self.keys[start..start + BLOCK]
+                        .iter()
+                        .fold(0, |count, key| count + (*key < target) as usize);
+
+                    if i < BLOCK {
+                        found = Some(start + i);
+                    }
+                    block = block * (BLOCK + 1) + i + 1;
+                }
+
+                found.filter(|slot| self.positions[*slot] != PADDING)
+            }
+        }
+    }
+
+    /// Return the index, in the original sorted array, of the first value that is not less than the target. None is
+    /// returned if every value is less than the target.
+    pub fn lower_bound(&self, target: T) -> Option<usize> {
+        self.lower_bound_slot(target)
+            .map(|slot| self.positions[slot])
+    }
+
+    /// Return the index, in the original sorted array, of the first occurance of the target. None is returned if the
+    /// target was not found.
+    pub fn index_of(&self, target: T) -> Option<usize> {
+        match self.lower_bound_slot(target) {
+            Some(slot) if self.keys[slot] == target => Some(self.positions[slot]),
+            _ => None,
+        }
+    }
+
+    /// Return true if the target is in the index
+    pub fn contains(&self, target: T) -> bool {
+        self.index_of(target).is_some()
+    }
+
+    /// The layout the index was built with
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// The number of values in the index
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return true if the index holds no values
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    fn check_layout(layout: super::Layout) {
+        use super::*;
+
+        for len in 0..300 {
+            let sorted: Vec<i32> = (0..len).map(|i| (i / 3) * 2).collect();
+            let index = StaticIndex::new(&sorted, layout).unwrap();
+
+            for target in -2..len + 2 {
+                let expected = sorted.partition_point(|value| *value < target);
+                let expected = if expected < sorted.len() {
+                    Some(expected)
+                } else {
+                    None
+                };
+
+                assert_eq!(index.lower_bound(target), expected);
+                assert_eq!(
+                    index.index_of(target),
+                    expected.filter(|i| sorted[*i] == target)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn static_index_eytzinger() {
+        check_layout(super::Layout::Eytzinger);
+    }
+
+    #[test]
+    fn static_index_btree() {
+        check_layout(super::Layout::BTree);
+    }
+
+    #[test]
+    fn static_index_str() {
+        use super::*;
+
+        let words = vec!["algorithm", "cake", "denver", "rust", "zebra"];
+        let index = StaticIndex::new(&words, Layout::BTree).unwrap();
+
+        assert_eq!(index.len(), 5);
+        assert_eq!(index.index_of("rust"), Some(3));
+        assert_eq!(index.lower_bound("python"), Some(3));
+        assert!(!index.contains("c++"));
+    }
+
+    #[test]
+    fn static_index_unsorted() {
+        use super::*;
+
+        assert!(StaticIndex::new(&[3, 1, 2], Layout::Eytzinger).is_err());
+        assert!(StaticIndex::new(&[1.0, f64::NAN], Layout::BTree).is_err());
+        assert!(StaticIndex::<u8>::new(&[], Layout::BTree)
+            .unwrap()
+            .is_empty());
+    }
+}
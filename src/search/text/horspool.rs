@@ -0,0 +1,67 @@
+use super::Searcher;
+
+/// Boyer-Moore-Horspool search. The pattern is compared right to left, on a mismatch the window skips ahead by the
+/// distance of the window's last byte from the end of the pattern. Long patterns over a large alphabet skip most of
+/// the text.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::text::{Horspool, Searcher};
+///
+/// let horspool = Horspool::new("fox");
+/// assert_eq!(horspool.find_str("the quick brown fox"), Some(16));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Horspool {
+    pattern: Vec<u8>,
+    shift: Vec<usize>,
+}
+
+impl Horspool {
+    /// This will return a new Horspool searcher, the shift table is built at this time.
+    pub fn new<P>(pattern: P) -> Self
+    where
+        P: AsRef<[u8]>,
+    {
+        let pattern = pattern.as_ref().to_vec();
+        let m = pattern.len();
+        let mut shift = vec![m.max(1); 256];
+
+        // the last byte is left out, it would give a shift of 0
+        for (i, byte) in pattern.iter().enumerate().take(m.saturating_sub(1)) {
+            shift[*byte as usize] = m - 1 - i;
+        }
+
+        Horspool { pattern, shift }
+    }
+}
+
+impl Searcher for Horspool {
+    fn pattern(&self) -> &[u8] {
+        &self.pattern
+    }
+
+    fn find_at(&self, text: &[u8], start: usize) -> Option<usize> {
+        let m = self.pattern.len();
+        if m == 0 {
+            return if start <= text.len() {
+                Some(start)
+            } else {
+                None
+            };
+        }
+
+        let mut window = start;
+        while window + m <= text.len() {
+            let last = text[window + m - 1];
+
+            if last == self.pattern[m - 1] && text[window..window + m - 1] == self.pattern[..m - 1]
+            {
+                return Some(window);
+            }
+            window += self.shift[last as usize];
+        }
+
+        None
+    }
+}
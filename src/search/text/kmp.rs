@@ -0,0 +1,84 @@
+use super::Searcher;
+
+/// Knuth-Morris-Pratt search. The failure table is built from the pattern once, the text is then read left to right
+/// without ever moving backwards, in O(n + m) time.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::text::{Kmp, Searcher};
+///
+/// let kmp = Kmp::new("abab");
+///
+/// assert_eq!(kmp.failure_table(), &[0, 0, 1, 2]);
+/// assert_eq!(kmp.find_str("xxababab"), Some(2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Kmp {
+    pattern: Vec<u8>,
+    failure: Vec<usize>,
+}
+
+impl Kmp {
+    /// This will return a new Kmp searcher, the failure table is built at this time.
+    pub fn new<P>(pattern: P) -> Self
+    where
+        P: AsRef<[u8]>,
+    {
+        let pattern = pattern.as_ref().to_vec();
+        let mut failure = vec![0; pattern.len()];
+        let mut border = 0;
+
+        for i in 1..pattern.len() {
+            while border > 0 && pattern[i] != pattern[border] {
+                border = failure[border - 1];
+            }
+
+            if pattern[i] == pattern[border] {
+                border += 1;
+            }
+            failure[i] = border;
+        }
+
+        Kmp { pattern, failure }
+    }
+
+    /// The failure table, entry i is the length of the longest proper prefix of pattern[..=i] that is also a suffix
+    /// of it. On a mismatch after matching i + 1 bytes the search carries on from that many matched bytes.
+    pub fn failure_table(&self) -> &[usize] {
+        &self.failure
+    }
+}
+
+impl Searcher for Kmp {
+    fn pattern(&self) -> &[u8] {
+        &self.pattern
+    }
+
+    fn find_at(&self, text: &[u8], start: usize) -> Option<usize> {
+        let m = self.pattern.len();
+        if m == 0 {
+            return if start <= text.len() {
+                Some(start)
+            } else {
+                None
+            };
+        }
+
+        let mut matched = 0;
+        for (i, byte) in text.iter().enumerate().skip(start) {
+            while matched > 0 && *byte != self.pattern[matched] {
+                matched = self.failure[matched - 1];
+            }
+
+            if *byte == self.pattern[matched] {
+                matched += 1;
+            }
+
+            if matched == m {
+                return Some(i + 1 - m);
+            }
+        }
+
+        None
+    }
+}
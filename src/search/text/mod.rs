@@ -0,0 +1,241 @@
+//! Exact pattern search over text. Every searcher is built once from a pattern and can then be run over any number
+//! of texts, as bytes or as strings.
+//!
+//! # Examples
+//! ```
+//! use rs_algo::search::text::{Kmp, Searcher};
+//!
+//! let kmp = Kmp::new("ana");
+//!
+//! assert_eq!(kmp.find(b"bananas"), Some(1));
+//! assert_eq!(kmp.find_all(b"bananas"), vec![1]);
+//! assert_eq!(kmp.overlapping(b"bananas").collect::<Vec<_>>(), vec![1, 3]);
+//! ```
+pub use self::horspool::Horspool;
+pub use self::kmp::Kmp;
+pub use self::rabin_karp::RabinKarp;
+pub use self::two_way::TwoWay;
+
+mod horspool;
+mod kmp;
+mod rabin_karp;
+mod two_way;
+
+/// The search operations shared by every exact pattern searcher. Only `pattern` and `find_at` need to be
+/// implemented, the rest are built on top of those two.
+///
+/// The string versions return byte offsets into the string, these always fall on a char boundary so they can be used
+/// to slice the string. An empty pattern matches at every char boundary of a string and at every offset of a byte
+/// slice.
+pub trait Searcher {
+    /// The pattern this searcher was built from
+    fn pattern(&self) -> &[u8];
+
+    /// Return the offset of the first match that starts at or after `start`, or None if there is no such match.
+    fn find_at(&self, text: &[u8], start: usize) -> Option<usize>;
+
+    /// Return the offset of the first match, or None if the pattern was not found.
+    fn find(&self, text: &[u8]) -> Option<usize> {
+        self.find_at(text, 0)
+    }
+
+    /// Return the offsets of every match, matches do not overlap each other.
+    fn find_all(&self, text: &[u8]) -> Vec<usize>
+    where
+        Self: Sized,
+    {
+        let step = self.pattern().len().max(1);
+        Matches::new(self, text, None, step).collect()
+    }
+
+    /// Iterate over the offsets of every match, including matches that overlap each other.
+    fn overlapping<'a>(&'a self, text: &'a [u8]) -> Matches<'a, Self>
+    where
+        Self: Sized,
+    {
+        Matches::new(self, text, None, 1)
+    }
+
+    /// Return the byte offset of the first match in the string, or None if the pattern was not found.
+    fn find_str(&self, text: &str) -> Option<usize>
+    where
+        Self: Sized,
+    {
+        Matches::new(self, text.as_bytes(), Some(text), 1).next()
+    }
+
+    /// Return the byte offsets of every match in the string, matches do not overlap each other.
+    fn find_all_str(&self, text: &str) -> Vec<usize>
+    where
+        Self: Sized,
+    {
+        let step = self.pattern().len().max(1);
+        Matches::new(self, text.as_bytes(), Some(text), step).collect()
+    }
+
+    /// Iterate over the byte offsets of every match in the string, including matches that overlap each other.
+    fn overlapping_str<'a>(&'a self, text: &'a str) -> Matches<'a, Self>
+    where
+        Self: Sized,
+    {
+        Matches::new(self, text.as_bytes(), Some(text), 1)
+    }
+}
+
+/// An iterator over the match offsets of a Searcher, created by `overlapping` and `overlapping_str`
+#[derive(Debug)]
+pub struct Matches<'a, S> {
+    searcher: &'a S,
+    text: &'a [u8],
+    // set when searching a string, matches must then fall on a char boundary
+    string: Option<&'a str>,
+    position: usize,
+    step: usize,
+}
+
+impl<'a, S> Matches<'a, S>
+where
+    S: Searcher,
+{
+    fn new(searcher: &'a S, text: &'a [u8], string: Option<&'a str>, step: usize) -> Self {
+        Matches {
+            searcher,
+            text,
+            string,
+            position: 0,
+            step,
+        }
+    }
+}
+
+impl<S> Iterator for Matches<'_, S>
+where
+    S: Searcher,
+{
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        loop {
+            if self.position > self.text.len() {
+                return None;
+            }
+
+            let found = self.searcher.find_at(self.text, self.position)?;
+
+            match self.string {
+                // only an empty pattern can match inside a char, skip to the next offset
+                Some(string) if !string.is_char_boundary(found) => self.position = found + 1,
+                _ => {
+                    self.position = found + self.step;
+                    return Some(found);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    fn naive(pattern: &[u8], text: &[u8]) -> Vec<usize> {
+        (0..=text.len())
+            .filter(|i| text[*i..].starts_with(pattern))
+            .collect()
+    }
+
+    fn check<S: super::Searcher>(make: fn(&[u8]) -> S) {
+        let texts: Vec<&[u8]> = vec![
+            b"",
+            b"a",
+            b"aaaaaaaaaa",
+            b"abababababab",
+            b"abracadabra abracadabra",
+            b"the quick brown fox jumps over the lazy dog",
+            b"GCATCGCAGAGAGTATACAGTACG",
+            b"aabaabaaabaabaabaaab",
+        ];
+        let patterns: Vec<&[u8]> = vec![
+            b"",
+            b"a",
+            b"aa",
+            b"aaa",
+            b"ab",
+            b"aba",
+            b"abab",
+            b"abra",
+            b"cad",
+            b"dog",
+            b"the",
+            b"GCAGAGAG",
+            b"aabaab",
+            b"baabaaab",
+            b"zzz",
+            b"abracadabra abracadabra!",
+        ];
+
+        for pattern in patterns.iter() {
+            let searcher = make(pattern);
+            assert_eq!(searcher.pattern(), *pattern);
+
+            for text in texts.iter() {
+                let expected = naive(pattern, text);
+                let overlapping: Vec<usize> = searcher.overlapping(text).collect();
+
+                assert_eq!(overlapping, expected);
+                assert_eq!(searcher.find(text), expected.first().cloned());
+
+                let mut disjoint: Vec<usize> = Vec::new();
+                for i in expected.iter() {
+                    match disjoint.last() {
+                        Some(last) if *i < last + pattern.len().max(1) => {}
+                        _ => disjoint.push(*i),
+                    }
+                }
+                assert_eq!(searcher.find_all(text), disjoint);
+            }
+        }
+    }
+
+    fn check_str<S: super::Searcher>(make: fn(&[u8]) -> S) {
+        let text = "naïve café, naïve résumé";
+
+        let searcher = make("naïve".as_bytes());
+        assert_eq!(searcher.find_all_str(text), vec![0, 14]);
+        assert_eq!(&text[14..20], "naïve");
+
+        let searcher = make("é".as_bytes());
+        for i in searcher.overlapping_str(text) {
+            assert!(text[i..].starts_with('é'));
+        }
+        assert_eq!(searcher.find_str(text), Some(10));
+
+        let empty = make(b"");
+        let boundaries: Vec<usize> = text.char_indices().map(|(i, _)| i).collect();
+        let mut found: Vec<usize> = empty.overlapping_str(text).collect();
+        assert_eq!(found.pop(), Some(text.len()));
+        assert_eq!(found, boundaries);
+    }
+
+    #[test]
+    fn text_kmp() {
+        check(|pattern| super::Kmp::new(pattern));
+        check_str(|pattern| super::Kmp::new(pattern));
+    }
+
+    #[test]
+    fn text_horspool() {
+        check(|pattern| super::Horspool::new(pattern));
+        check_str(|pattern| super::Horspool::new(pattern));
+    }
+
+    #[test]
+    fn text_rabin_karp() {
+        check(|pattern| super::RabinKarp::new(pattern));
+        check_str(|pattern| super::RabinKarp::new(pattern));
+    }
+
+    #[test]
+    fn text_two_way() {
+        check(|pattern| super::TwoWay::new(pattern));
+        check_str(|pattern| super::TwoWay::new(pattern));
+    }
+}
@@ -0,0 +1,85 @@
+use super::Searcher;
+
+// a large prime, so the hash of a window fits in a u64 during the update
+const MODULUS: u64 = 1_000_000_007;
+const BASE: u64 = 256;
+
+/// Rabin-Karp search. A rolling hash of the current window is updated in O(1) as the window slides, the bytes are only
+/// compared when the hash matches the pattern's hash.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::text::{RabinKarp, Searcher};
+///
+/// let rabin_karp = RabinKarp::new("cad");
+/// assert_eq!(rabin_karp.find_str("abracadabra"), Some(4));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RabinKarp {
+    pattern: Vec<u8>,
+    hash: u64,
+    // BASE^(m - 1) % MODULUS, the weight of the byte leaving the window
+    leading: u64,
+}
+
+fn hash(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0, |hash, byte| (hash * BASE + *byte as u64) % MODULUS)
+}
+
+impl RabinKarp {
+    /// This will return a new RabinKarp searcher, the pattern's hash is computed at this time.
+    pub fn new<P>(pattern: P) -> Self
+    where
+        P: AsRef<[u8]>,
+    {
+        let pattern = pattern.as_ref().to_vec();
+        let leading = (1..pattern.len()).fold(1, |power, _| power * BASE % MODULUS);
+
+        RabinKarp {
+            hash: hash(&pattern),
+            pattern,
+            leading,
+        }
+    }
+}
+
+impl Searcher for RabinKarp {
+    fn pattern(&self) -> &[u8] {
+        &self.pattern
+    }
+
+    fn find_at(&self, text: &[u8], start: usize) -> Option<usize> {
+        let m = self.pattern.len();
+        if m == 0 {
+            return if start <= text.len() {
+                Some(start)
+            } else {
+                None
+            };
+        }
+
+        if start + m > text.len() {
+            return None;
+        }
+
+        let mut window = hash(&text[start..start + m]);
+        let mut i = start;
+
+        loop {
+            if window == self.hash && text[i..i + m] == self.pattern[..] {
+                return Some(i);
+            }
+
+            if i + m == text.len() {
+                return None;
+            }
+
+            // drop text[i] from the front and add text[i + m] to the back
+            let leaving = text[i] as u64 * self.leading % MODULUS;
+            window = ((window + MODULUS - leaving) * BASE + text[i + m] as u64) % MODULUS;
+            i += 1;
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::cmp::max;
+
+use super::Searcher;
+
+/// Two-Way search (Crochemore-Perrin). The pattern is split at a critical factorization, the right half is matched
+/// left to right and then the left half right to left. This runs in O(n + m) time with O(1) extra memory and is the
+/// algorithm behind the standard library's own `str::find`.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::text::{Searcher, TwoWay};
+///
+/// let two_way = TwoWay::new("dabra");
+/// assert_eq!(two_way.find_all_str("abracadabra abracadabra"), vec![6, 18]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TwoWay {
+    pattern: Vec<u8>,
+    critical: usize,
+    period: usize,
+    // a long period means the pattern is not periodic, no memory of the previous match is kept
+    long_period: bool,
+}
+
+// return the start and period of the maximal suffix of the bytes, either by the normal or the reversed byte order
+fn maximal_suffix(bytes: &[u8], reversed: bool) -> (usize, usize) {
+    let mut left = 0;
+    let mut right = 1;
+    let mut offset = 0;
+    let mut period = 1;
+
+    while let Some(&a) = bytes.get(right + offset) {
+        let b = bytes[left + offset];
+
+        if (a < b && !reversed) || (a > b && reversed) {
+            // the suffix is smaller, the period is the whole prefix so far
+            right += offset + 1;
+            offset = 0;
+            period = right - left;
+        } else if a == b {
+            if offset + 1 == period {
+                right += offset + 1;
+                offset = 0;
+            } else {
+                offset += 1;
+            }
+        } else {
+            // the suffix is larger, start over from here
+            left = right;
+            right += 1;
+            offset = 0;
+            period = 1;
+        }
+    }
+
+    (left, period)
+}
+
+impl TwoWay {
+    /// This will return a new TwoWay searcher, the critical factorization of the pattern is found at this time.
+    pub fn new<P>(pattern: P) -> Self
+    where
+        P: AsRef<[u8]>,
+    {
+        let pattern = pattern.as_ref().to_vec();
+
+        if pattern.is_empty() {
+            return TwoWay {
+                pattern,
+                critical: 0,
+                period: 1,
+                long_period: false,
+            };
+        }
+
+        let (critical_normal, period_normal) = maximal_suffix(&pattern, false);
+        let (critical_reversed, period_reversed) = maximal_suffix(&pattern, true);
+
+        let (critical, period) = if critical_normal > critical_reversed {
+            (critical_normal, period_normal)
+        } else {
+            (critical_reversed, period_reversed)
+        };
+
+        if pattern[..critical] == pattern[period..period + critical] {
+            TwoWay {
+                pattern,
+                critical,
+                period,
+                long_period: false,
+            }
+        } else {
+            let period = max(critical, pattern.len() - critical) + 1;
+
+            TwoWay {
+                pattern,
+                critical,
+                period,
+                long_period: true,
+            }
+        }
+    }
+}
+
+impl Searcher for TwoWay {
+    fn pattern(&self) -> &[u8] {
+        &self.pattern
+    }
+
+    fn find_at(&self, text: &[u8], start: usize) -> Option<usize> {
+        let m = self.pattern.len();
+        if m == 0 {
+            return if start <= text.len() {
+                Some(start)
+            } else {
+                None
+            };
+        }
+
+        let mut position = start;
+        // how much of the pattern's prefix is already known to match, only used for periodic patterns
+        let mut memory = 0;
+
+        'search: while position + m <= text.len() {
+            let right_start = if self.long_period {
+                self.critical
+            } else {
+                max(self.critical, memory)
+            };
+
+            for i in right_start..m {
+                if self.pattern[i] != text[position + i] {
+                    position += i - self.critical + 1;
+                    memory = 0;
+                    continue 'search;
+                }
+            }
+
+            let left_start = if self.long_period { 0 } else { memory };
+
+            for i in (left_start..self.critical).rev() {
+                if self.pattern[i] != text[position + i] {
+                    position += self.period;
+                    if !self.long_period {
+                        memory = m - self.period;
+                    }
+                    continue 'search;
+                }
+            }
+
+            return Some(position);
+        }
+
+        None
+    }
+}
@@ -0,0 +1,588 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+#[derive(Debug, Clone)]
+struct Node<V> {
+    // the bytes on the edge leading into this node
+    label: Vec<u8>,
+    // the value and weight of the key ending at this node
+    entry: Option<(V, u64)>,
+    // sorted by the first byte of their labels, no two share a first byte
+    children: Vec<Node<V>>,
+    // the highest weight of any key below this node
+    best: Option<u64>,
+}
+
+impl<V> Node<V> {
+    fn new(label: Vec<u8>) -> Self {
+        Node {
+            label,
+            entry: None,
+            children: Vec::new(),
+            best: None,
+        }
+    }
+
+    fn child(&self, byte: u8) -> Result<usize, usize> {
+        self.children
+            .binary_search_by_key(&byte, |child| child.label[0])
+    }
+
+    fn update_best(&mut self) {
+        let below = self.children.iter().filter_map(|child| child.best).max();
+        let own = self.entry.as_ref().map(|(_, weight)| *weight);
+
+        self.best = own.max(below);
+    }
+}
+
+/// A compressed radix trie. Keys are byte strings, so both `&str` and `&[u8]` can be used, and chains of nodes with a
+/// single child are merged into one edge. Every key also carries a weight, used to rank completions.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::RadixTrie;
+///
+/// let mut commands = RadixTrie::new();
+/// commands.insert_weighted("git commit", 1, 50);
+/// commands.insert_weighted("git checkout", 2, 80);
+/// commands.insert_weighted("git cherry-pick", 3, 10);
+/// commands.insert_weighted("grep", 4, 90);
+///
+/// assert_eq!(commands.get("git commit"), Some(&1));
+///
+/// let keys: Vec<Vec<u8>> = commands.iter_prefix("git ch").map(|(key, _)| key).collect();
+/// assert_eq!(keys, vec![b"git checkout".to_vec(), b"git cherry-pick".to_vec()]);
+///
+/// let best: Vec<&i32> = commands.top_k("git", 2).into_iter().map(|(_, value)| value).collect();
+/// assert_eq!(best, vec![&2, &1]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RadixTrie<V> {
+    root: Node<V>,
+    len: usize,
+}
+
+impl<V> Default for RadixTrie<V> {
+    fn default() -> Self {
+        RadixTrie::new()
+    }
+}
+
+impl<V> RadixTrie<V> {
+    /// This will return a new empty RadixTrie
+    pub fn new() -> Self {
+        RadixTrie {
+            root: Node::new(Vec::new()),
+            len: 0,
+        }
+    }
+
+    /// The number of keys in the trie
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return true if the trie holds no keys
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Insert a key with a weight of 0. If the key was already in the trie its old value is returned.
+    pub fn insert<K>(&mut self, key: K, value: V) -> Option<V>
+    where
+        K: AsRef<[u8]>,
+    {
+        self.insert_weighted(key, value, 0)
+    }
+
+    /// Insert a key with a weight, keys with a higher weight are returned first by `top_k`. If the key was already in
+    /// the trie its old value is returned and the weight is replaced.
+    pub fn insert_weighted<K>(&mut self, key: K, value: V, weight: u64) -> Option<V>
+    where
+        K: AsRef<[u8]>,
+    {
+        let old = insert(&mut self.root, key.as_ref(), value, weight);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// Remove a key, returning its value. None is returned if the key was not in the trie.
+    pub fn remove<K>(&mut self, key: K) -> Option<V>
+    where
+        K: AsRef<[u8]>,
+    {
+        let old = remove(&mut self.root, key.as_ref());
+        if old.is_some() {
+            self.len -= 1;
+        }
+        old
+    }
+
+    // the node the key ends at, along with how many bytes of that node's label the key still needs
+    fn find(&self, key: &[u8]) -> Option<(&Node<V>, usize)> {
+        let mut node = &self.root;
+        let mut key = key;
+
+        while !key.is_empty() {
+            let child = &node.children[node.child(key[0]).ok()?];
+            let common = common_prefix(&child.label, key);
+
+            if common == key.len() {
+                return Some((child, child.label.len() - common));
+            }
+            if common < child.label.len() {
+                return None;
+            }
+
+            node = child;
+            key = &key[common..];
+        }
+
+        Some((node, 0))
+    }
+
+    /// Return the value of the key, or None if the key is not in the trie.
+    pub fn get<K>(&self, key: K) -> Option<&V>
+    where
+        K: AsRef<[u8]>,
+    {
+        match self.find(key.as_ref()) {
+            Some((node, 0)) => node.entry.as_ref().map(|(value, _)| value),
+            _ => None,
+        }
+    }
+
+    /// Return the weight of the key, or None if the key is not in the trie.
+    pub fn weight<K>(&self, key: K) -> Option<u64>
+    where
+        K: AsRef<[u8]>,
+    {
+        match self.find(key.as_ref()) {
+            Some((node, 0)) => node.entry.as_ref().map(|(_, weight)| *weight),
+            _ => None,
+        }
+    }
+
+    /// Return true if the key is in the trie
+    pub fn contains_key<K>(&self, key: K) -> bool
+    where
+        K: AsRef<[u8]>,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Return the longest key in the trie that is a prefix of the given key, along with its value. This is the
+    /// lookup a routing table does. None is returned if no key is a prefix.
+    ///
+    /// # Examples
+    /// ```
+    /// use rs_algo::search::RadixTrie;
+    ///
+    /// let mut routes = RadixTrie::new();
+    /// routes.insert("/api", "api");
+    /// routes.insert("/api/users", "users");
+    ///
+    /// assert_eq!(routes.longest_prefix("/api/users/42"), Some((b"/api/users".to_vec(), &"users")));
+    /// assert_eq!(routes.longest_prefix("/apix"), Some((b"/api".to_vec(), &"api")));
+    /// assert_eq!(routes.longest_prefix("/static"), None);
+    /// ```
+    pub fn longest_prefix<K>(&self, key: K) -> Option<(Vec<u8>, &V)>
+    where
+        K: AsRef<[u8]>,
+    {
+        let key = key.as_ref();
+        let mut node = &self.root;
+        let mut depth = 0;
+        let mut found = node.entry.as_ref().map(|(value, _)| (0, value));
+
+        while depth < key.len() {
+            let child = match node.child(key[depth]) {
+                Ok(i) => &node.children[i],
+                Err(_) => break,
+            };
+            if !key[depth..].starts_with(&child.label) {
+                break;
+            }
+
+            node = child;
+            depth += child.label.len();
+            if let Some((value, _)) = node.entry.as_ref() {
+                found = Some((depth, value));
+            }
+        }
+
+        found.map(|(len, value)| (key[..len].to_vec(), value))
+    }
+
+    /// Iterate over every key that starts with the prefix, along with its value, in sorted order.
+    pub fn iter_prefix<K>(&self, prefix: K) -> PrefixIter<'_, V>
+    where
+        K: AsRef<[u8]>,
+    {
+        let prefix = prefix.as_ref();
+        let mut stack = Vec::new();
+
+        if let Some((node, missing)) = self.find(prefix) {
+            let mut key = prefix.to_vec();
+            key.extend_from_slice(&node.label[node.label.len() - missing..]);
+            stack.push((node, key));
+        }
+
+        PrefixIter { stack }
+    }
+
+    /// Iterate over every key in the trie, along with its value, in sorted order.
+    pub fn iter(&self) -> PrefixIter<'_, V> {
+        self.iter_prefix([])
+    }
+
+    /// Return the k keys with the highest weight that start with the prefix, along with their values. Keys with the
+    /// same weight come in sorted order. Only the parts of the trie that can still hold one of the k keys are visited.
+    pub fn top_k<K>(&self, prefix: K, k: usize) -> Vec<(Vec<u8>, &V)>
+    where
+        K: AsRef<[u8]>,
+    {
+        let prefix = prefix.as_ref();
+        let mut found = Vec::new();
+        let mut heap = BinaryHeap::new();
+
+        if let Some((node, missing)) = self.find(prefix) {
+            if let Some(best) = node.best {
+                let mut key = prefix.to_vec();
+                key.extend_from_slice(&node.label[node.label.len() - missing..]);
+                heap.push(Candidate {
+                    weight: best,
+                    key: Reverse(key),
+                    item: Item::Node(node),
+                });
+            }
+        }
+
+        while found.len() < k {
+            let Candidate { key, item, .. } = match heap.pop() {
+                Some(candidate) => candidate,
+                None => break,
+            };
+
+            match item {
+                Item::Entry(value) => found.push((key.0, value)),
+                Item::Node(node) => {
+                    if let Some((value, weight)) = node.entry.as_ref() {
+                        heap.push(Candidate {
+                            weight: *weight,
+                            key: key.clone(),
+                            item: Item::Entry(value),
+                        });
+                    }
+
+                    for child in node.children.iter() {
+                        if let Some(best) = child.best {
+                            let mut child_key = key.0.clone();
+                            child_key.extend_from_slice(&child.label);
+                            heap.push(Candidate {
+                                weight: best,
+                                key: Reverse(child_key),
+                                item: Item::Node(child),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        found
+    }
+
+    // a depth first walk in sorted key order. visit is given the state of the parent, the label leading into the
+    // node, the full key and the node's value, and returns the node's state or None to skip everything below it
+    pub(crate) fn walk<'a, S, F>(&'a self, start: &S, visit: &mut F)
+    where
+        F: FnMut(&S, &[u8], &[u8], Option<&'a V>) -> Option<S>,
+    {
+        walk(&self.root, start, &mut Vec::new(), visit);
+    }
+}
+
+fn walk<'a, V, S, F>(node: &'a Node<V>, parent: &S, key: &mut Vec<u8>, visit: &mut F)
+where
+    F: FnMut(&S, &[u8], &[u8], Option<&'a V>) -> Option<S>,
+{
+    key.extend_from_slice(&node.label);
+
+    let value = node.entry.as_ref().map(|(value, _)| value);
+    if let Some(state) = visit(parent, &node.label, key, value) {
+        for child in node.children.iter() {
+            walk(child, &state, key, visit);
+        }
+    }
+
+    key.truncate(key.len() - node.label.len());
+}
+
+fn common_prefix(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b.iter()).take_while(|(x, y)| x == y).count()
+}
+
+fn insert<V>(node: &mut Node<V>, key: &[u8], value: V, weight: u64) -> Option<V> {
+    if key.is_empty() {
+        let old = node.entry.replace((value, weight));
+        node.update_best();
+        return old.map(|(value, _)| value);
+    }
+
+    let old = match node.child(key[0]) {
+        Err(at) => {
+            let mut leaf = Node::new(key.to_vec());
+            leaf.entry = Some((value, weight));
+            leaf.best = Some(weight);
+            node.children.insert(at, leaf);
+            None
+        }
+        Ok(i) => {
+            let child = &mut node.children[i];
+            let common = common_prefix(&child.label, key);
+
+            if common < child.label.len() {
+                // split the edge where the key leaves it
+                let rest = child.label.split_off(common);
+                let mut lower = Node::new(rest);
+                lower.entry = child.entry.take();
+                lower.children = std::mem::take(&mut child.children);
+                lower.best = child.best;
+                child.children.push(lower);
+            }
+
+            insert(child, &key[common..], value, weight)
+        }
+    };
+
+    node.update_best();
+    old
+}
+
+fn remove<V>(node: &mut Node<V>, key: &[u8]) -> Option<V> {
+    if key.is_empty() {
+        let (value, _) = node.entry.take()?;
+        node.update_best();
+        return Some(value);
+    }
+
+    let i = node.child(key[0]).ok()?;
+    let child = &mut node.children[i];
+    if !key.starts_with(&child.label) {
+        return None;
+    }
+
+    let value = remove(child, &key[child.label.len()..])?;
+
+    if child.entry.is_none() {
+        match child.children.len() {
+            0 => {
+                node.children.remove(i);
+            }
+            1 => {
+                // a node without a value and with one child is merged with that child
+                let lower = child.children.pop().unwrap();
+                child.label.extend_from_slice(&lower.label);
+                child.entry = lower.entry;
+                child.children = lower.children;
+                child.best = lower.best;
+            }
+            _ => {}
+        }
+    }
+
+    node.update_best();
+    Some(value)
+}
+
+enum Item<'a, V> {
+    Node(&'a Node<V>),
+    Entry(&'a V),
+}
+
+// an entry in the top_k heap, ordered by weight and then by key with the smaller key first
+struct Candidate<'a, V> {
+    weight: u64,
+    key: Reverse<Vec<u8>>,
+    item: Item<'a, V>,
+}
+
+impl<V> PartialEq for Candidate<'_, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<V> Eq for Candidate<'_, V> {}
+
+impl<V> PartialOrd for Candidate<'_, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<V> Ord for Candidate<'_, V> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // a node comes before the entries below it that share its weight
+        let rank = |item: &Item<V>| match item {
+            Item::Node(_) => 1,
+            Item::Entry(_) => 0,
+        };
+
+        self.weight
+            .cmp(&other.weight)
+            .then_with(|| self.key.cmp(&other.key))
+            .then_with(|| rank(&self.item).cmp(&rank(&other.item)))
+    }
+}
+
+/// An iterator over the keys and values of a RadixTrie in sorted order, created by `iter_prefix` and `iter`
+pub struct PrefixIter<'a, V> {
+    // nodes still to visit, with the full key that ends at each one
+    stack: Vec<(&'a Node<V>, Vec<u8>)>,
+}
+
+impl<'a, V> Iterator for PrefixIter<'a, V> {
+    type Item = (Vec<u8>, &'a V);
+
+    fn next(&mut self) -> Option<(Vec<u8>, &'a V)> {
+        while let Some((node, key)) = self.stack.pop() {
+            for child in node.children.iter().rev() {
+                let mut child_key = key.clone();
+                child_key.extend_from_slice(&child.label);
+                self.stack.push((child, child_key));
+            }
+
+            if let Some((value, _)) = node.entry.as_ref() {
+                return Some((key, value));
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn trie_insert_get_remove() {
+        use super::*;
+
+        let words = [
+            "romane",
+            "romanus",
+            "romulus",
+            "rubens",
+            "ruber",
+            "rubicon",
+            "rubicundus",
+            "rom",
+            "",
+        ];
+        let mut trie = RadixTrie::new();
+        let mut map = BTreeMap::new();
+
+        for (i, word) in words.iter().enumerate() {
+            assert_eq!(trie.insert(word, i), None);
+            map.insert(word.as_bytes().to_vec(), i);
+        }
+        assert_eq!(trie.insert("rom", 100), Some(7));
+        map.insert(b"rom".to_vec(), 100);
+
+        assert_eq!(trie.len(), map.len());
+        assert_eq!(trie.get("ro"), None);
+        assert_eq!(trie.get("romanes"), None);
+        assert_eq!(trie.get(""), Some(&8));
+
+        let all: Vec<(Vec<u8>, usize)> = trie.iter().map(|(key, value)| (key, *value)).collect();
+        let expected: Vec<(Vec<u8>, usize)> = map.clone().into_iter().collect();
+        assert_eq!(all, expected);
+
+        for word in ["rubens", "rom", "romane", "nope", "rub"].iter() {
+            assert_eq!(trie.remove(word), map.remove(word.as_bytes()));
+            let all: Vec<(Vec<u8>, usize)> =
+                trie.iter().map(|(key, value)| (key, *value)).collect();
+            let expected: Vec<(Vec<u8>, usize)> = map.clone().into_iter().collect();
+            assert_eq!(all, expected);
+        }
+
+        assert_eq!(trie.len(), map.len());
+        assert!(trie.contains_key("romanus"));
+    }
+
+    #[test]
+    fn trie_prefix_iteration() {
+        use super::*;
+
+        let mut trie = RadixTrie::new();
+        for word in ["tea", "ted", "ten", "to", "inn", "in", "i", "team"].iter() {
+            trie.insert(word, ());
+        }
+
+        let keys = |prefix: &str| -> Vec<String> {
+            trie.iter_prefix(prefix)
+                .map(|(key, _)| String::from_utf8(key).unwrap())
+                .collect()
+        };
+
+        assert_eq!(keys("te"), vec!["tea", "team", "ted", "ten"]);
+        assert_eq!(keys("tea"), vec!["tea", "team"]);
+        assert_eq!(keys("i"), vec!["i", "in", "inn"]);
+        assert_eq!(keys("x"), Vec::<String>::new());
+        assert_eq!(keys("").len(), 8);
+    }
+
+    #[test]
+    fn trie_longest_prefix() {
+        use super::*;
+
+        let mut routes = RadixTrie::new();
+        routes.insert(&[10u8, 0][..], "ten");
+        routes.insert(&[10u8, 0, 1][..], "ten-one");
+        routes.insert(&[192u8, 168][..], "local");
+
+        assert_eq!(
+            routes.longest_prefix([10u8, 0, 1, 7]),
+            Some((vec![10, 0, 1], &"ten-one"))
+        );
+        assert_eq!(
+            routes.longest_prefix([10u8, 0, 2]),
+            Some((vec![10, 0], &"ten"))
+        );
+        assert_eq!(routes.longest_prefix([10u8]), None);
+    }
+
+    #[test]
+    fn trie_top_k() {
+        use super::*;
+
+        let mut trie = RadixTrie::new();
+        trie.insert_weighted("apple", 'a', 5);
+        trie.insert_weighted("apply", 'b', 9);
+        trie.insert_weighted("apricot", 'c', 7);
+        trie.insert_weighted("app", 'd', 7);
+        trie.insert_weighted("banana", 'e', 100);
+
+        let top = |trie: &RadixTrie<char>, prefix: &str, k: usize| -> Vec<char> {
+            trie.top_k(prefix, k)
+                .into_iter()
+                .map(|(_, value)| *value)
+                .collect()
+        };
+
+        assert_eq!(top(&trie, "ap", 10), vec!['b', 'd', 'c', 'a']);
+        assert_eq!(top(&trie, "", 2), vec!['e', 'b']);
+        assert_eq!(top(&trie, "appl", 1), vec!['b']);
+        assert_eq!(top(&trie, "zzz", 3), Vec::<char>::new());
+
+        trie.remove("apply");
+        assert_eq!(top(&trie, "ap", 2), vec!['d', 'c']);
+        assert_eq!(trie.weight("apricot"), Some(7));
+    }
+}
@@ -0,0 +1,256 @@
+/// Whether a unimodal search should look for the lowest or the highest value of the function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    Minimize,
+    Maximize,
+}
+
+/// The result of a unimodal search
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Extremum<X, Y> {
+    /// the argument where the extremum was found
+    pub arg: X,
+    /// the value of the function at `arg`
+    pub value: Y,
+    /// how many times the function was called during the search
+    pub evaluations: usize,
+}
+
+fn better<Y>(goal: Goal, a: &Y, b: &Y) -> bool
+where
+    Y: PartialOrd,
+{
+    match goal {
+        Goal::Minimize => a < b,
+        Goal::Maximize => a > b,
+    }
+}
+
+/// Ternary search over the integers low..=high. The function needs to be unimodal over the range, strictly
+/// decreasing then strictly increasing when minimizing (the other way around when maximizing).
+///
+/// The range is narrowed by thirds until it spans no more than `tolerance` values, the remaining values are then
+/// checked one by one. A tolerance of 0 narrows the range all the way down. An error is returned if low is greater
+/// than high.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::unimodal::{self, Goal};
+///
+/// let min = unimodal::ternary(-100, 100, 0, Goal::Minimize, |x| (x - 17) * (x - 17)).unwrap();
+/// assert_eq!(min.arg, 17);
+/// assert_eq!(min.value, 0);
+/// assert!(min.evaluations < 201);
+/// ```
+pub fn ternary<F, Y>(
+    low: i64,
+    high: i64,
+    tolerance: u64,
+    goal: Goal,
+    mut f: F,
+) -> Result<Extremum<i64, Y>, String>
+where
+    F: FnMut(i64) -> Y,
+    Y: PartialOrd,
+{
+    if low > high {
+        return Err("low cannot be greater than high for a ternary search".to_string());
+    }
+
+    let mut low = low;
+    let mut high = high;
+    let mut evaluations = 0;
+
+    while high.abs_diff(low) > tolerance {
+        let third = (high.abs_diff(low) / 3) as i64;
+        let left = low + third;
+        let right = high - third;
+
+        let left_value = f(left);
+        let right_value = f(right);
+        evaluations += 2;
+
+        if better(goal, &left_value, &right_value) {
+            high = right - 1;
+        } else {
+            low = left + 1;
+        }
+    }
+
+    let mut best = Extremum {
+        arg: low,
+        value: f(low),
+        evaluations: 0,
+    };
+    evaluations += 1;
+
+    for x in (low..=high).skip(1) {
+        let value = f(x);
+        evaluations += 1;
+
+        if better(goal, &value, &best.value) {
+            best.arg = x;
+            best.value = value;
+        }
+    }
+
+    best.evaluations = evaluations;
+    Ok(best)
+}
+
+/// Golden-section search over the interval [low, high]. The function needs to be unimodal over the interval,
+/// derivatives are never needed.
+///
+/// Each step shrinks the interval by the golden ratio while re-using one of the previous evaluations, so only one
+/// new evaluation is made per step. The search stops once the interval is no wider than `tolerance`, the middle of
+/// that interval is returned. An error is returned if the interval or the tolerance is not valid.
+///
+/// # Examples
+/// ```
+/// use rs_algo::search::unimodal::{self, Goal};
+///
+/// let max = unimodal::golden_section(0.0, 3.0, 1e-9, Goal::Maximize, |x: f64| x.sin()).unwrap();
+/// assert!((max.arg - std::f64::consts::FRAC_PI_2).abs() < 1e-6);
+/// assert!((max.value - 1.0).abs() < 1e-9);
+/// ```
+pub fn golden_section<F>(
+    low: f64,
+    high: f64,
+    tolerance: f64,
+    goal: Goal,
+    mut f: F,
+) -> Result<Extremum<f64, f64>, String>
+where
+    F: FnMut(f64) -> f64,
+{
+    if !low.is_finite() || !high.is_finite() || low > high {
+        return Err("low and high need to be finite with low not greater than high".to_string());
+    }
+
+    if tolerance.is_nan() || tolerance <= 0.0 {
+        return Err("tolerance needs to be greater than 0".to_string());
+    }
+
+    // 1 / phi, the fraction of the interval kept on each step
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+
+    let mut a = low;
+    let mut b = high;
+    let mut c = b - ratio * (b - a);
+    let mut d = a + ratio * (b - a);
+    let mut fc = f(c);
+    let mut fd = f(d);
+    let mut evaluations = 2;
+
+    while b - a > tolerance {
+        let width = b - a;
+
+        if better(goal, &fc, &fd) {
+            b = d;
+            d = c;
+            fd = fc;
+            c = b - ratio * (b - a);
+            fc = f(c);
+        } else {
+            a = c;
+            c = d;
+            fc = fd;
+            d = a + ratio * (b - a);
+            fd = f(d);
+        }
+        evaluations += 1;
+
+        // the interval can stop shrinking once it is as narrow as floating point allows
+        if b - a >= width {
+            break;
+        }
+    }
+
+    let arg = a + (b - a) / 2.0;
+    let value = f(arg);
+    evaluations += 1;
+
+    Ok(Extremum {
+        arg,
+        value,
+        evaluations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn ternary_minimize() {
+        use super::*;
+
+        let min = ternary(-1000, 1000, 0, Goal::Minimize, |x| (x + 42) * (x + 42) + 3).unwrap();
+        assert_eq!(min.arg, -42);
+        assert_eq!(min.value, 3);
+    }
+
+    #[test]
+    fn ternary_maximize_tolerance() {
+        use super::*;
+
+        let mut calls = 0;
+        let max = ternary(0, 10_000, 8, Goal::Maximize, |x| {
+            calls += 1;
+            -(x - 7777).abs()
+        })
+        .unwrap();
+
+        assert_eq!(max.arg, 7777);
+        assert_eq!(max.value, 0);
+        assert_eq!(max.evaluations, calls);
+        assert!(max.evaluations < 100);
+    }
+
+    #[test]
+    fn ternary_edges() {
+        use super::*;
+
+        let single = ternary(5, 5, 0, Goal::Minimize, |x| x).unwrap();
+        assert_eq!((single.arg, single.evaluations), (5, 1));
+
+        let left = ternary(0, 50, 0, Goal::Minimize, |x| x).unwrap();
+        assert_eq!(left.arg, 0);
+
+        let right = ternary(0, 50, 3, Goal::Maximize, |x| x).unwrap();
+        assert_eq!(right.arg, 50);
+
+        assert!(ternary(1, 0, 0, Goal::Minimize, |x| x).is_err());
+
+        let top = ternary(i64::MAX - 4, i64::MAX, 10, Goal::Maximize, |x| x).unwrap();
+        assert_eq!((top.arg, top.evaluations), (i64::MAX, 5));
+
+        let wide = ternary(i64::MIN, i64::MAX, 0, Goal::Maximize, |x| x).unwrap();
+        assert_eq!(wide.arg, i64::MAX);
+    }
+
+    #[test]
+    fn golden_section_minimize() {
+        use super::*;
+
+        let min = golden_section(-10.0, 10.0, 1e-8, Goal::Minimize, |x| (x - 1.5).powi(2)).unwrap();
+        assert!((min.arg - 1.5).abs() < 1e-6);
+        assert!(min.value < 1e-12);
+        assert!(min.evaluations < 60);
+    }
+
+    #[test]
+    fn golden_section_invalid() {
+        use super::*;
+
+        assert!(golden_section(1.0, 0.0, 1e-3, Goal::Minimize, |x| x).is_err());
+        assert!(golden_section(0.0, 1.0, 0.0, Goal::Minimize, |x| x).is_err());
+        assert!(golden_section(0.0, f64::INFINITY, 1e-3, Goal::Minimize, |x| x).is_err());
+    }
+
+    #[test]
+    fn golden_section_tiny_tolerance() {
+        use super::*;
+
+        let max = golden_section(0.0, 2.0, 1e-300, Goal::Maximize, |x| -(x - 1.0).abs()).unwrap();
+        assert!((max.arg - 1.0).abs() < 1e-12);
+    }
+}
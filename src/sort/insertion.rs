@@ -10,11 +10,11 @@
 /// assert_eq!(sorted, vec![-8, 2, 2, 3, 8, 34]);
 /// assert_eq!(a, vec![3, 2, -8, 34, 2, 8]);
 /// ```
-pub fn sort<T>(array: &Vec<T>) -> Vec<T>
+pub fn sort<T>(array: &[T]) -> Vec<T>
 where
     T: PartialOrd + Copy,
 {
-    let mut sorted = array.clone();
+    let mut sorted = array.to_vec();
     sort_mut(&mut sorted);
     sorted
 }
@@ -30,7 +30,7 @@ where
 ///
 /// assert_eq!(a, vec![-8, 2, 2, 3, 8, 34]);
 /// ```
-pub fn sort_mut<T>(array: &mut Vec<T>)
+pub fn sort_mut<T>(array: &mut [T])
 where
     T: PartialOrd + Copy,
 {
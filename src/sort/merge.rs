@@ -1,4 +1,8 @@
-fn merge<T>(left: &Vec<T>, right: &Vec<T>, array: &mut Vec<T>)
+use std::cmp::Ordering;
+
+use crate::search::binary;
+
+fn merge<T>(left: &[T], right: &[T], array: &mut [T])
 where
     T: PartialOrd + Copy,
 {
@@ -30,7 +34,7 @@ where
     }
 }
 
-fn divide_list<T>(array: &mut Vec<T>)
+fn divide_list<T>(array: &mut [T])
 where
     T: PartialOrd + Copy,
 {
@@ -41,12 +45,8 @@ where
         let mut left: Vec<T> = Vec::with_capacity(mid);
         let mut right: Vec<T> = Vec::with_capacity(len - mid);
 
-        for i in 0..mid {
-            left.push(array[i]);
-        }
-        for i in mid..len {
-            right.push(array[i]);
-        }
+        left.extend_from_slice(&array[..mid]);
+        right.extend_from_slice(&array[mid..]);
 
         divide_list(&mut left);
         divide_list(&mut right);
@@ -67,11 +67,11 @@ where
 /// assert_eq!(sorted, vec![-8, 2, 2, 3, 8, 34]);
 /// assert_eq!(a, vec![3, 2, -8, 34, 2, 8]);
 /// ```
-pub fn sort<T>(a: &Vec<T>) -> Vec<T>
+pub fn sort<T>(a: &[T]) -> Vec<T>
 where
     T: PartialOrd + Copy,
 {
-    let mut sorted = a.clone();
+    let mut sorted = a.to_vec();
     sort_mut(&mut sorted);
     sorted
 }
@@ -87,7 +87,7 @@ where
 ///
 /// assert_eq!(a, vec![-8, 2, 2, 3, 8, 34]);
 /// ```
-pub fn sort_mut<T>(a: &mut Vec<T>)
+pub fn sort_mut<T>(a: &mut [T])
 where
     T: PartialOrd + Copy,
 {
@@ -99,6 +99,91 @@ where
     divide_list(a);
 }
 
+// slices this short are sorted by binary insertion instead of being split further
+const INSERTION_LEN: usize = 16;
+
+// merge the sorted runs a[..mid] and a[mid..] without a buffer, by rotating the part of the left run that belongs
+// after part of the right run and then merging the two smaller pairs of runs that leaves
+fn merge_in_place<T, F>(a: &mut [T], mid: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = a.len();
+    if mid == 0 || mid == len {
+        return;
+    }
+    if len == 2 {
+        if compare(&a[1], &a[0]) == Ordering::Less {
+            a.swap(0, 1);
+        }
+        return;
+    }
+
+    let (left_cut, right_cut) = if mid >= len - mid {
+        let left_cut = mid / 2;
+        let pivot = &a[left_cut];
+        let right_cut = mid
+            + binary::partition_point(&a[mid..], |value| compare(value, pivot) == Ordering::Less);
+        (left_cut, right_cut)
+    } else {
+        let right_cut = mid + (len - mid) / 2;
+        let pivot = &a[right_cut];
+        let left_cut =
+            binary::partition_point(&a[..mid], |value| compare(pivot, value) != Ordering::Less);
+        (left_cut, right_cut)
+    };
+
+    a[left_cut..right_cut].rotate_left(mid - left_cut);
+    let new_mid = left_cut + (right_cut - mid);
+
+    merge_in_place(&mut a[..new_mid], left_cut, compare);
+    merge_in_place(&mut a[new_mid..], right_cut - new_mid, compare);
+}
+
+fn sort_in_place<T, F>(a: &mut [T], compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if a.len() <= INSERTION_LEN {
+        for i in 1..a.len() {
+            let (sorted, rest) = a.split_at(i);
+            let at =
+                binary::partition_point(sorted, |value| compare(&rest[0], value) != Ordering::Less);
+            a[at..=i].rotate_right(1);
+        }
+        return;
+    }
+
+    let mid = a.len() / 2;
+    sort_in_place(&mut a[..mid], compare);
+    sort_in_place(&mut a[mid..], compare);
+
+    // runs that are already in order, as they are for sorted input, need no merge
+    if compare(&a[mid], &a[mid - 1]) == Ordering::Less {
+        merge_in_place(a, mid, compare);
+    }
+}
+
+/// Sort the given slice with merge sort, ordering values with the compare function. The sort is stable, equal values
+/// keep their order, and values are only moved, never copied or cloned, so any type can be sorted. The runs are
+/// merged in place, so no extra memory is used, at the cost of O(n log² n) time.
+///
+/// # Examples
+/// ```
+/// use rs_algo::sort::merge;
+///
+/// let mut people = vec![("sam", 31), ("ana", 25), ("lee", 31), ("kim", 25)];
+/// merge::sort_by_mut(&mut people, |a, b| a.1.cmp(&b.1));
+///
+/// assert_eq!(people, vec![("ana", 25), ("kim", 25), ("sam", 31), ("lee", 31)]);
+/// ```
+pub fn sort_by_mut<T, F>(a: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sort_in_place(a, &mut compare);
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -154,4 +239,30 @@ mod tests {
             vec!["April", "Batman", "December", "July", "June", "May"]
         );
     }
+
+    #[test]
+    fn merge_sort_by_mut() {
+        use super::*;
+        use crate::test_util::random;
+
+        let mut state = 17u64;
+        for len in [0, 1, 2, 15, 16, 17, 100, 1000].iter() {
+            let mut a: Vec<(u64, usize)> = (0..*len).map(|i| (random(&mut state, 20), i)).collect();
+            let mut expected = a.clone();
+            expected.sort_by_key(|value| value.0);
+
+            // the second field records the original order, so this also checks the sort is stable
+            sort_by_mut(&mut a, |x, y| x.0.cmp(&y.0));
+            assert_eq!(a, expected);
+
+            sort_by_mut(&mut a, |x, y| y.cmp(x));
+            expected.reverse();
+            assert_eq!(a, expected);
+        }
+
+        let mut words: Vec<String> =
+            vec!["pear".to_string(), "fig".to_string(), "apple".to_string()];
+        sort_by_mut(&mut words, |a, b| a.len().cmp(&b.len()));
+        assert_eq!(words, vec!["fig", "pear", "apple"]);
+    }
 }
@@ -1,13 +1,11 @@
-fn exchange<T>(array: &mut Vec<T>, j: usize, k: usize)
+fn exchange<T>(array: &mut [T], j: usize, k: usize)
 where
     T: PartialOrd + Copy,
 {
-    let temp = array[j];
-    array[j] = array[k];
-    array[k] = temp;
+    array.swap(j, k);
 }
 
-fn partition<T>(array: &mut Vec<T>, start_index: usize, end_index: usize) -> usize
+fn partition<T>(array: &mut [T], start_index: usize, end_index: usize) -> usize
 where
     T: PartialOrd + Copy,
 {
@@ -25,7 +23,7 @@ where
     j
 }
 
-fn quick_sort<T>(array: &mut Vec<T>, start: usize, end: usize)
+fn quick_sort<T>(array: &mut [T], start: usize, end: usize)
 where
     T: PartialOrd + Copy,
 {
@@ -51,11 +49,11 @@ where
 /// assert_eq!(sorted, vec![-8, 2, 2, 3, 8, 34]);
 /// assert_eq!(a, vec![3, 2, -8, 34, 2, 8]);
 /// ```
-pub fn sort<T>(array: &Vec<T>) -> Vec<T>
+pub fn sort<T>(array: &[T]) -> Vec<T>
 where
     T: PartialOrd + Copy,
 {
-    let mut sorted = array.clone();
+    let mut sorted = array.to_vec();
     sort_mut(&mut sorted);
     sorted
 }
@@ -71,7 +69,7 @@ where
 ///
 /// assert_eq!(a, vec![-8, 2, 2, 3, 8, 34]);
 /// ```
-pub fn sort_mut<T>(array: &mut Vec<T>)
+pub fn sort_mut<T>(array: &mut [T])
 where
     T: PartialOrd + Copy,
 {
@@ -0,0 +1,14 @@
+// a small xorshift generator so the randomised tests are the same on every run, the state must not start at zero
+pub(crate) fn random(state: &mut u64, n: u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state % n
+}
+
+// a sequence shorter than max_len of values below alphabet
+pub(crate) fn random_vec(state: &mut u64, max_len: u64, alphabet: u64) -> Vec<u64> {
+    (0..random(state, max_len))
+        .map(|_| random(state, alphabet))
+        .collect()
+}